@@ -0,0 +1,30 @@
+"""Serving agent tasks from FastAPI without blocking the event loop.
+
+Run:
+    pip install fastapi uvicorn
+    export MODEL_API_KEY=...
+    uvicorn examples.async_fastapi:app
+"""
+import openrustswarm_core as ors
+from fastapi import FastAPI, HTTPException
+
+graph = ors.AgentGraphPy()
+# Register personas up front: the graph is shared with in-flight tasks.
+graph.register_agent(ors.Agent("Researcher", "Answer questions using the web tools."))
+
+app = FastAPI()
+
+
+@app.get("/ask")
+async def ask(task_id: str, question: str):
+    buffer = ors.HistoryBuffer()
+    buffer.add(ors.TrajectoryPoint(1, "Task", question))
+    try:
+        ctx = await graph.run_task_async(task_id, buffer, agent_name="Researcher")
+    except RuntimeError as e:
+        raise HTTPException(status_code=502, detail=str(e))
+    return {
+        "task_id": ctx.task_id,
+        "answer": ctx.final_answer,
+        "steps": buffer.len(),
+    }
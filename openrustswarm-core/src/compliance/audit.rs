@@ -2,10 +2,12 @@
 //!
 //! Provides immutable, append-only audit trail for all agent actions.
 
+use super::ids::unique_id;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Write;
 use tracing::warn; // Add tracing::warn for logging
 
 /// Single audit event
@@ -51,12 +53,7 @@ impl AuditLogger {
     }
 
     fn generate_id() -> String {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        format!("audit-{}", nanos)
+        unique_id("audit")
     }
 
     fn now() -> String {
@@ -118,6 +115,33 @@ impl AuditLogger {
         serde_json::to_string_pretty(&*events).unwrap_or_default()
     }
 
+    /// Write all events as CSV (`id,timestamp,agent_id,action,outcome,reason`)
+    /// one row at a time, without building the whole document in memory
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "id,timestamp,agent_id,action,outcome,reason")?;
+        let events = self.events.read();
+        for event in events.iter() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                csv_field(&event.id),
+                csv_field(&event.timestamp),
+                csv_field(&event.agent_id),
+                csv_field(&event.action),
+                csv_field(&event.outcome),
+                csv_field(event.reason.as_deref().unwrap_or("")),
+            )?;
+        }
+        writer.flush()
+    }
+
+    pub fn export_csv(&self) -> String {
+        let mut buf = Vec::new();
+        // Writing into a Vec cannot fail
+        let _ = self.write_csv(&mut buf);
+        String::from_utf8(buf).unwrap_or_default()
+    }
+
     pub fn export_user_logs(&self, user_id: &str) -> String {
         let events = self.events.read();
         let index = self.user_index.read();
@@ -136,9 +160,59 @@ impl AuditLogger {
         // Note: Actual events not deleted for audit immutability, but index removed
     }
 
+    /// Re-key a user's events under `pseudonym`, passing each stored reason
+    /// through `redact`. Events are kept, so counts stay intact. Returns how
+    /// many events were rewritten.
+    pub fn anonymize_user_logs(
+        &self,
+        user_id: &str,
+        pseudonym: &str,
+        redact: impl Fn(&str) -> String,
+    ) -> usize {
+        // Same lock order as log_approval/log_denial: events, then index
+        let mut events = self.events.write();
+        let mut index = self.user_index.write();
+        let Some(indices) = index.remove(user_id) else {
+            return 0;
+        };
+
+        for &i in &indices {
+            if let Some(event) = events.get_mut(i) {
+                event.agent_id = pseudonym.to_string();
+                event.action = redact(&event.action);
+                event.reason = event.reason.as_deref().map(&redact);
+            }
+        }
+        let count = indices.len();
+        index
+            .entry(pseudonym.to_string())
+            .or_default()
+            .extend(indices);
+        count
+    }
+
     pub fn count(&self) -> usize {
         self.events.read().len()
     }
+
+    /// Drop every audit event and the user index.
+    ///
+    /// This breaks the append-only guarantee, so callers must gate it.
+    pub fn clear(&self) {
+        let mut events = self.events.write();
+        warn!("[Audit] Clearing {} audit events", events.len());
+        events.clear();
+        self.user_index.write().clear();
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
 }
 
 impl Default for AuditLogger {
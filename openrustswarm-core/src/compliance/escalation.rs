@@ -2,6 +2,7 @@
 //!
 //! Queues high-risk actions for human approval before execution.
 
+use super::ids::unique_id;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -236,13 +237,7 @@ impl EscalationFlow {
         reason: &str,
     ) -> PendingAction {
         let pending = PendingAction {
-            id: format!(
-                "esc-{}",
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_nanos()
-            ),
+            id: unique_id("esc"),
             agent_id: agent_id.to_string(),
             action: action.to_string(),
             data: data.to_string(),
@@ -0,0 +1,40 @@
+//! Unique ID generation for compliance records
+//!
+//! Timestamps alone collide when records are created faster than the clock
+//! ticks, so every ID also carries a process-wide monotonic counter.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Build an ID of the form `{prefix}-{nanos}-{counter}`, unique within the process
+pub fn unique_id(prefix: &str) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, nanos, seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_concurrent_ids_are_unique() {
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| (0..10_000).map(|_| unique_id("t")).collect::<Vec<_>>()))
+            .collect();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(seen.insert(id), "duplicate id generated");
+            }
+        }
+        assert_eq!(seen.len(), 80_000);
+    }
+}
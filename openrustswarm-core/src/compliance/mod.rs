@@ -8,27 +8,35 @@
 //! - Rate limiting (cost control)
 //! - Escalation flow (human-in-the-loop)
 //! - Input sanitization (prompt injection defense)
+//! - Security gate (all of the above chained in order)
 
 pub mod audit;
 pub mod escalation;
+pub mod gate;
+pub mod ids;
 pub mod pii;
 pub mod policy;
 pub mod ratelimit;
 pub mod sanitizer;
 pub mod trace;
 
+use hmac::{Hmac, Mac};
 use pyo3::prelude::*;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 pub use audit::AuditLogger;
 pub use escalation::EscalationFlow;
+pub use gate::{GateDecision, SecurityGate};
 pub use pii::PIIRedactor;
 pub use policy::PolicyEngine;
 pub use ratelimit::RateLimiter;
 pub use sanitizer::InputSanitizer;
-pub use trace::DecisionTracker;
+pub use trace::{DecisionTracker, TraceStep};
 
 /// Result of a compliance check
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +89,10 @@ pub struct ComplianceEngine {
     pii_redactor: Arc<PIIRedactor>,
     policy_engine: Arc<PolicyEngine>,
     decision_tracker: Arc<DecisionTracker>,
+    pii_denials: AtomicUsize,
+    policy_denials: AtomicUsize,
+    /// HMAC key for user pseudonyms; random per engine unless configured
+    pseudonym_key: Vec<u8>,
 }
 
 #[pymethods]
@@ -93,48 +105,59 @@ impl ComplianceEngine {
             pii_redactor: Arc::new(PIIRedactor::default()),
             policy_engine: Arc::new(PolicyEngine::new()),
             decision_tracker: Arc::new(DecisionTracker::new()),
+            pii_denials: AtomicUsize::new(0),
+            policy_denials: AtomicUsize::new(0),
+            pseudonym_key: Self::random_pseudonym_key(),
         }
     }
 
+    /// Set the secret key for `anonymize_user_data` pseudonyms. Reuse the same
+    /// key across restarts to keep pseudonyms stable; without it, pseudonyms
+    /// cannot be linked back to user IDs. Must be at least 16 bytes.
+    pub fn set_pseudonym_key(&mut self, key: Vec<u8>) -> PyResult<()> {
+        if key.len() < MIN_PSEUDONYM_KEY_LEN {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Pseudonym key must be at least {} bytes, got {}",
+                MIN_PSEUDONYM_KEY_LEN,
+                key.len()
+            )));
+        }
+        self.pseudonym_key = key;
+        Ok(())
+    }
+
     /// Check if an action is allowed by policy
     pub fn check_action(&self, agent_id: String, action: String, data: String) -> ComplianceResult {
         // Start decision trace
         let trace_id = self.decision_tracker.start_trace(&agent_id, &action);
 
-        // Check for PII in data
-        let pii_detected = self.pii_redactor.detect_pii(&data);
-        if !pii_detected.is_empty() {
-            let reason = format!("PII detected: {:?}", pii_detected);
-            self.audit_logger.log_denial(&agent_id, &action, &reason);
-            return ComplianceResult {
-                approved: false,
-                reason,
-                policy_id: Some("PII_PROTECTION".to_string()),
-                audit_id: trace_id,
-            };
-        }
-
-        // Evaluate policy rules
-        let policy_result = self.policy_engine.evaluate(&agent_id, &action, &data);
+        let mut result = self.evaluate(&agent_id, &action, &data);
+        result.audit_id = trace_id;
 
-        if policy_result.allowed {
+        if result.approved {
             self.audit_logger.log_approval(&agent_id, &action);
-            ComplianceResult {
-                approved: true,
-                reason: "Action approved".to_string(),
-                policy_id: None,
-                audit_id: trace_id,
-            }
         } else {
             self.audit_logger
-                .log_denial(&agent_id, &action, &policy_result.reason);
-            ComplianceResult {
-                approved: false,
-                reason: policy_result.reason,
-                policy_id: Some(policy_result.policy_id),
-                audit_id: trace_id,
-            }
+                .log_denial(&agent_id, &action, &result.reason);
+            let counter = match result.policy_id.as_deref() {
+                Some("PII_PROTECTION") => &self.pii_denials,
+                _ => &self.policy_denials,
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
         }
+        result
+    }
+
+    /// Evaluate PII and policy rules exactly like `check_action`, but without
+    /// starting a trace, writing audit events or updating counters. Useful for
+    /// replaying past actions against new rules.
+    pub fn check_action_dry_run(
+        &self,
+        agent_id: String,
+        action: String,
+        data: String,
+    ) -> ComplianceResult {
+        self.evaluate(&agent_id, &action, &data)
     }
 
     /// Redact PII from text
@@ -159,11 +182,90 @@ impl ComplianceEngine {
         self.audit_logger.export_json()
     }
 
+    /// Export audit logs as CSV, one row per event
+    pub fn export_audit_csv(&self) -> String {
+        self.audit_logger.export_csv()
+    }
+
+    /// Stream audit logs as CSV to a file
+    pub fn export_audit_csv_to_path(&self, path: String) -> PyResult<()> {
+        let file = std::fs::File::create(&path).map_err(|e| {
+            pyo3::exceptions::PyIOError::new_err(format!("cannot create {}: {}", path, e))
+        })?;
+        self.audit_logger
+            .write_csv(std::io::BufWriter::new(file))
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("write failed: {}", e)))
+    }
+
     /// Get decision trace for an audit ID
     pub fn get_trace(&self, trace_id: String) -> String {
         self.decision_tracker.get_trace(&trace_id)
     }
 
+    /// Get the steps of a decision trace as `TraceStep` objects
+    /// (empty for unknown trace IDs)
+    pub fn get_lineage(&self, trace_id: String) -> Vec<TraceStep> {
+        self.decision_tracker.get_lineage(&trace_id)
+    }
+
+    /// Record an intermediate reasoning step into an existing trace
+    pub fn add_trace_step(
+        &self,
+        trace_id: String,
+        action: String,
+        input: String,
+        output: String,
+        duration_ms: u64,
+    ) {
+        self.decision_tracker
+            .add_step(&trace_id, &action, &input, &output, duration_ms);
+    }
+
+    /// Mark a trace as finished (e.g. "completed" or "failed")
+    pub fn complete_trace(&self, trace_id: String, status: String) {
+        self.decision_tracker.complete_trace(&trace_id, &status);
+    }
+
+    /// Set every still-"running" trace to `status` (e.g. "interrupted").
+    /// Returns how many traces were closed.
+    pub fn finalize_open_traces(&self, status: String) -> usize {
+        let closed = self.decision_tracker.finalize_open(&status);
+        if closed > 0 {
+            info!(
+                "[Compliance] Finalized {} open traces as '{}'",
+                closed, status
+            );
+        }
+        closed
+    }
+
+    /// Number of traces still in "running" status
+    pub fn open_trace_count(&self) -> usize {
+        self.decision_tracker.open_trace_count()
+    }
+
+    /// Shutdown hook: closes in-flight traces as "interrupted" so exports
+    /// taken afterwards show no perpetually-open traces. Call it before the
+    /// process exits (e.g. from `atexit`), or use the engine as a context
+    /// manager, which calls it on exit.
+    pub fn shutdown(&self) -> usize {
+        self.finalize_open_traces("interrupted".to_string())
+    }
+
+    pub fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> bool {
+        self.shutdown();
+        false
+    }
+
     /// GDPR: Delete all data for a user
     pub fn delete_user_data(&self, user_id: String) -> bool {
         self.audit_logger.delete_user_logs(&user_id);
@@ -172,6 +274,29 @@ impl ComplianceEngine {
         true
     }
 
+    /// GDPR: Pseudonymize a user's data instead of deleting it.
+    ///
+    /// Audit events and decision traces are kept for audit integrity, but the
+    /// user ID is replaced with a keyed pseudonym (see `set_pseudonym_key`)
+    /// and PII is redacted from stored actions, reasons and step
+    /// inputs/outputs. Returns false if the user had no records.
+    pub fn anonymize_user_data(&self, user_id: String) -> bool {
+        let pseudonym = self.pseudonym(&user_id);
+        let redact = |text: &str| self.pii_redactor.redact(text);
+
+        let logs = self
+            .audit_logger
+            .anonymize_user_logs(&user_id, &pseudonym, redact);
+        let traces = self
+            .decision_tracker
+            .anonymize_user_traces(&user_id, &pseudonym, redact);
+        info!(
+            "🕶️  [GDPR] Anonymized {} audit events and {} traces as {}",
+            logs, traces, pseudonym
+        );
+        logs + traces > 0
+    }
+
     /// GDPR: Export all data for a user
     pub fn export_user_data(&self, user_id: String) -> String {
         let logs = self.audit_logger.export_user_logs(&user_id);
@@ -190,10 +315,108 @@ impl ComplianceEngine {
         let policy_count = self.policy_engine.count();
         let trace_count = self.decision_tracker.count();
         format!(
-            "ComplianceStats(audits={}, policies={}, traces={})",
-            audit_count, policy_count, trace_count
+            "ComplianceStats(audits={}, policies={}, traces={}, pii_denials={}, policy_denials={})",
+            audit_count,
+            policy_count,
+            trace_count,
+            self.pii_denials.load(Ordering::Relaxed),
+            self.policy_denials.load(Ordering::Relaxed)
         )
     }
+
+    /// Delete all audit events. Audit logs are append-only by design, so this
+    /// refuses to run unless `force` is set.
+    #[pyo3(signature = (force = false))]
+    pub fn clear_audit(&self, force: bool) -> PyResult<()> {
+        if !force {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "audit log is immutable; pass force=True to clear it",
+            ));
+        }
+        warn!("[Compliance] Audit log cleared (force=true)");
+        self.audit_logger.clear();
+        Ok(())
+    }
+
+    /// Delete all decision traces
+    pub fn clear_traces(&self) {
+        self.decision_tracker.clear();
+    }
+
+    /// Remove all policy rules, including the defaults
+    pub fn clear_policies(&self) {
+        self.policy_engine.clear();
+    }
+
+    /// Clear audit events, traces and policies. `force` is required for the audit log.
+    #[pyo3(signature = (force = false))]
+    pub fn clear(&self, force: bool) -> PyResult<()> {
+        self.clear_audit(force)?;
+        self.clear_traces();
+        self.clear_policies();
+        self.pii_denials.store(0, Ordering::Relaxed);
+        self.policy_denials.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl ComplianceEngine {
+    /// PII check then policy rules, with no side effects. `audit_id` is left empty.
+    fn evaluate(&self, agent_id: &str, action: &str, data: &str) -> ComplianceResult {
+        // Check for PII in data
+        let pii_detected = self.pii_redactor.detect_pii(data);
+        if !pii_detected.is_empty() {
+            return ComplianceResult {
+                approved: false,
+                reason: format!("PII detected: {:?}", pii_detected),
+                policy_id: Some("PII_PROTECTION".to_string()),
+                audit_id: String::new(),
+            };
+        }
+
+        // Evaluate policy rules
+        let policy_result = self.policy_engine.evaluate(agent_id, action, data);
+        if policy_result.allowed {
+            ComplianceResult {
+                approved: true,
+                reason: "Action approved".to_string(),
+                policy_id: None,
+                audit_id: String::new(),
+            }
+        } else {
+            ComplianceResult {
+                approved: false,
+                reason: policy_result.reason,
+                policy_id: Some(policy_result.policy_id),
+                audit_id: String::new(),
+            }
+        }
+    }
+
+    /// Stable pseudonym for a user ID, so anonymized records of the same user
+    /// can still be correlated. HMAC-SHA256 under `pseudonym_key`, so it cannot
+    /// be reversed by hashing candidate IDs without the key.
+    fn pseudonym(&self, user_id: &str) -> String {
+        let mac = hmac_sha256(&self.pseudonym_key, user_id.as_bytes());
+        let hex: String = mac[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("anon-{}", hex)
+    }
+
+    fn random_pseudonym_key() -> Vec<u8> {
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    }
+}
+
+/// Shortest key `set_pseudonym_key` accepts
+const MIN_PSEUDONYM_KEY_LEN: usize = 16;
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
 }
 
 impl Default for ComplianceEngine {
@@ -201,3 +424,272 @@ impl Default for ComplianceEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_steps_extend_lineage() {
+        let engine = ComplianceEngine::new();
+        let result = engine.check_action(
+            "agent-1".to_string(),
+            "search".to_string(),
+            "weather today".to_string(),
+        );
+        let trace_id = result.audit_id;
+
+        engine.add_trace_step(
+            trace_id.clone(),
+            "plan".to_string(),
+            "weather today".to_string(),
+            "call web_search".to_string(),
+            12,
+        );
+        engine.add_trace_step(
+            trace_id.clone(),
+            "answer".to_string(),
+            "search results".to_string(),
+            "sunny".to_string(),
+            30,
+        );
+        engine.complete_trace(trace_id.clone(), "completed".to_string());
+
+        let trace: serde_json::Value = serde_json::from_str(&engine.get_trace(trace_id)).unwrap();
+        assert_eq!(trace["steps"].as_array().unwrap().len(), 3);
+        assert_eq!(trace["steps"][2]["output"], "sunny");
+        assert_eq!(trace["status"], "completed");
+    }
+
+    #[test]
+    fn lineage_returns_steps_or_empty() {
+        let engine = ComplianceEngine::new();
+        let trace_id = engine
+            .check_action(
+                "agent-1".to_string(),
+                "search".to_string(),
+                "weather".to_string(),
+            )
+            .audit_id;
+        engine.add_trace_step(
+            trace_id.clone(),
+            "answer".to_string(),
+            "".to_string(),
+            "sunny".to_string(),
+            5,
+        );
+
+        let lineage = engine.get_lineage(trace_id);
+        assert_eq!(lineage.len(), 2);
+        assert_eq!(lineage[0].action, "search");
+        assert_eq!(lineage[1].output, "sunny");
+
+        assert!(engine.get_lineage("trace-unknown".to_string()).is_empty());
+    }
+
+    #[test]
+    fn clear_resets_stats() {
+        let engine = ComplianceEngine::new();
+        engine.check_action(
+            "agent-1".to_string(),
+            "search".to_string(),
+            "weather".to_string(),
+        );
+        assert_ne!(
+            engine.stats(),
+            "ComplianceStats(audits=0, policies=0, traces=0, pii_denials=0, policy_denials=0)"
+        );
+
+        assert!(engine.clear(false).is_err());
+        assert!(engine.audit_logger.count() > 0);
+
+        engine.clear(true).unwrap();
+        assert_eq!(
+            engine.stats(),
+            "ComplianceStats(audits=0, policies=0, traces=0, pii_denials=0, policy_denials=0)"
+        );
+    }
+
+    #[test]
+    fn finalize_closes_running_traces() {
+        let engine = ComplianceEngine::new();
+        let ids: Vec<String> = (0..3)
+            .map(|i| {
+                engine
+                    .check_action(
+                        format!("agent-{}", i),
+                        "search".to_string(),
+                        "q".to_string(),
+                    )
+                    .audit_id
+            })
+            .collect();
+        engine.complete_trace(ids[0].clone(), "completed".to_string());
+        assert_eq!(engine.open_trace_count(), 2);
+
+        assert_eq!(engine.finalize_open_traces("interrupted".to_string()), 2);
+        assert_eq!(engine.open_trace_count(), 0);
+        assert!(engine.get_trace(ids[0].clone()).contains("\"completed\""));
+        assert!(engine.get_trace(ids[1].clone()).contains("\"interrupted\""));
+
+        engine.check_action("agent-9".to_string(), "search".to_string(), "q".to_string());
+        assert_eq!(engine.shutdown(), 1);
+        assert_eq!(engine.decision_tracker.open_trace_count(), 0);
+    }
+
+    #[test]
+    fn anonymize_keeps_records_but_drops_user_id() {
+        let engine = ComplianceEngine::new();
+        let trace_id = engine
+            .check_action(
+                "alice".to_string(),
+                "search".to_string(),
+                "weather".to_string(),
+            )
+            .audit_id;
+        engine.add_trace_step(
+            trace_id.clone(),
+            "email".to_string(),
+            "write to alice@example.com".to_string(),
+            "sent".to_string(),
+            3,
+        );
+        engine.check_action(
+            "alice".to_string(),
+            "notify bob@example.com".to_string(),
+            "hi".to_string(),
+        );
+        assert!(engine.export_audit_logs().contains("bob@example.com"));
+        let stats_before = engine.stats();
+
+        assert!(engine.anonymize_user_data("alice".to_string()));
+        assert_eq!(engine.stats(), stats_before);
+
+        let trace = engine.get_trace(trace_id);
+        assert!(!trace.contains("alice"));
+        let pseudonym = engine.pseudonym("alice");
+        assert!(trace.contains(&pseudonym));
+        assert!(!engine.export_audit_logs().contains("\"alice\""));
+        assert!(!engine.export_audit_logs().contains("bob@example.com"));
+
+        let export = engine.export_user_data("alice".to_string());
+        assert!(!export.contains("search"));
+        assert!(!engine.anonymize_user_data("alice".to_string()));
+    }
+
+    #[test]
+    fn pseudonyms_are_keyed() {
+        // RFC 4231 test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = mac.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let mut a = ComplianceEngine::new();
+        let mut b = ComplianceEngine::new();
+        assert_ne!(a.pseudonym("alice"), b.pseudonym("alice"));
+
+        a.set_pseudonym_key(b"0123456789abcdef".to_vec()).unwrap();
+        b.set_pseudonym_key(b"0123456789abcdef".to_vec()).unwrap();
+        assert_eq!(a.pseudonym("alice"), b.pseudonym("alice"));
+        assert_ne!(a.pseudonym("alice"), a.pseudonym("bob"));
+    }
+
+    /// Minimal RFC 4180 parser for the round-trip test
+    fn parse_csv(text: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                (true, '"') => quoted = false,
+                (true, c) => field.push(c),
+                (false, '"') => quoted = true,
+                (false, ',') => row.push(std::mem::take(&mut field)),
+                (false, '\n') => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                (false, c) => field.push(c),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn audit_csv_round_trips() {
+        let engine = ComplianceEngine::new();
+        engine.audit_logger.log_approval("agent-1", "search");
+        engine
+            .audit_logger
+            .log_denial("agent-2", "delete", "blocked, \"unsafe\"\nsee policy");
+
+        let csv = engine.export_audit_csv();
+        let rows = parse_csv(&csv);
+        assert_eq!(
+            rows[0],
+            ["id", "timestamp", "agent_id", "action", "outcome", "reason"]
+        );
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][2..], ["agent-1", "search", "APPROVED", ""]);
+        assert_eq!(
+            rows[2][2..],
+            [
+                "agent-2",
+                "delete",
+                "DENIED",
+                "blocked, \"unsafe\"\nsee policy"
+            ]
+        );
+
+        let path = std::env::temp_dir().join(format!("audit-{}.csv", std::process::id()));
+        engine
+            .export_audit_csv_to_path(path.to_string_lossy().to_string())
+            .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(written, csv);
+    }
+
+    #[test]
+    fn dry_run_leaves_audit_untouched() {
+        let engine = ComplianceEngine::new();
+        engine.add_policy(
+            "DENY_EXPORT".to_string(),
+            ".*export.*".to_string(),
+            false,
+            "Exports are blocked".to_string(),
+        );
+
+        let dry = engine.check_action_dry_run(
+            "agent-1".to_string(),
+            "export".to_string(),
+            "report".to_string(),
+        );
+        assert!(!dry.approved);
+        assert_eq!(dry.policy_id.as_deref(), Some("DENY_EXPORT"));
+        assert_eq!(engine.audit_logger.count(), 0);
+        assert_eq!(engine.decision_tracker.count(), 0);
+
+        engine.check_action(
+            "agent-1".to_string(),
+            "export".to_string(),
+            "report".to_string(),
+        );
+        engine.check_action(
+            "agent-1".to_string(),
+            "email".to_string(),
+            "contact bob@example.com".to_string(),
+        );
+        assert_eq!(engine.audit_logger.count(), 2);
+        assert!(engine.stats().ends_with("pii_denials=1, policy_denials=1)"));
+    }
+}
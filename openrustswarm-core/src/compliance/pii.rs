@@ -2,13 +2,17 @@
 //!
 //! Automatically detect and mask sensitive data:
 //! - Email addresses
-//! - Phone numbers
+//! - Phone numbers (US and international `+<country>` format)
+//! - IBANs (mod-97 validated)
 //! - Social Security Numbers
 //! - Credit card numbers
 //! - API keys
 
+use parking_lot::RwLock;
 use pyo3::prelude::*;
+use rand::RngCore;
 use regex::Regex;
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
 /// PII pattern definitions
@@ -18,6 +22,13 @@ static EMAIL_PATTERN: LazyLock<Option<Regex>> =
 static PHONE_PATTERN: LazyLock<Option<Regex>> =
     LazyLock::new(|| Regex::new(r"\b\d{3}[-.\s]?\d{3}[-.\s]?\d{4}\b").ok());
 
+static PHONE_INTL_PATTERN: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"\+\d{1,3}(?:[-.\s]?\d){6,14}\b").ok());
+
+static IBAN_PATTERN: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Z]{2}\d{2}(?:\s?[A-Z0-9]{4}){2,7}(?:\s?[A-Z0-9]{1,3})?\b").ok()
+});
+
 static SSN_PATTERN: LazyLock<Option<Regex>> =
     LazyLock::new(|| Regex::new(r"\b\d{3}-\d{2}-\d{4}\b").ok());
 
@@ -69,6 +80,147 @@ static DEMOGRAPHIC_PATTERN: LazyLock<Option<Regex>> =
 static GPS_PATTERN: LazyLock<Option<Regex>> =
     LazyLock::new(|| Regex::new(r"\b-?\d{1,3}\.\d{4,},\s*-?\d{1,3}\.\d{4,}\b").ok());
 
+/// Luhn checksum used to validate card numbers
+fn luhn_valid(digits: &[u32]) -> bool {
+    if digits.len() < 2 {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// ISO 13616 mod-97 check: move the first four characters to the end,
+/// map letters to 10..35 and require the remainder to be 1
+fn iban_valid(value: &str) -> bool {
+    let compact: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let mut remainder = 0u32;
+    for c in compact[4..].iter().chain(&compact[..4]) {
+        remainder = match c.to_digit(36) {
+            Some(d) if d < 10 => (remainder * 10 + d) % 97,
+            Some(d) => (remainder * 100 + d) % 97,
+            None => return false,
+        };
+    }
+    remainder == 1
+}
+
+/// ASCII digits only; `\d` also matches other Unicode decimal digits, so
+/// fixed-width checks must verify the length before indexing
+fn digits_of(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Score a match by pattern specificity, refined by per-type validators
+fn match_confidence(pii_type: &str, value: &str) -> f32 {
+    match pii_type {
+        "Email" => 0.95,
+        "APIKey" => 0.9,
+        "IBAN" => {
+            if iban_valid(value) {
+                0.95
+            } else {
+                0.1
+            }
+        }
+        "PhoneIntl" => {
+            // E.164 caps numbers at 15 digits including the country code
+            if digits_of(value).len() <= 15 {
+                0.8
+            } else {
+                0.3
+            }
+        }
+        "SSN" => {
+            // Area 000/666/9xx, group 00 and serial 0000 are never issued
+            let digits = digits_of(value);
+            if digits.len() != 9 {
+                return 0.1;
+            }
+            let area = digits[0] * 100 + digits[1] * 10 + digits[2];
+            let group = digits[3] * 10 + digits[4];
+            let serial = digits[5..].iter().fold(0, |acc, d| acc * 10 + d);
+            if area == 0 || area == 666 || area >= 900 || group == 0 || serial == 0 {
+                0.3
+            } else {
+                0.9
+            }
+        }
+        "CreditCard" => {
+            if luhn_valid(&digits_of(value)) {
+                0.95
+            } else {
+                0.3
+            }
+        }
+        "Phone" => {
+            // NANP area codes and exchanges never start with 0 or 1
+            let digits = digits_of(value);
+            if digits.len() != 10 {
+                0.1
+            } else if digits[0] < 2 || digits[3] < 2 {
+                0.3
+            } else if value.chars().any(|c| !c.is_ascii_digit()) {
+                0.8
+            } else {
+                0.6
+            }
+        }
+        "DigitalID" => {
+            let octets: Vec<&str> = value.split('.').collect();
+            if octets.len() == 4 {
+                if octets
+                    .iter()
+                    .all(|o| o.parse::<u32>().is_ok_and(|n| n <= 255))
+                {
+                    0.7
+                } else {
+                    0.2
+                }
+            } else {
+                0.8
+            }
+        }
+        "GPS" => {
+            let coords: Vec<f64> = value
+                .split(',')
+                .filter_map(|c| c.trim().parse::<f64>().ok())
+                .collect();
+            if coords.len() == 2 && coords[0].abs() <= 90.0 && coords[1].abs() <= 180.0 {
+                0.8
+            } else {
+                0.2
+            }
+        }
+        "Address" => 0.7,
+        "Biometric" | "Medical" => 0.6,
+        "DOB" => 0.5,
+        "Demographic" => 0.4,
+        "DriversLicense" => 0.3,
+        "BankAccount" => 0.25,
+        "Passport" => 0.2,
+        _ => 0.5,
+    }
+}
+
 /// PII detection result
 #[derive(Debug, Clone)]
 #[pyclass]
@@ -81,22 +233,92 @@ pub struct PIIMatch {
     pub start: usize,
     #[pyo3(get)]
     pub end: usize,
+    /// How likely the match is real PII (0.0-1.0), from pattern
+    /// specificity plus validators such as Luhn for cards
+    #[pyo3(get)]
+    pub confidence: f32,
 }
 
 #[pymethods]
 impl PIIMatch {
     pub fn __repr__(&self) -> String {
         format!(
-            "PIIMatch({}: '{}' at {}..{})",
-            self.pii_type, self.value, self.start, self.end
+            "PIIMatch({}: '{}' at {}..{}, confidence={:.2})",
+            self.pii_type, self.value, self.start, self.end, self.confidence
         )
     }
 }
 
+/// How matches of one PII type are rewritten by `PIIRedactor::redact`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RedactionPolicy {
+    /// Mask every character, with a type-specific char if given
+    Full(Option<char>),
+    /// Mask all but the last four alphanumerics, keeping separators
+    PartialLast4,
+    /// Replace with a stable `[TYPE:<hmac prefix>]` token
+    Hash,
+}
+
+impl RedactionPolicy {
+    /// Parse "full", "full:<char>", "partial_last4" or "hash"
+    fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "full" => Some(RedactionPolicy::Full(None)),
+            "partial_last4" => Some(RedactionPolicy::PartialLast4),
+            "hash" => Some(RedactionPolicy::Hash),
+            _ => {
+                let mut chars = policy.strip_prefix("full:")?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(RedactionPolicy::Full(Some(c))),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn apply(self, pii_type: &str, value: &str, default_char: char, hash_key: &[u8]) -> String {
+        match self {
+            RedactionPolicy::Full(c) => c.unwrap_or(default_char).to_string().repeat(value.len()),
+            RedactionPolicy::PartialLast4 => {
+                let keep_from = value
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .count()
+                    .saturating_sub(4);
+                let mut seen = 0;
+                value
+                    .chars()
+                    .map(|c| {
+                        if !c.is_alphanumeric() {
+                            return c;
+                        }
+                        seen += 1;
+                        if seen > keep_from {
+                            c
+                        } else {
+                            default_char
+                        }
+                    })
+                    .collect()
+            }
+            RedactionPolicy::Hash => {
+                let digest = hex::encode(super::hmac_sha256(hash_key, value.as_bytes()));
+                format!("[{}:{}]", pii_type, &digest[..12])
+            }
+        }
+    }
+}
+
 /// PII redactor
 #[pyclass]
 pub struct PIIRedactor {
     redaction_char: char,
+    /// Per-type overrides; types without one are fully masked
+    policies: RwLock<HashMap<String, RedactionPolicy>>,
+    /// HMAC key for the "hash" policy, so tokens cannot be reversed by
+    /// hashing candidate values
+    hash_key: RwLock<Vec<u8>>,
 }
 
 #[pymethods]
@@ -104,7 +326,39 @@ impl PIIRedactor {
     #[new]
     #[pyo3(signature = (redaction_char = '*'))]
     pub fn new(redaction_char: char) -> Self {
-        PIIRedactor { redaction_char }
+        PIIRedactor {
+            redaction_char,
+            policies: RwLock::new(HashMap::new()),
+            hash_key: RwLock::new(random_hash_key()),
+        }
+    }
+
+    /// Set the secret key for "hash" policy tokens. Reuse the same key across
+    /// restarts to keep tokens stable. Must be at least 16 bytes.
+    pub fn set_hash_key(&self, key: Vec<u8>) -> PyResult<()> {
+        if key.len() < MIN_HASH_KEY_LEN {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Hash key must be at least {} bytes, got {}",
+                MIN_HASH_KEY_LEN,
+                key.len()
+            )));
+        }
+        *self.hash_key.write() = key;
+        Ok(())
+    }
+
+    /// Set how matches of `pii_type` (e.g. "SSN", "CreditCard") are redacted:
+    /// "full", "full:<char>" (mask with a type-specific char),
+    /// "partial_last4" or "hash"
+    pub fn set_type_policy(&self, pii_type: String, policy: String) -> PyResult<()> {
+        let parsed = RedactionPolicy::parse(&policy).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown redaction policy '{}': expected full, full:<char>, partial_last4 or hash",
+                policy
+            ))
+        })?;
+        self.policies.write().insert(pii_type, parsed);
+        Ok(())
     }
 
     /// Detect all PII in text
@@ -119,6 +373,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Email", m.as_str()),
                 });
             }
         }
@@ -131,6 +386,36 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Phone", m.as_str()),
+                });
+            }
+        }
+
+        // International phone
+        if let Some(pattern) = PHONE_INTL_PATTERN.as_ref() {
+            for m in pattern.find_iter(text) {
+                matches.push(PIIMatch {
+                    pii_type: "PhoneIntl".to_string(),
+                    value: m.as_str().to_string(),
+                    start: m.start(),
+                    end: m.end(),
+                    confidence: match_confidence("PhoneIntl", m.as_str()),
+                });
+            }
+        }
+
+        // IBAN (checksum must pass, so random alphanumerics are not flagged)
+        if let Some(pattern) = IBAN_PATTERN.as_ref() {
+            for m in pattern.find_iter(text) {
+                if !iban_valid(m.as_str()) {
+                    continue;
+                }
+                matches.push(PIIMatch {
+                    pii_type: "IBAN".to_string(),
+                    value: m.as_str().to_string(),
+                    start: m.start(),
+                    end: m.end(),
+                    confidence: match_confidence("IBAN", m.as_str()),
                 });
             }
         }
@@ -143,6 +428,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("SSN", m.as_str()),
                 });
             }
         }
@@ -155,6 +441,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("CreditCard", m.as_str()),
                 });
             }
         }
@@ -167,6 +454,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("APIKey", m.as_str()),
                 });
             }
         }
@@ -179,6 +467,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Address", m.as_str()),
                 });
             }
         }
@@ -191,6 +480,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("DOB", m.as_str()),
                 });
             }
         }
@@ -203,6 +493,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Passport", m.as_str()),
                 });
             }
         }
@@ -215,6 +506,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Biometric", m.as_str()),
                 });
             }
         }
@@ -227,6 +519,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("DriversLicense", m.as_str()),
                 });
             }
         }
@@ -239,6 +532,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("BankAccount", m.as_str()),
                 });
             }
         }
@@ -251,6 +545,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Medical", m.as_str()),
                 });
             }
         }
@@ -263,6 +558,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("DigitalID", m.as_str()),
                 });
             }
         }
@@ -275,6 +571,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("Demographic", m.as_str()),
                 });
             }
         }
@@ -287,6 +584,7 @@ impl PIIRedactor {
                     value: m.as_str().to_string(),
                     start: m.start(),
                     end: m.end(),
+                    confidence: match_confidence("GPS", m.as_str()),
                 });
             }
         }
@@ -294,17 +592,41 @@ impl PIIRedactor {
         matches
     }
 
-    /// Redact all PII from text
-    pub fn redact(&self, text: &str) -> String {
-        let mut result = text.to_string();
+    /// Detect PII, keeping only matches whose confidence meets `threshold`
+    pub fn detect_pii_min_confidence(&self, text: &str, threshold: f32) -> Vec<PIIMatch> {
+        self.detect_pii(text)
+            .into_iter()
+            .filter(|m| m.confidence >= threshold)
+            .collect()
+    }
 
-        // Redact in reverse order to preserve positions
+    /// Redact all PII from text, applying each type's policy.
+    /// Overlapping matches are merged and take the policy of the earliest
+    /// (then longest) one.
+    pub fn redact(&self, text: &str) -> String {
         let mut matches = self.detect_pii(text);
-        matches.sort_by(|a, b| b.start.cmp(&a.start));
+        matches.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
 
+        let mut spans: Vec<(usize, usize, String)> = Vec::new();
         for m in matches {
-            let redacted = self.redaction_char.to_string().repeat(m.value.len());
-            result.replace_range(m.start..m.end, &redacted);
+            match spans.last_mut() {
+                Some((_, end, _)) if m.start < *end => *end = (*end).max(m.end),
+                _ => spans.push((m.start, m.end, m.pii_type)),
+            }
+        }
+
+        // Redact in reverse order to preserve positions
+        let policies = self.policies.read();
+        let hash_key = self.hash_key.read();
+        let mut result = text.to_string();
+        for (start, end, pii_type) in spans.into_iter().rev() {
+            let policy = policies
+                .get(&pii_type)
+                .copied()
+                .unwrap_or(RedactionPolicy::Full(None));
+            let redacted =
+                policy.apply(&pii_type, &text[start..end], self.redaction_char, &hash_key);
+            result.replace_range(start..end, &redacted);
         }
 
         result
@@ -316,8 +638,137 @@ impl PIIRedactor {
     }
 }
 
+/// Shortest key `set_hash_key` accepts
+const MIN_HASH_KEY_LEN: usize = 16;
+
+fn random_hash_key() -> Vec<u8> {
+    let mut key = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
 impl Default for PIIRedactor {
     fn default() -> Self {
         Self::new('*')
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_ssn_scores_high() {
+        let redactor = PIIRedactor::default();
+        let matches = redactor.detect_pii("SSN on file: 123-45-6789");
+        let ssn = matches.iter().find(|m| m.pii_type == "SSN").unwrap();
+        assert!(ssn.confidence >= 0.8);
+    }
+
+    #[test]
+    fn random_token_scores_low() {
+        let redactor = PIIRedactor::default();
+        let matches = redactor.detect_pii("build tag QX7ZK2P");
+        let token = matches.iter().find(|m| m.value == "QX7ZK2P").unwrap();
+        assert!(token.confidence < 0.5);
+        assert!(redactor
+            .detect_pii_min_confidence("build tag QX7ZK2P", 0.5)
+            .is_empty());
+    }
+
+    #[test]
+    fn luhn_separates_real_cards() {
+        assert!(match_confidence("CreditCard", "4111 1111 1111 1111") > 0.9);
+        assert!(match_confidence("CreditCard", "4111 1111 1111 1112") < 0.5);
+    }
+
+    #[test]
+    fn iban_requires_valid_checksum() {
+        let redactor = PIIRedactor::default();
+        let matches = redactor.detect_pii("Pay to GB82 WEST 1234 5698 7654 32 today");
+        let iban = matches.iter().find(|m| m.pii_type == "IBAN").unwrap();
+        assert_eq!(iban.value, "GB82 WEST 1234 5698 7654 32");
+        assert!(iban.confidence > 0.9);
+
+        assert!(redactor
+            .detect_pii("DE89370400440532013000")
+            .iter()
+            .any(|m| m.pii_type == "IBAN"));
+        assert!(!redactor
+            .detect_pii("Pay to GB82 WEST 1234 5698 7654 33 today")
+            .iter()
+            .any(|m| m.pii_type == "IBAN"));
+    }
+
+    #[test]
+    fn international_phone_detected() {
+        let redactor = PIIRedactor::default();
+        let matches = redactor.detect_pii("Call +44 20 7946 0958 after 9");
+        let phone = matches.iter().find(|m| m.pii_type == "PhoneIntl").unwrap();
+        assert_eq!(phone.value, "+44 20 7946 0958");
+        assert!(phone.confidence >= 0.8);
+    }
+
+    #[test]
+    fn non_ascii_digits_do_not_panic() {
+        let redactor = PIIRedactor::default();
+        // Arabic-Indic and fullwidth digits match `\d` but are not ASCII
+        redactor.detect_pii("SSN ١٢٣-٤٥-٦٧٨٩ and phone １２３-４５６-７８９０");
+        assert!(match_confidence("SSN", "١٢٣-٤٥-٦٧٨٩") < 0.5);
+        assert!(match_confidence("Phone", "１２３-４５６-７８９０") < 0.5);
+    }
+
+    #[test]
+    fn card_keeps_last_four_under_partial_policy() {
+        let redactor = PIIRedactor::default();
+        redactor
+            .set_type_policy("CreditCard".to_string(), "partial_last4".to_string())
+            .unwrap();
+        assert_eq!(
+            redactor.redact("Card: 4111 1111 1111 1234"),
+            "Card: **** **** **** 1234"
+        );
+        // Undelimited cards also match BankAccount; the merged span keeps the card policy
+        assert_eq!(
+            redactor.redact("Card: 4111111111111234"),
+            "Card: ************1234"
+        );
+    }
+
+    #[test]
+    fn ssn_fully_masked_with_type_char() {
+        let redactor = PIIRedactor::default();
+        redactor
+            .set_type_policy("SSN".to_string(), "full:X".to_string())
+            .unwrap();
+        assert_eq!(
+            redactor.redact("SSN 123-45-6789, mail a@b.io"),
+            "SSN XXXXXXXXXXX, mail ******"
+        );
+
+        redactor
+            .set_type_policy("SSN".to_string(), "hash".to_string())
+            .unwrap();
+        let hashed = redactor.redact("SSN 123-45-6789");
+        assert!(hashed.starts_with("SSN [SSN:") && !hashed.contains("6789"));
+        assert_eq!(hashed, redactor.redact("SSN 123-45-6789"));
+
+        // Tokens are keyed per redactor, so they differ until keys match
+        let other = PIIRedactor::default();
+        other
+            .set_type_policy("SSN".to_string(), "hash".to_string())
+            .unwrap();
+        assert_ne!(hashed, other.redact("SSN 123-45-6789"));
+        assert!(other.set_hash_key(b"short".to_vec()).is_err());
+        redactor.set_hash_key(b"0123456789abcdef".to_vec()).unwrap();
+        other.set_hash_key(b"0123456789abcdef".to_vec()).unwrap();
+        assert_eq!(
+            redactor.redact("SSN 123-45-6789"),
+            other.redact("SSN 123-45-6789")
+        );
+
+        assert!(redactor
+            .set_type_policy("SSN".to_string(), "blur".to_string())
+            .is_err());
+    }
+}
@@ -149,6 +149,10 @@ impl PolicyEngine {
     pub fn list_policies(&self) -> Vec<Policy> {
         self.policies.read().clone()
     }
+
+    pub fn clear(&self) {
+        self.policies.write().clear();
+    }
 }
 
 impl Default for PolicyEngine {
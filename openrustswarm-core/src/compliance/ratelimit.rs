@@ -58,22 +58,36 @@ impl SlidingWindow {
     }
 
     fn check_and_increment(&mut self) -> bool {
-        let now = Instant::now();
+        self.check_and_add(1)
+    }
 
-        // Reset window if expired
+    /// Reset window if expired
+    fn roll(&mut self) {
+        let now = Instant::now();
         if now.duration_since(self.window_start) >= self.window_duration {
             self.window_start = now;
             self.count = 0;
         }
+    }
 
-        if self.count >= self.limit {
+    /// Admit `amount` units if they fit in the current window's budget
+    fn check_and_add(&mut self, amount: u32) -> bool {
+        self.roll();
+
+        if self.count.saturating_add(amount) > self.limit {
             false
         } else {
-            self.count += 1;
+            self.count += amount;
             true
         }
     }
 
+    /// Charge `amount` units unconditionally, even past the limit
+    fn add(&mut self, amount: u32) {
+        self.roll();
+        self.count = self.count.saturating_add(amount);
+    }
+
     fn remaining(&self) -> u32 {
         if self.count >= self.limit {
             0
@@ -112,6 +126,7 @@ pub struct RateLimiter {
     config: RateLimitConfig,
     request_windows: RwLock<HashMap<String, SlidingWindow>>,
     action_windows: RwLock<HashMap<String, SlidingWindow>>,
+    token_windows: RwLock<HashMap<String, SlidingWindow>>,
 }
 
 #[pymethods]
@@ -128,6 +143,7 @@ impl RateLimiter {
             config: cfg,
             request_windows: RwLock::new(HashMap::new()),
             action_windows: RwLock::new(HashMap::new()),
+            token_windows: RwLock::new(HashMap::new()),
         }
     }
 
@@ -191,6 +207,78 @@ impl RateLimiter {
         }
     }
 
+    /// Charge `tokens` against the agent's per-minute token budget.
+    /// Blocked requests are not charged.
+    pub fn check_tokens(&self, agent_id: String, tokens: u32) -> RateLimitResult {
+        let mut windows = self.token_windows.write();
+
+        let window = windows
+            .entry(agent_id.clone())
+            .or_insert_with(|| SlidingWindow::new(self.config.tokens_per_minute, 60));
+
+        if window.check_and_add(tokens) {
+            RateLimitResult {
+                allowed: true,
+                remaining: window.remaining(),
+                reason: String::new(),
+            }
+        } else {
+            warn!(
+                "🚫 [RateLimiter] {} tokens blocked for {}: token budget exceeded",
+                tokens, agent_id
+            );
+            self.token_limit_exceeded(window.remaining())
+        }
+    }
+
+    /// Record `tokens` that were already spent against the agent's budget.
+    /// Usage is always charged, even past the budget, so an overage blocks
+    /// later calls until the window resets.
+    pub fn record_tokens(&self, agent_id: String, tokens: u32) -> RateLimitResult {
+        let mut windows = self.token_windows.write();
+
+        let window = windows
+            .entry(agent_id.clone())
+            .or_insert_with(|| SlidingWindow::new(self.config.tokens_per_minute, 60));
+
+        window.add(tokens);
+        if window.remaining() > 0 {
+            RateLimitResult {
+                allowed: true,
+                remaining: window.remaining(),
+                reason: String::new(),
+            }
+        } else {
+            warn!(
+                "🚫 [RateLimiter] Token budget exhausted for {} ({} tokens/minute)",
+                agent_id, self.config.tokens_per_minute
+            );
+            self.token_limit_exceeded(0)
+        }
+    }
+
+    /// Check whether the agent has any token budget left, without charging it
+    pub fn check_token_budget(&self, agent_id: String) -> RateLimitResult {
+        let mut windows = self.token_windows.write();
+
+        let remaining = match windows.get_mut(&agent_id) {
+            Some(window) => {
+                window.roll();
+                window.remaining()
+            }
+            None => self.config.tokens_per_minute,
+        };
+        if remaining > 0 {
+            RateLimitResult {
+                allowed: true,
+                remaining,
+                reason: String::new(),
+            }
+        } else {
+            self.token_limit_exceeded(0)
+        }
+    }
+
     /// Get current usage stats
     pub fn get_stats(&self, agent_id: String) -> String {
         let req_windows = self.request_windows.read();
@@ -205,9 +293,70 @@ impl RateLimiter {
             .map(|w| w.remaining())
             .unwrap_or(self.config.actions_per_hour);
 
+        let tok_remaining = self
+            .token_windows
+            .read()
+            .get(&agent_id)
+            .map(|w| w.remaining())
+            .unwrap_or(self.config.tokens_per_minute);
+
         format!(
-            "RateLimitStats(requests_remaining={}, actions_remaining={})",
-            req_remaining, act_remaining
+            "RateLimitStats(requests_remaining={}, actions_remaining={}, tokens_remaining={})",
+            req_remaining, act_remaining, tok_remaining
         )
     }
 }
+
+impl RateLimiter {
+    fn token_limit_exceeded(&self, remaining: u32) -> RateLimitResult {
+        RateLimitResult {
+            allowed: false,
+            remaining,
+            reason: format!(
+                "Token limit exceeded: {} tokens/minute",
+                self.config.tokens_per_minute
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_budget_blocks_large_requests() {
+        let limiter = RateLimiter::new(Some(RateLimitConfig::new(60, 1000, 1000)));
+
+        let small = limiter.check_tokens("agent-1".to_string(), 300);
+        assert!(small.allowed);
+        assert_eq!(small.remaining, 700);
+
+        let big = limiter.check_tokens("agent-1".to_string(), 900);
+        assert!(!big.allowed);
+        assert_eq!(big.remaining, 700);
+
+        assert!(limiter.check_tokens("agent-1".to_string(), 700).allowed);
+        assert!(!limiter.check_tokens("agent-1".to_string(), 1).allowed);
+
+        // Budgets are per agent
+        assert!(limiter.check_tokens("agent-2".to_string(), 900).allowed);
+    }
+
+    #[test]
+    fn test_recorded_overage_exhausts_budget() {
+        let limiter = RateLimiter::new(Some(RateLimitConfig::new(60, 1000, 1000)));
+        assert!(limiter.check_token_budget("agent-1".to_string()).allowed);
+
+        let first = limiter.record_tokens("agent-1".to_string(), 600);
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 400);
+
+        // Spent tokens are charged even when they overshoot the budget
+        let second = limiter.record_tokens("agent-1".to_string(), 600);
+        assert!(!second.allowed);
+        assert_eq!(second.remaining, 0);
+        assert!(!limiter.check_token_budget("agent-1".to_string()).allowed);
+        assert!(!limiter.check_tokens("agent-1".to_string(), 1).allowed);
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Tracks the full lineage of agent decisions for audit purposes.
 
+use super::ids::unique_id;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -50,12 +51,7 @@ impl DecisionTracker {
     }
 
     fn generate_id(prefix: &str) -> String {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        format!("{}-{}", prefix, nanos)
+        unique_id(prefix)
     }
 
     fn now() -> String {
@@ -126,6 +122,26 @@ impl DecisionTracker {
         }
     }
 
+    /// Close every trace still "running" with `status`. Returns how many were closed.
+    pub fn finalize_open(&self, status: &str) -> usize {
+        let mut traces = self.traces.write();
+        let mut closed = 0;
+        for trace in traces.values_mut().filter(|t| t.status == "running") {
+            trace.status = status.to_string();
+            closed += 1;
+        }
+        closed
+    }
+
+    /// Number of traces still in "running" status
+    pub fn open_trace_count(&self) -> usize {
+        self.traces
+            .read()
+            .values()
+            .filter(|t| t.status == "running")
+            .count()
+    }
+
     pub fn get_trace(&self, trace_id: &str) -> String {
         let traces = self.traces.read();
         traces
@@ -164,9 +180,48 @@ impl DecisionTracker {
         }
     }
 
+    /// Re-key a user's traces under `pseudonym`, passing every step's input and
+    /// output through `redact`. Traces are kept, so counts stay intact. Returns
+    /// how many traces were rewritten.
+    pub fn anonymize_user_traces(
+        &self,
+        user_id: &str,
+        pseudonym: &str,
+        redact: impl Fn(&str) -> String,
+    ) -> usize {
+        // Same lock order as start_trace: traces, then index
+        let mut traces = self.traces.write();
+        let mut index = self.user_index.write();
+        let Some(trace_ids) = index.remove(user_id) else {
+            return 0;
+        };
+
+        for id in &trace_ids {
+            if let Some(trace) = traces.get_mut(id) {
+                trace.agent_id = pseudonym.to_string();
+                for step in &mut trace.steps {
+                    step.action = redact(&step.action);
+                    step.input = redact(&step.input);
+                    step.output = redact(&step.output);
+                }
+            }
+        }
+        let count = trace_ids.len();
+        index
+            .entry(pseudonym.to_string())
+            .or_default()
+            .extend(trace_ids);
+        count
+    }
+
     pub fn count(&self) -> usize {
         self.traces.read().len()
     }
+
+    pub fn clear(&self) {
+        self.traces.write().clear();
+        self.user_index.write().clear();
+    }
 }
 
 impl Default for DecisionTracker {
@@ -13,6 +13,15 @@ pub struct Agent {
     /// System instructions or "persona" that guides the model's behavior
     #[pyo3(get, set)]
     pub instructions: String,
+    /// Sampling temperature for this agent's turns (overrides `CogOpsConfig.temperature`)
+    #[pyo3(get, set)]
+    pub temperature: Option<f32>,
+    /// Model tried first for this agent's turns, ahead of the fallback list
+    #[pyo3(get, set)]
+    pub model: Option<String>,
+    /// Output token limit for this agent's turns (overrides `CogOpsConfig.max_tokens`)
+    #[pyo3(get, set)]
+    pub max_tokens: Option<u32>,
     /// Targeted potential handoff agents by name
     handoff_names: Vec<String>,
 }
@@ -20,10 +29,20 @@ pub struct Agent {
 #[pymethods]
 impl Agent {
     #[new]
-    pub fn new(name: String, instructions: String) -> Self {
+    #[pyo3(signature = (name, instructions, temperature = None, model = None, max_tokens = None))]
+    pub fn new(
+        name: String,
+        instructions: String,
+        temperature: Option<f32>,
+        model: Option<String>,
+        max_tokens: Option<u32>,
+    ) -> Self {
         Agent {
             name,
             instructions,
+            temperature,
+            model,
+            max_tokens,
             handoff_names: Vec::new(),
         }
     }
@@ -43,6 +43,63 @@ pub struct CogOpsConfig {
     pub introspection: IntrospectionConfig,
     #[pyo3(get, set)]
     pub system_prompt: String,
+    /// Hosts `fetch_url` and `http_request` may contact (subdomains included); empty allows any public host
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub allowed_url_hosts: Vec<String>,
+    /// Hard cap on bytes read from a `fetch_url` or `http_request` response body
+    #[pyo3(get, set)]
+    #[serde(default = "default_max_fetch_bytes")]
+    pub max_fetch_bytes: usize,
+    /// Seconds a cached `web_search` result stays fresh (0 disables the cache)
+    #[pyo3(get, set)]
+    #[serde(default = "default_search_cache_ttl_secs")]
+    pub search_cache_ttl_secs: u64,
+    /// Maximum number of cached `web_search` queries (0 disables the cache)
+    #[pyo3(get, set)]
+    #[serde(default = "default_search_cache_size")]
+    pub search_cache_size: usize,
+    /// Default sampling temperature (None uses the model's default)
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Default output token limit per model call
+    #[pyo3(get, set)]
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Enables the `run_python` tool; off by default because it executes model-written code
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub allow_code_execution: bool,
+    /// Wall-clock limit for a single `run_python` call
+    #[pyo3(get, set)]
+    #[serde(default = "default_code_timeout_secs")]
+    pub code_timeout_secs: u64,
+    /// Most trajectory points replayed into each model prompt, counting the
+    /// original task, which is always kept (0 replays the whole history)
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub prompt_history_window: usize,
+}
+
+fn default_max_fetch_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_search_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_search_cache_size() -> usize {
+    128
+}
+
+fn default_max_tokens() -> u32 {
+    2048
+}
+
+fn default_code_timeout_secs() -> u64 {
+    5
 }
 
 #[pymethods]
@@ -74,6 +131,15 @@ impl Default for CogOpsConfig {
                 2. Use calculate for any math\n\
                 3. Call finish(answer) when you have the final answer\n\
                 4. DO NOT say 'I cannot access real-time data' - use the tools!".to_string(),
+            allowed_url_hosts: Vec::new(),
+            max_fetch_bytes: default_max_fetch_bytes(),
+            search_cache_ttl_secs: default_search_cache_ttl_secs(),
+            search_cache_size: default_search_cache_size(),
+            temperature: None,
+            max_tokens: default_max_tokens(),
+            allow_code_execution: false,
+            code_timeout_secs: default_code_timeout_secs(),
+            prompt_history_window: 0,
         }
     }
 }
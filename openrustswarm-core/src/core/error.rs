@@ -0,0 +1,115 @@
+//! Structured failures for agent task execution
+//!
+//! `AgentRunError` is what `AgentGraph::run_task` returns on failure. Each
+//! variant maps to its own Python exception (all subclasses of
+//! `AgentRunFailed`, itself a `RuntimeError`), so retry logic can branch on
+//! the cause.
+
+use pyo3::create_exception;
+use pyo3::prelude::*;
+
+create_exception!(
+    cogops_core,
+    AgentRunFailed,
+    pyo3::exceptions::PyRuntimeError,
+    "A task run failed"
+);
+create_exception!(
+    cogops_core,
+    MissingApiKeyError,
+    AgentRunFailed,
+    "MODEL_API_KEY is not set"
+);
+create_exception!(
+    cogops_core,
+    ModelsExhaustedError,
+    AgentRunFailed,
+    "Every fallback model refused or failed the request"
+);
+create_exception!(
+    cogops_core,
+    AgentTimeoutError,
+    AgentRunFailed,
+    "Model requests timed out"
+);
+create_exception!(
+    cogops_core,
+    AgentNetworkError,
+    AgentRunFailed,
+    "Model endpoint unreachable"
+);
+create_exception!(
+    cogops_core,
+    MiddlewareError,
+    AgentRunFailed,
+    "A middleware hook failed"
+);
+
+/// Result type for task runs
+pub type AgentRunResult<T> = Result<T, AgentRunError>;
+
+/// Task run error types
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentRunError {
+    /// `MODEL_API_KEY` is not set
+    MissingApiKey,
+    /// Every fallback model answered with an error (quota, unsupported, ...)
+    AllModelsExhausted,
+    /// No model answered before the request timeout
+    Timeout,
+    /// No model could be reached
+    Network(String),
+    /// A before/after middleware hook returned an error
+    Middleware(String),
+}
+
+impl std::fmt::Display for AgentRunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentRunError::MissingApiKey => write!(f, "MODEL_API_KEY not found"),
+            AgentRunError::AllModelsExhausted => write!(f, "All models exhausted or failed"),
+            AgentRunError::Timeout => write!(f, "Model request timed out"),
+            AgentRunError::Network(msg) => write!(f, "Network error: {}", msg),
+            AgentRunError::Middleware(msg) => write!(f, "Middleware error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AgentRunError {}
+
+impl From<AgentRunError> for PyErr {
+    fn from(err: AgentRunError) -> PyErr {
+        let msg = err.to_string();
+        match err {
+            AgentRunError::MissingApiKey => MissingApiKeyError::new_err(msg),
+            AgentRunError::AllModelsExhausted => ModelsExhaustedError::new_err(msg),
+            AgentRunError::Timeout => AgentTimeoutError::new_err(msg),
+            AgentRunError::Network(_) => AgentNetworkError::new_err(msg),
+            AgentRunError::Middleware(_) => MiddlewareError::new_err(msg),
+        }
+    }
+}
+
+/// Register the exception hierarchy on the Python module
+pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("AgentRunFailed", py.get_type_bound::<AgentRunFailed>())?;
+    m.add(
+        "MissingApiKeyError",
+        py.get_type_bound::<MissingApiKeyError>(),
+    )?;
+    m.add(
+        "ModelsExhaustedError",
+        py.get_type_bound::<ModelsExhaustedError>(),
+    )?;
+    m.add(
+        "AgentTimeoutError",
+        py.get_type_bound::<AgentTimeoutError>(),
+    )?;
+    m.add(
+        "AgentNetworkError",
+        py.get_type_bound::<AgentNetworkError>(),
+    )?;
+    m.add("MiddlewareError", py.get_type_bound::<MiddlewareError>())?;
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::TrajectoryPoint;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Shared state passed through the CogOps middleware pipeline.
 #[pyclass]
@@ -19,6 +19,9 @@ pub struct CogOpsContext {
     /// Final answer from finish() tool - indicates successful completion
     #[pyo3(get, set)]
     pub final_answer: Option<String>,
+    /// Structured final answer from finish_json() tool, as a JSON object string
+    #[pyo3(get, set)]
+    pub final_answer_json: Option<String>,
     /// Trajectory stored as JSON for Python interop
     trajectory: Arc<RwLock<Vec<TrajectoryPoint>>>,
     /// Metadata as JSON string
@@ -36,6 +39,7 @@ impl CogOpsContext {
             should_stop: false,
             stop_reason: None,
             final_answer: None,
+            final_answer_json: None,
             trajectory: Arc::new(RwLock::new(Vec::new())),
             metadata_json: "{}".to_string(),
         }
@@ -55,6 +59,25 @@ impl CogOpsContext {
         let traj = self.trajectory.read();
         traj.len()
     }
+
+    /// Replaces the system prompt (agent instructions) sent to the model.
+    pub fn set_system_prompt(&mut self, prompt: String) {
+        self.prompt = prompt;
+    }
+
+    /// Halts the task before (or after) the next model call with the given reason.
+    pub fn set_stop(&mut self, reason: String) {
+        self.should_stop = true;
+        self.stop_reason = Some(reason);
+    }
+}
+
+impl CogOpsContext {
+    /// Trajectory points recorded at or after index `start`.
+    pub fn trajectory_since(&self, start: usize) -> Vec<TrajectoryPoint> {
+        let traj = self.trajectory.read();
+        traj.iter().skip(start).cloned().collect()
+    }
 }
 
 /// A "Plugin" that hooks into the agent's lifecycle.
@@ -78,6 +101,70 @@ pub trait Middleware: Send + Sync {
     }
 }
 
+/// Hook invoked with the live context; an `Err` stops the task with that reason.
+pub type ContextHook = Box<dyn Fn(&mut CogOpsContext) -> Result<(), String> + Send + Sync>;
+
+/// Middleware assembled from optional before/after hooks.
+/// Used to attach Python callables to the pipeline.
+pub struct HookMiddleware {
+    name: String,
+    before: Option<ContextHook>,
+    after: Option<ContextHook>,
+}
+
+impl HookMiddleware {
+    pub fn new(name: String, before: Option<ContextHook>, after: Option<ContextHook>) -> Self {
+        HookMiddleware {
+            name,
+            before,
+            after,
+        }
+    }
+
+    /// Wraps Python callables taking a `CogOpsContext`; changes the callable
+    /// makes to the context are copied back after it returns.
+    pub fn from_python(name: String, before: Option<PyObject>, after: Option<PyObject>) -> Self {
+        Self::new(name, before.map(python_hook), after.map(python_hook))
+    }
+
+    fn run(&self, hook: &Option<ContextHook>, stage: &str, ctx: &mut CogOpsContext) {
+        if let Some(hook) = hook {
+            if let Err(e) = hook(ctx) {
+                warn!("🔌 [Pipeline] {} {} hook failed: {}", self.name, stage, e);
+                ctx.should_stop = true;
+                ctx.stop_reason = Some(format!("{} {} hook failed: {}", self.name, stage, e));
+            }
+        }
+    }
+}
+
+fn python_hook(callable: PyObject) -> ContextHook {
+    Box::new(move |ctx| {
+        Python::with_gil(|py| {
+            let view = Py::new(py, ctx.clone()).map_err(|e| e.to_string())?;
+            let result = callable.call1(py, (view.clone_ref(py),));
+            *ctx = view.borrow(py).clone();
+            result.map(|_| ()).map_err(|e| e.to_string())
+        })
+    })
+}
+
+impl Middleware for HookMiddleware {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn before_step(&self, ctx: &mut CogOpsContext) -> Result<(), String> {
+        self.run(&self.before, "before", ctx);
+        Ok(())
+    }
+
+    fn after_step(&self, ctx: &mut CogOpsContext) -> Result<(), String> {
+        self.run(&self.after, "after", ctx);
+        Ok(())
+    }
+}
+
 /// Container for middleware instances
 pub struct MiddlewarePipeline {
     middlewares: Vec<Box<dyn Middleware>>,
@@ -1,15 +1,18 @@
+use crate::compliance::ratelimit::{RateLimitConfig, RateLimiter};
 use crate::core::agent::{Agent, AgentRegistry};
 use crate::core::config::CogOpsConfig;
-use crate::core::middleware::{CogOpsContext, Middleware, MiddlewarePipeline};
+use crate::core::error::{AgentRunError, AgentRunResult};
+use crate::core::middleware::{CogOpsContext, HookMiddleware, Middleware, MiddlewarePipeline};
 use crate::core::tools::{execute_tool, get_tool_definitions, ToolResult};
 use crate::{HistoryBuffer, TrajectoryPoint};
 use pyo3::prelude::*;
+use pyo3::types::{PyCFunction, PyDict, PyTuple};
 use serde_json::json;
 use std::sync::{Arc, Mutex, OnceLock};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tokio::runtime::Runtime;
 use tokio::task::AbortHandle;
-use tracing::info;
+use tracing::{info, warn};
 
 // Prevent OS-level thread and socket exhaustion by sharing the core async I/O drivers
 use std::env;
@@ -38,6 +41,100 @@ fn get_shared_client() -> reqwest::Client {
     }).clone()
 }
 
+/// Returns true when the last `window` tool calls in `points` are identical.
+/// A window of 0 disables detection.
+fn is_stuck(points: &[TrajectoryPoint], window: usize) -> bool {
+    if window == 0 {
+        return false;
+    }
+    let calls: Vec<&str> = points
+        .iter()
+        .filter(|p| p.action == "ToolCall")
+        .map(|p| p.thought.as_str())
+        .collect();
+    calls.len() >= window && calls[calls.len() - window..].windows(2).all(|w| w[0] == w[1])
+}
+
+/// The points replayed into a prompt: the original `Task` plus the most recent
+/// points, `window` in total. A window of 0 keeps everything.
+fn prompt_history(points: &[TrajectoryPoint], window: usize) -> Vec<&TrajectoryPoint> {
+    if window == 0 || points.len() <= window {
+        return points.iter().collect();
+    }
+    match points.iter().position(|p| p.action == "Task") {
+        Some(task) => {
+            let tail_start = (points.len() - (window - 1)).max(task + 1);
+            std::iter::once(&points[task])
+                .chain(&points[tail_start..])
+                .collect()
+        }
+        None => points[points.len() - window..].iter().collect(),
+    }
+}
+
+/// Lifecycle state of a task started with `spawn_task`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Killed,
+    Failed,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Running => "Running",
+            TaskStatus::Completed => "Completed",
+            TaskStatus::Killed => "Killed",
+            TaskStatus::Failed => "Failed",
+        }
+    }
+}
+
+/// Finished task statuses kept for `task_status`; the oldest are dropped first.
+const MAX_FINISHED_STATUSES: usize = 1024;
+
+/// Status of every running task plus the most recently finished ones.
+#[derive(Default)]
+struct TaskStatuses {
+    statuses: HashMap<String, TaskStatus>,
+    /// Finished task IDs, oldest first
+    finished: VecDeque<String>,
+}
+
+impl TaskStatuses {
+    fn start(&mut self, task_id: String) {
+        self.finished.retain(|id| *id != task_id);
+        self.statuses.insert(task_id, TaskStatus::Running);
+    }
+
+    /// Moves a running task to `status`, evicting the oldest finished entries
+    /// past `MAX_FINISHED_STATUSES`. Tasks that are not running are left alone.
+    fn finish(&mut self, task_id: &str, status: TaskStatus) {
+        match self.statuses.get_mut(task_id) {
+            Some(current) if *current == TaskStatus::Running => *current = status,
+            _ => return,
+        }
+        self.finished.push_back(task_id.to_string());
+        while self.finished.len() > MAX_FINISHED_STATUSES {
+            if let Some(oldest) = self.finished.pop_front() {
+                self.statuses.remove(&oldest);
+            }
+        }
+    }
+
+    fn get(&self, task_id: &str) -> Option<TaskStatus> {
+        self.statuses.get(task_id).copied()
+    }
+}
+
+/// A spawned task's abort handle and the caller's buffer it writes into.
+struct RunningTask {
+    abort: AbortHandle,
+    buffer: HistoryBuffer,
+}
+
 /// Orchestrates the lifecycle of an AI agent within the middleware pipeline.
 ///
 /// `AgentGraph` manages agent registration, middleware injection, and the
@@ -48,7 +145,9 @@ pub struct AgentGraph {
     registry: AgentRegistry,
     pub runtime: Arc<Runtime>,
     client: reqwest::Client,
-    active_tasks: Arc<Mutex<HashMap<String, AbortHandle>>>,
+    active_tasks: Arc<Mutex<HashMap<String, RunningTask>>>,
+    task_statuses: Arc<Mutex<TaskStatuses>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl AgentGraph {
@@ -60,6 +159,8 @@ impl AgentGraph {
             runtime: get_shared_runtime(),
             client: get_shared_client(),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
+            task_statuses: Arc::new(Mutex::new(TaskStatuses::default())),
+            rate_limiter: None,
         }
     }
 
@@ -72,6 +173,8 @@ impl AgentGraph {
             runtime: get_shared_runtime(),
             client: get_shared_client(),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
+            task_statuses: Arc::new(Mutex::new(TaskStatuses::default())),
+            rate_limiter: None,
         }
     }
 
@@ -80,6 +183,12 @@ impl AgentGraph {
         self.registry.register(agent);
     }
 
+    /// Charges each model response's token usage to the agent's budget in `limiter`
+    /// and stops the run before the next model call once it is spent.
+    pub fn set_rate_limiter(&mut self, limiter: Arc<RateLimiter>) {
+        self.rate_limiter = Some(limiter);
+    }
+
     /// Attaches a middleware layer to the execution pipeline.
     pub fn use_middleware(&mut self, middleware: Box<dyn Middleware>) {
         self.pipeline.add(middleware);
@@ -91,14 +200,17 @@ impl AgentGraph {
     /// 1. Send task + history to model with tool definitions
     /// 2. Model either returns text OR requests a tool call
     /// 3. If tool call: execute tool, add observation to history, repeat
-    /// 4. If finish() called: return success with final answer
-    /// 5. Max 10 iterations to prevent infinite loops
+    /// 4. If finish() or finish_json() called: return success with final answer
+    /// 5. Max 15 iterations to prevent infinite loops
+    /// 6. Stop early when the last `loop_detection_window` tool calls are identical
+    ///
+    /// Failures are classified as an `AgentRunError` so callers can branch on the cause.
     pub async fn run_task(
         &self,
         task_id: &str,
         buffer: &HistoryBuffer,
         agent_name: Option<&str>,
-    ) -> Result<CogOpsContext, String> {
+    ) -> AgentRunResult<CogOpsContext> {
         let display_name = agent_name.unwrap_or("DefaultAgent");
         info!(
             "▶️ [AgentGraph] Starting Task: {} (Agent: {})",
@@ -106,8 +218,9 @@ impl AgentGraph {
         );
 
         // Initialize Context
-        let prompt = agent_name
-            .and_then(|n| self.registry.get(n))
+        let agent = agent_name.and_then(|n| self.registry.get(n));
+        let prompt = agent
+            .as_ref()
             .map(|a| a.instructions.clone())
             .unwrap_or_else(|| "You are a helpful research assistant. Use the provided tools to find real information and answer questions accurately.".to_string());
 
@@ -120,23 +233,31 @@ impl AgentGraph {
 
         // STEP 1: Pre-Execution Hooks
         info!("🔄 [AgentGraph] Stage: Pre-Step Hooks ({})", display_name);
-        self.pipeline.run_before(&mut ctx)?;
+        let seeded_len = ctx.trajectory_len();
+        self.pipeline
+            .run_before(&mut ctx)
+            .map_err(AgentRunError::Middleware)?;
 
         if ctx.should_stop {
             info!("⏹️ [AgentGraph] Stopped: {:?}", ctx.stop_reason);
             return Ok(ctx);
         }
 
+        // Middleware may rewrite the prompt or inject trajectory points
+        let prompt = ctx.prompt.clone();
+        for point in ctx.trajectory_since(seeded_len) {
+            buffer.add(point);
+        }
+
         // STEP 2: ReAct Loop with Tool Use
         info!(" [AgentGraph] Stage: ReAct Loop with Tool Use");
 
-        let api_key =
-            env::var("MODEL_API_KEY").map_err(|_| "MODEL_API_KEY not found".to_string())?;
+        let api_key = env::var("MODEL_API_KEY").map_err(|_| AgentRunError::MissingApiKey)?;
 
         // Model fallback list - Gemma models first (have quota), Gemini as backup
         // Model fallback list - User requested gemini-2.5-flash
         // Model fallback list - Optimized based on live quota (gemini-3-flash has 0 usage)
-        let fallback_models = vec![
+        let mut fallback_models = vec![
             "gemini-2.0-flash", // Per User Request
             "gemma-3-27b-it", // High Quota (30 RPM) - Primary
             "gemma-3-12b-it", // High Quota (30 RPM)
@@ -145,17 +266,53 @@ impl AgentGraph {
             "gemini-2.5-flash",
         ];
 
+        // Per-agent overrides: preferred model first, then sampling settings
+        if let Some(model) = agent.as_ref().and_then(|a| a.model.as_deref()) {
+            fallback_models.retain(|m| *m != model);
+            fallback_models.insert(0, model);
+        }
+        let max_tokens = agent
+            .as_ref()
+            .and_then(|a| a.max_tokens)
+            .unwrap_or(self.config.max_tokens);
+        let temperature = agent
+            .as_ref()
+            .and_then(|a| a.temperature)
+            .or(self.config.temperature);
+        let mut generation_config = json!({ "maxOutputTokens": max_tokens });
+        if let Some(t) = temperature {
+            generation_config["temperature"] = json!(t);
+        }
+
         let base_url = env::var("MODEL_BASE_URL")
             .unwrap_or_else(|_| "https://generativelanguage.googleapis.com/v1beta/models".to_string());
         let tool_defs = get_tool_definitions();
 
         let max_iterations = 15;
+        let loop_window = self.config.introspection.loop_detection_window;
+        let run_start = buffer.len();
         let mut final_answer: Option<String> = None;
+        let mut final_answer_json: Option<String> = None;
         let mut step_num = buffer.len() as u32 + 1;
 
         for iteration in 0..max_iterations {
             info!("   [ReAct] Iteration {}/{}", iteration + 1, max_iterations);
 
+            // Stop before calling the model once the token budget is spent
+            if let Some(limiter) = &self.rate_limiter {
+                let check = limiter.check_token_budget(display_name.to_string());
+                if !check.allowed {
+                    info!("   [ReAct] {}, stopping", check.reason);
+                    buffer.add(TrajectoryPoint::new(
+                        step_num,
+                        "Thought".to_string(),
+                        check.reason,
+                    ));
+                    ctx.stop_reason = Some("token_budget_exceeded".to_string());
+                    break;
+                }
+            }
+
             // Build conversation history
             let mut contents = Vec::new();
 
@@ -173,8 +330,9 @@ impl AgentGraph {
                 "parts": [{"text": "I will use the available tools to find real information and provide accurate answers."}]
             }));
 
-            // Add all history points
-            for point in buffer.get_raw() {
+            // Add history points, bounded by the prompt window
+            let history = buffer.get_raw();
+            for point in prompt_history(&history, self.config.prompt_history_window) {
                 let role = match point.action.as_str() {
                     "Task" | "User" | "Observation" | "ToolResult" => "user",
                     _ => "model",
@@ -191,6 +349,9 @@ impl AgentGraph {
 
             // Try models with fallback
             let mut response_json: Option<serde_json::Value> = None;
+            // Tell "every model refused" apart from "no model was reachable"
+            let mut transport_error: Option<reqwest::Error> = None;
+            let mut any_http_response = false;
 
             for model in &fallback_models {
                 let url = format!("{}/{}:generateContent?key={}", base_url, model, api_key);
@@ -203,7 +364,7 @@ impl AgentGraph {
                     gemma_contents.push(json!({
                         "role": "user",
                         "parts": [{"text": format!(
-                            "You have access to these tools: web_search(query), calculate(expression), finish(answer).\n\
+                            "You have access to these tools: web_search(query), calculate(expression), finish(answer), finish_json(answer).\n\
                             To use a tool, respond ONLY with a JSON object like:\n\
                             {{\"tool\": \"web_search\", \"args\": {{\"query\": \"NVIDIA stock price\"}}}}\n\
                             When you have the final answer, use:\n\
@@ -213,23 +374,20 @@ impl AgentGraph {
                     }));
                     json!({
                         "contents": gemma_contents,
-                        "generationConfig": {
-                            "maxOutputTokens": 2048
-                        }
+                        "generationConfig": generation_config.clone()
                     })
                 } else {
                     // Function calling for Gemini models
                     json!({
                         "contents": contents,
                         "tools": [tool_defs],
-                        "generationConfig": {
-                            "maxOutputTokens": 2048
-                        }
+                        "generationConfig": generation_config.clone()
                     })
                 };
 
                 match self.client.post(&url).json(&body).send().await {
                     Ok(resp) => {
+                        any_http_response = true;
                         if resp.status().is_success() {
                             if let Ok(json) = resp.json::<serde_json::Value>().await {
                                 response_json = Some(json);
@@ -254,7 +412,10 @@ impl AgentGraph {
                             }
                         }
                     }
-                    Err(e) => info!("   [ReAct] Request failed for {}: {}", model, e),
+                    Err(e) => {
+                        info!("   [ReAct] Request failed for {}: {}", model, e);
+                        transport_error = Some(e);
+                    }
                 }
 
                 if response_json.is_some() {
@@ -262,7 +423,27 @@ impl AgentGraph {
                 }
             }
 
-            let response = response_json.ok_or("All models exhausted or failed")?;
+            let response = match (response_json, transport_error) {
+                (Some(response), _) => response,
+                (None, Some(e)) if !any_http_response => {
+                    return Err(if e.is_timeout() {
+                        AgentRunError::Timeout
+                    } else {
+                        AgentRunError::Network(e.to_string())
+                    });
+                }
+                (None, _) => return Err(AgentRunError::AllModelsExhausted),
+            };
+
+            // Charge the response's actual token usage, even past the budget;
+            // the overage blocks the next call
+            if let Some(limiter) = &self.rate_limiter {
+                let tokens = response["usageMetadata"]["totalTokenCount"]
+                    .as_u64()
+                    .unwrap_or(0)
+                    .min(u32::MAX as u64) as u32;
+                limiter.record_tokens(display_name.to_string(), tokens);
+            }
 
             // Parse response - check for function calls
             let candidate = &response["candidates"][0];
@@ -289,7 +470,7 @@ impl AgentGraph {
                         step_num += 1;
 
                         // Execute the tool
-                        let result = execute_tool(&self.client, func_name, func_args).await;
+                        let result = execute_tool(&self.client, &self.config, func_name, func_args).await;
 
                         match &result {
                             ToolResult::Success(output) => {
@@ -307,8 +488,11 @@ impl AgentGraph {
                                 step_num += 1;
 
                                 // Check if this was the finish() tool
-                                if func_name == "finish" {
+                                if func_name == "finish" || func_name == "finish_json" {
                                     final_answer = Some(output.clone());
+                                    if func_name == "finish_json" {
+                                        final_answer_json = Some(output.clone());
+                                    }
                                     info!("   [ReAct] 🏁 Task completed with answer!");
                                 }
                             }
@@ -350,7 +534,7 @@ impl AgentGraph {
                                     step_num += 1;
 
                                     // Execute the tool
-                                    let result = execute_tool(&self.client, tool_name, &args).await;
+                                    let result = execute_tool(&self.client, &self.config, tool_name, &args).await;
 
                                     match &result {
                                         ToolResult::Success(output) => {
@@ -366,8 +550,11 @@ impl AgentGraph {
                                             ));
                                             step_num += 1;
 
-                                            if tool_name == "finish" {
+                                            if tool_name == "finish" || tool_name == "finish_json" {
                                                 final_answer = Some(output.clone());
+                                                if tool_name == "finish_json" {
+                                                    final_answer_json = Some(output.clone());
+                                                }
                                                 info!("   [ReAct] 🏁 Task completed with answer!");
                                             }
                                         }
@@ -405,11 +592,24 @@ impl AgentGraph {
             if final_answer.is_some() {
                 break;
             }
+
+            // Stuck detection: the agent keeps issuing the same tool call
+            if is_stuck(&buffer.get_raw()[run_start..], loop_window) {
+                info!("   [ReAct] Detected repeated tool call, stopping");
+                buffer.add(TrajectoryPoint::new(
+                    step_num,
+                    "Thought".to_string(),
+                    "Detected repeated tool call, stopping".to_string(),
+                ));
+                ctx.stop_reason = Some("loop_detected".to_string());
+                break;
+            }
         }
 
         // Update context with final result
         if let Some(answer) = &final_answer {
             ctx.final_answer = Some(answer.clone());
+            ctx.final_answer_json = final_answer_json;
             if let Some(last) = buffer.last() {
                 ctx.add_trajectory_point(last);
             }
@@ -423,7 +623,9 @@ impl AgentGraph {
 
         // STEP 3: Post-Execution Hooks
         info!("🔄 [AgentGraph] Stage: Post-Step Hooks");
-        self.pipeline.run_after(&mut ctx)?;
+        self.pipeline
+            .run_after(&mut ctx)
+            .map_err(AgentRunError::Middleware)?;
 
         info!("[AgentGraph] ReAct Loop Complete.");
         Ok(ctx)
@@ -431,9 +633,28 @@ impl AgentGraph {
 }
 
 /// Python-accessible wrapper for the synchronous `AgentGraph`.
+///
+/// The graph is shared with in-flight async tasks, so registration
+/// (agents, middleware) must happen before tasks are started.
 #[pyclass(name = "AgentGraphPy")]
 pub struct AgentGraphPy {
-    inner: AgentGraph,
+    inner: Arc<AgentGraph>,
+}
+
+impl AgentGraphPy {
+    /// Mutable access to the graph; fails while async tasks still hold a reference.
+    fn graph_mut(&mut self) -> PyResult<&mut AgentGraph> {
+        Arc::get_mut(&mut self.inner).ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err(
+                "AgentGraph cannot be modified while async tasks are running",
+            )
+        })
+    }
+
+    /// Shared handle to the graph, for workflows that spawn concurrent tasks.
+    pub(crate) fn shared(&self) -> Arc<AgentGraph> {
+        self.inner.clone()
+    }
 }
 
 #[pymethods]
@@ -445,15 +666,44 @@ impl AgentGraphPy {
             Some(c) => AgentGraph::with_config(c),
             None => AgentGraph::new(),
         };
-        AgentGraphPy { inner }
+        AgentGraphPy {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Enforces `config.tokens_per_minute` per agent using each model response's usage.
+    pub fn set_rate_limit(&mut self, config: RateLimitConfig) -> PyResult<()> {
+        self.graph_mut()?
+            .set_rate_limiter(Arc::new(RateLimiter::new(Some(config))));
+        Ok(())
     }
 
     /// Registers a new `Agent` persona.
-    pub fn register_agent(&mut self, agent: Agent) {
-        self.inner.register_agent(agent);
+    pub fn register_agent(&mut self, agent: Agent) -> PyResult<()> {
+        self.graph_mut()?.register_agent(agent);
+        Ok(())
+    }
+
+    /// Attaches Python before/after hooks as middleware. Each hook is called
+    /// with the task's `CogOpsContext` and may modify it; raising an exception
+    /// stops the task with the exception message as `stop_reason`.
+    #[pyo3(signature = (name, before = None, after = None))]
+    pub fn add_python_middleware(
+        &mut self,
+        name: String,
+        before: Option<PyObject>,
+        after: Option<PyObject>,
+    ) -> PyResult<()> {
+        self.graph_mut()?
+            .use_middleware(Box::new(HookMiddleware::from_python(name, before, after)));
+        Ok(())
     }
 
     /// Initiates a task execution cycle with ReAct loop.
+    ///
+    /// Raises a subclass of `AgentRunFailed` (itself a `RuntimeError`):
+    /// `MissingApiKeyError`, `ModelsExhaustedError`, `AgentTimeoutError`,
+    /// `AgentNetworkError` or `MiddlewareError`.
     #[pyo3(signature = (task_id, buffer, agent_name = None))]
     pub fn run_task(
         &self,
@@ -463,7 +713,74 @@ impl AgentGraphPy {
     ) -> PyResult<CogOpsContext> {
         self.inner.runtime.block_on(async {
             self.inner.run_task(&task_id, buffer, agent_name.as_deref()).await
-        }).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+        }).map_err(PyErr::from)
+    }
+
+    /// Runs a task on the shared tokio runtime and returns an asyncio awaitable
+    /// resolving to the final `CogOpsContext`.
+    ///
+    /// Must be called from a running asyncio event loop (e.g. inside a FastAPI
+    /// handler); the result is delivered back via `call_soon_threadsafe`, so the
+    /// loop is never blocked. Task failures raise the same `AgentRunFailed`
+    /// subclasses as `run_task` when awaited.
+    #[pyo3(signature = (task_id, buffer, agent_name = None))]
+    pub fn run_task_async<'py>(
+        &self,
+        py: Python<'py>,
+        task_id: String,
+        buffer: &HistoryBuffer,
+        agent_name: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import_bound("asyncio")?.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+
+        let loop_ref: PyObject = event_loop.unbind();
+        let future_ref: PyObject = future.clone().unbind();
+        let graph = self.inner.clone();
+        let buf = buffer.clone();
+
+        self.inner.runtime.spawn(async move {
+            let result = graph.run_task(&task_id, &buf, agent_name.as_deref()).await;
+
+            Python::with_gil(|py| {
+                let (method, value) = match result {
+                    Ok(ctx) => ("set_result", ctx.into_py(py)),
+                    Err(e) => (
+                        "set_exception",
+                        PyErr::from(e)
+                            .value_bound(py)
+                            .clone()
+                            .into_any()
+                            .unbind(),
+                    ),
+                };
+                // The done-check runs on the loop thread together with the set,
+                // so a cancellation cannot land between them
+                let deliver = PyCFunction::new_closure_bound(
+                    py,
+                    None,
+                    None,
+                    move |args: &Bound<'_, PyTuple>,
+                          _kwargs: Option<&Bound<'_, PyDict>>|
+                          -> PyResult<()> {
+                        let py = args.py();
+                        if future_ref.call_method0(py, "done")?.extract::<bool>(py)? {
+                            return Ok(());
+                        }
+                        future_ref.call_method1(py, method, (value.clone_ref(py),))?;
+                        Ok(())
+                    },
+                );
+                let delivered = deliver.and_then(|callback| {
+                    loop_ref.call_method1(py, "call_soon_threadsafe", (callback,))
+                });
+                if let Err(e) = delivered {
+                    warn!("[AgentGraph] Could not deliver async result for {}: {}", task_id, e);
+                }
+            });
+        });
+
+        Ok(future)
     }
 
     /// Spawns a background task execution cycle with ReAct loop (Non-Blocking).
@@ -476,44 +793,78 @@ impl AgentGraphPy {
         agent_name: Option<String>,
     ) -> PyResult<()> {
         let task_name = task_id.clone();
-        let agent = agent_name.clone();
+        // Arc-backed clone: the task writes straight into the caller's buffer
         let buf = buffer.clone();
-        
-        let config = self.inner.config.clone();
+
+        let graph = self.inner.clone();
         let tasks_map = self.inner.active_tasks.clone();
-        let handle_task_id = task_id.clone();
-        
+        let statuses = self.inner.task_statuses.clone();
+
+        // Hold the map lock across the spawn so a task that finishes instantly
+        // (e.g. auth error) cannot run its cleanup before its entry is inserted.
+        let mut map = self.inner.active_tasks.lock().unwrap();
+
         // Spawn onto the existing tokio thread pool as a lightweight Future
         // preventing OS-level Thread Exhaustion (os error 11)
         let handle = self.inner.runtime.spawn(async move {
-            let inner_graph = crate::core::runner::AgentGraph::with_config(config);
-            let _ = inner_graph.run_task(&task_name, &buf, agent.as_deref()).await;
-            
-            // Cleanup on finish
+            let result = graph.run_task(&task_name, &buf, agent_name.as_deref()).await;
+
+            let final_status = if result.is_ok() {
+                TaskStatus::Completed
+            } else {
+                TaskStatus::Failed
+            };
+
+            // Cleanup on finish (lock order: tasks map, then statuses)
             let mut map = tasks_map.lock().unwrap();
+            statuses.lock().unwrap().finish(&task_name, final_status);
             map.remove(&task_name);
         });
-        
-        {
-            let mut map = self.inner.active_tasks.lock().unwrap();
-            // Prevent race condition: if future already finished instantly (e.g. auth error), don't resurrect its ID.
-            if !handle.is_finished() {
-                map.insert(handle_task_id, handle.abort_handle());
-            }
-        }
-        
+
+        self.inner
+            .task_statuses
+            .lock()
+            .unwrap()
+            .start(task_id.clone());
+        map.insert(
+            task_id,
+            RunningTask {
+                abort: handle.abort_handle(),
+                buffer: buffer.clone(),
+            },
+        );
+
         Ok(())
     }
 
-    /// Hard kills a task mid-flight, aborting the async tokio future instantly. 
+    /// Hard kills a task mid-flight, aborting the async tokio future instantly.
     /// This causes the agent to "die" without warning, preventing further tool calls or LLM requests.
     pub fn kill_task(&self, task_id: String) -> PyResult<bool> {
-        let mut map = self.inner.active_tasks.lock().unwrap();
-        if let Some(abort_handle) = map.remove(&task_id) {
-            abort_handle.abort();
-            return Ok(true);
-        }
-        Ok(false)
+        Ok(self.kill_task_with_trajectory(task_id).is_some())
+    }
+
+    /// Kills a task like `kill_task` and returns the partial trajectory it had
+    /// written to its buffer, or `None` if no task with this ID is running.
+    pub fn kill_task_with_trajectory(&self, task_id: String) -> Option<Vec<TrajectoryPoint>> {
+        let task = self.inner.active_tasks.lock().unwrap().remove(&task_id)?;
+        task.abort.abort();
+        self.inner
+            .task_statuses
+            .lock()
+            .unwrap()
+            .finish(&task_id, TaskStatus::Killed);
+        Some(task.buffer.get_raw())
+    }
+
+    /// Returns "Running", "Completed", "Killed" or "Failed" for a task started
+    /// with `spawn_task`, or `None` if the ID is unknown.
+    pub fn task_status(&self, task_id: String) -> Option<String> {
+        self.inner
+            .task_statuses
+            .lock()
+            .unwrap()
+            .get(&task_id)
+            .map(|s| s.as_str().to_string())
     }
     
     /// Returns the number of currently active task futures.
@@ -522,3 +873,468 @@ impl AgentGraphPy {
         map.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::{AgentRunFailed, MissingApiKeyError, ModelsExhaustedError};
+    use crate::core::middleware::ContextHook;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    // Tests below point MODEL_BASE_URL at a local mock, so they must not overlap
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn point(action: &str, thought: &str) -> TrajectoryPoint {
+        TrajectoryPoint::new(1, action.to_string(), thought.to_string())
+    }
+
+    /// Reads one HTTP request, returning it raw (request line, headers and body).
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut data = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = match stream.read(&mut chunk) {
+                Ok(0) | Err(_) => return String::from_utf8_lossy(&data).into_owned(),
+                Ok(n) => n,
+            };
+            data.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                let headers = String::from_utf8_lossy(&data[..pos]).to_lowercase();
+                let body_len = headers
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if data.len() >= pos + 4 + body_len {
+                    return String::from_utf8_lossy(&data).into_owned();
+                }
+            }
+        }
+    }
+
+    /// Serves the same model response to every request, recording each raw request.
+    fn spawn_mock_model(response: serde_json::Value) -> (String, Arc<Mutex<Vec<String>>>) {
+        spawn_slow_mock_model(response, usize::MAX, std::time::Duration::ZERO)
+    }
+
+    /// Like `spawn_mock_model`, but stalls for `delay` before answering request
+    /// number `slow_from` (0-based) and every request after it.
+    fn spawn_slow_mock_model(
+        response: serde_json::Value,
+        slow_from: usize,
+        delay: std::time::Duration,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let body = response.to_string();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let request = read_request(&mut stream);
+                let index = {
+                    let mut recorded = recorded.lock().unwrap();
+                    recorded.push(request);
+                    recorded.len() - 1
+                };
+                if index >= slow_from {
+                    std::thread::sleep(delay);
+                }
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(reply.as_bytes());
+            }
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    #[test]
+    fn test_is_stuck_window() {
+        let points = vec![
+            point("ToolCall", "calculate(1+1)"),
+            point("Observation", "Result: 2"),
+            point("ToolCall", "calculate(1+1)"),
+            point("Observation", "Result: 2"),
+        ];
+        assert!(is_stuck(&points, 2));
+        assert!(!is_stuck(&points, 3));
+        assert!(!is_stuck(&points, 0));
+
+        let varied = vec![point("ToolCall", "a()"), point("ToolCall", "b()")];
+        assert!(!is_stuck(&varied, 2));
+    }
+
+    #[test]
+    fn test_run_task_stops_on_repeated_tool_call() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "calculate", "args": {"expression": "1+1"}}}
+            ]}}]
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let graph = AgentGraph::new();
+        let buffer = HistoryBuffer::new();
+        let ctx = graph
+            .runtime
+            .block_on(graph.run_task("loop-test", &buffer, None))
+            .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(ctx.stop_reason.as_deref(), Some("loop_detected"));
+        assert_eq!(
+            buffer.last().unwrap().thought,
+            "Detected repeated tool call, stopping"
+        );
+    }
+
+    #[test]
+    fn test_run_task_stops_when_token_budget_exceeded() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [{"text": "thinking"}]}}],
+            "usageMetadata": {"totalTokenCount": 400}
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let mut graph = AgentGraph::new();
+        graph.set_rate_limiter(Arc::new(RateLimiter::new(Some(RateLimitConfig::new(
+            60, 1000, 1000,
+        )))));
+        let buffer = HistoryBuffer::new();
+        let ctx = graph
+            .runtime
+            .block_on(graph.run_task("budget-test", &buffer, None))
+            .unwrap();
+
+        // The third response overshoots 1000 tokens and is still charged,
+        // so the fourth call is never sent
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(ctx.stop_reason.as_deref(), Some("token_budget_exceeded"));
+        assert!(buffer.last().unwrap().thought.contains("Token limit exceeded"));
+    }
+
+    #[test]
+    fn test_run_task_populates_final_answer_json() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "finish_json", "args": {"answer": {"total": 42}}}}
+            ]}}]
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let graph = AgentGraph::new();
+        let buffer = HistoryBuffer::new();
+        let ctx = graph
+            .runtime
+            .block_on(graph.run_task("json-test", &buffer, None))
+            .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(ctx.final_answer_json.as_deref(), Some(r#"{"total":42}"#));
+        assert_eq!(ctx.final_answer, ctx.final_answer_json);
+    }
+
+    #[test]
+    fn test_prompt_history_window_keeps_task_and_tail() {
+        let mut points = vec![point("Task", "original question")];
+        points.extend((0..99).map(|i| point("Observation", &format!("obs {}", i))));
+
+        let kept = prompt_history(&points, 5);
+        assert_eq!(kept.len(), 5);
+        assert_eq!(kept[0].thought, "original question");
+        assert_eq!(kept[1].thought, "obs 95");
+        assert_eq!(kept[4].thought, "obs 98");
+
+        assert_eq!(prompt_history(&points, 0).len(), 100);
+        assert_eq!(prompt_history(&points[..3], 5).len(), 3);
+        let untasked = prompt_history(&points[1..], 4);
+        assert_eq!(untasked[0].thought, "obs 95");
+    }
+
+    #[test]
+    fn test_long_history_prompt_is_bounded() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "finish", "args": {"answer": "done"}}}
+            ]}}]
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let window = 10;
+        let graph = AgentGraph::with_config(CogOpsConfig {
+            prompt_history_window: window,
+            ..CogOpsConfig::default()
+        });
+        let buffer = HistoryBuffer::new();
+        buffer.add(point("Task", "original question"));
+        for i in 0..99 {
+            buffer.add(point("Observation", &format!("obs {}", i)));
+        }
+        graph
+            .runtime
+            .block_on(graph.run_task("window-test", &buffer, None))
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let raw = &requests[0];
+        let body_start = raw.find("\r\n\r\n").unwrap() + 4;
+        let body: serde_json::Value = serde_json::from_str(&raw[body_start..]).unwrap();
+        let contents = body["contents"].as_array().unwrap();
+        // System prompt and model acknowledgement, then the windowed history
+        assert_eq!(contents.len(), window + 2);
+        assert_eq!(contents[2]["parts"][0]["text"], "[Task] original question");
+        assert_eq!(contents[window + 1]["parts"][0]["text"], "[Observation] obs 98");
+    }
+
+    #[test]
+    fn test_agent_overrides_reach_request_body() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "finish", "args": {"answer": "done"}}}
+            ]}}]
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let mut graph = AgentGraph::new();
+        graph.register_agent(Agent::new(
+            "Explorer".to_string(),
+            "Be creative.".to_string(),
+            Some(0.2),
+            Some("custom-model".to_string()),
+            Some(512),
+        ));
+        let buffer = HistoryBuffer::new();
+        graph
+            .runtime
+            .block_on(graph.run_task("override-test", &buffer, Some("Explorer")))
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let raw = &requests[0];
+        assert!(raw.starts_with("POST /custom-model:generateContent"));
+
+        let body_start = raw.find("\r\n\r\n").unwrap() + 4;
+        let body: serde_json::Value = serde_json::from_str(&raw[body_start..]).unwrap();
+        let generation = &body["generationConfig"];
+        assert_eq!(generation["maxOutputTokens"], 512);
+        assert!((generation["temperature"].as_f64().unwrap() - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_kill_task_returns_partial_trajectory() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, _requests) = spawn_slow_mock_model(
+            json!({
+                "candidates": [{"content": {"parts": [
+                    {"functionCall": {"name": "calculate", "args": {"expression": "2*3"}}}
+                ]}}]
+            }),
+            1,
+            std::time::Duration::from_secs(10),
+        );
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let graph = AgentGraphPy::new(None);
+        let buffer = HistoryBuffer::new();
+        graph
+            .spawn_task("kill-test".to_string(), &buffer, None)
+            .unwrap();
+
+        // Wait for the first tool call + observation, then the task stalls on the model
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while buffer.len() < 2 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(graph.active_task_count(), 1);
+
+        let partial = graph
+            .kill_task_with_trajectory("kill-test".to_string())
+            .unwrap();
+        assert!(partial.len() >= 2);
+        assert_eq!(partial[0].action, "ToolCall");
+        assert_eq!(graph.active_task_count(), 0);
+        assert!(!graph.kill_task("kill-test".to_string()).unwrap());
+        assert_eq!(graph.task_status("kill-test".to_string()).as_deref(), Some("Killed"));
+    }
+
+    #[test]
+    fn test_finished_statuses_are_capped() {
+        let mut table = TaskStatuses::default();
+        table.start("long-runner".to_string());
+        for i in 0..MAX_FINISHED_STATUSES + 10 {
+            let id = format!("task-{}", i);
+            table.start(id.clone());
+            table.finish(&id, TaskStatus::Completed);
+        }
+        assert_eq!(table.statuses.len(), MAX_FINISHED_STATUSES + 1);
+        assert_eq!(table.get("task-0"), None);
+        assert_eq!(table.get("task-10"), Some(TaskStatus::Completed));
+        assert_eq!(table.get("long-runner"), Some(TaskStatus::Running));
+
+        // A finished status is never overwritten by a late cleanup
+        table.finish("task-10", TaskStatus::Failed);
+        assert_eq!(table.get("task-10"), Some(TaskStatus::Completed));
+    }
+
+    #[test]
+    fn test_task_status_running_to_completed() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, _requests) = spawn_slow_mock_model(
+            json!({
+                "candidates": [{"content": {"parts": [
+                    {"functionCall": {"name": "finish", "args": {"answer": "done"}}}
+                ]}}]
+            }),
+            0,
+            std::time::Duration::from_millis(500),
+        );
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let graph = AgentGraphPy::new(None);
+        assert!(graph.task_status("status-test".to_string()).is_none());
+        graph
+            .spawn_task("status-test".to_string(), &HistoryBuffer::new(), None)
+            .unwrap();
+        assert_eq!(graph.task_status("status-test".to_string()).as_deref(), Some("Running"));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while graph.active_task_count() > 0 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(graph.task_status("status-test".to_string()).as_deref(), Some("Completed"));
+    }
+
+    #[test]
+    fn test_missing_api_key_is_reported_as_such() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        env::remove_var("MODEL_API_KEY");
+
+        let graph = AgentGraph::new();
+        let buffer = HistoryBuffer::new();
+        buffer.add(point("Task", "What is 2+2?"));
+        let err = graph
+            .runtime
+            .block_on(graph.run_task("no-key", &buffer, None))
+            .unwrap_err();
+
+        assert_eq!(err, AgentRunError::MissingApiKey);
+        assert_eq!(err.to_string(), "MODEL_API_KEY not found");
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let py_err = PyErr::from(err);
+            assert!(py_err.is_instance_of::<MissingApiKeyError>(py));
+            assert!(py_err.is_instance_of::<AgentRunFailed>(py));
+            assert!(py_err.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+            assert!(!py_err.is_instance_of::<ModelsExhaustedError>(py));
+        });
+    }
+
+    #[test]
+    fn test_unreachable_endpoint_is_a_network_error() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // Bind then drop a listener so the port is known to refuse connections
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", format!("http://{}", addr));
+
+        let graph = AgentGraph::new();
+        let buffer = HistoryBuffer::new();
+        buffer.add(point("Task", "What is 2+2?"));
+        let err = graph
+            .runtime
+            .block_on(graph.run_task("offline", &buffer, None))
+            .unwrap_err();
+
+        assert!(matches!(err, AgentRunError::Network(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_before_hook_halts_execution() {
+        let reject: ContextHook = Box::new(|_ctx| Err("prompt rejected".to_string()));
+        let mut graph = AgentGraph::new();
+        graph.use_middleware(Box::new(HookMiddleware::new(
+            "guard".to_string(),
+            Some(reject),
+            None,
+        )));
+        let buffer = HistoryBuffer::new();
+        let ctx = graph
+            .runtime
+            .block_on(graph.run_task("halt-test", &buffer, None))
+            .unwrap();
+
+        assert!(ctx.should_stop);
+        assert_eq!(
+            ctx.stop_reason.as_deref(),
+            Some("guard before hook failed: prompt rejected")
+        );
+        assert!(ctx.final_answer.is_none());
+        assert_eq!(buffer.len(), 0);
+    }
+
+    #[test]
+    fn test_middleware_prompt_rewrite_reaches_request() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let (base_url, requests) = spawn_mock_model(json!({
+            "candidates": [{"content": {"parts": [
+                {"functionCall": {"name": "finish", "args": {"answer": "done"}}}
+            ]}}]
+        }));
+        env::set_var("MODEL_API_KEY", "test-key");
+        env::set_var("MODEL_BASE_URL", &base_url);
+
+        let strip_secret: ContextHook = Box::new(|ctx| {
+            let cleaned = ctx.prompt.replace("sk-live-123", "[REDACTED]");
+            ctx.set_system_prompt(cleaned);
+            ctx.add_trajectory_point(TrajectoryPoint::new(
+                0,
+                "User".to_string(),
+                "guardrail note".to_string(),
+            ));
+            Ok(())
+        });
+        let mut graph = AgentGraph::new();
+        graph.register_agent(Agent::new(
+            "Leaky".to_string(),
+            "Use key sk-live-123 for lookups.".to_string(),
+            None,
+            None,
+            None,
+        ));
+        graph.use_middleware(Box::new(HookMiddleware::new(
+            "redactor".to_string(),
+            Some(strip_secret),
+            None,
+        )));
+        let buffer = HistoryBuffer::new();
+        graph
+            .runtime
+            .block_on(graph.run_task("rewrite-test", &buffer, Some("Leaky")))
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(!requests[0].contains("sk-live-123"));
+        assert!(requests[0].contains("Use key [REDACTED] for lookups."));
+        assert!(requests[0].contains("guardrail note"));
+    }
+}
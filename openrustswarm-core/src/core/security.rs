@@ -76,10 +76,31 @@ impl AgentIdentity {
     }
 }
 
+/// A trusted public key with an optional expiry
+#[derive(Clone)]
+struct TrustedKey {
+    key: [u8; 32],
+    /// Unix timestamp (seconds) after which trust lapses
+    expires_at_secs: Option<u64>,
+}
+
+impl TrustedKey {
+    fn is_expired(&self, now_secs: u64) -> bool {
+        matches!(self.expires_at_secs, Some(exp) if now_secs >= exp)
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// A store of trusted public keys
 #[pyclass]
 pub struct TrustStore {
-    trusted_keys: RwLock<HashMap<String, [u8; 32]>>,
+    trusted_keys: RwLock<HashMap<String, TrustedKey>>,
 }
 
 #[pymethods]
@@ -91,8 +112,15 @@ impl TrustStore {
         }
     }
 
-    /// Add a trusted agent and its public key
-    pub fn add_trusted_agent(&self, agent_id: String, pubkey_hex: String) -> PyResult<()> {
+    /// Add a trusted agent and its public key, optionally expiring at
+    /// `expires_at_secs` (Unix seconds)
+    #[pyo3(signature = (agent_id, pubkey_hex, expires_at_secs = None))]
+    pub fn add_trusted_agent(
+        &self,
+        agent_id: String,
+        pubkey_hex: String,
+        expires_at_secs: Option<u64>,
+    ) -> PyResult<()> {
         let bytes = hex::decode(pubkey_hex)
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
 
@@ -106,7 +134,13 @@ impl TrustStore {
         key_bytes.copy_from_slice(&bytes);
 
         let mut keys = self.trusted_keys.write();
-        keys.insert(agent_id, key_bytes);
+        keys.insert(
+            agent_id,
+            TrustedKey {
+                key: key_bytes,
+                expires_at_secs,
+            },
+        );
         Ok(())
     }
 
@@ -119,8 +153,8 @@ impl TrustStore {
                 key_bytes.copy_from_slice(&bytes);
 
                 let keys = self.trusted_keys.read();
-                if let Some(stored_key) = keys.get(agent_id) {
-                    return stored_key == &key_bytes;
+                if let Some(stored) = keys.get(agent_id) {
+                    return stored.key == key_bytes && !stored.is_expired(now_secs());
                 }
             }
         }
@@ -131,6 +165,26 @@ impl TrustStore {
         let mut keys = self.trusted_keys.write();
         keys.remove(agent_id);
     }
+
+    /// Drop all expired keys, returning how many were removed
+    pub fn prune_expired(&self) -> usize {
+        let now = now_secs();
+        let mut keys = self.trusted_keys.write();
+        let before = keys.len();
+        keys.retain(|_, stored| !stored.is_expired(now));
+        before - keys.len()
+    }
+
+    /// List (agent_id, pubkey_hex) pairs for auditing
+    pub fn list_trusted(&self) -> Vec<(String, String)> {
+        let keys = self.trusted_keys.read();
+        let mut entries: Vec<(String, String)> = keys
+            .iter()
+            .map(|(id, stored)| (id.clone(), hex::encode(stored.key)))
+            .collect();
+        entries.sort();
+        entries
+    }
 }
 
 impl Default for TrustStore {
@@ -139,15 +193,30 @@ impl Default for TrustStore {
     }
 }
 
-/// Sign data using an identity
-pub fn sign_data(identity: &AgentIdentity, data: &[u8]) -> Vec<u8> {
+/// Prefix `data` with a length-prefixed domain-separation tag so a signature
+/// produced for one message type cannot be replayed as another.
+fn domain_separated(context: &str, data: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + context.len() + data.len());
+    message.extend_from_slice(&(context.len() as u64).to_le_bytes());
+    message.extend_from_slice(context.as_bytes());
+    message.extend_from_slice(data);
+    message
+}
+
+/// Sign data using an identity under a domain-separation context
+pub fn sign_data(identity: &AgentIdentity, context: &str, data: &[u8]) -> Vec<u8> {
     let (signing_key, _) = identity.get_keys();
-    let signature: Signature = signing_key.sign(data);
+    let signature: Signature = signing_key.sign(&domain_separated(context, data));
     signature.to_bytes().to_vec()
 }
 
-/// Verify data using a public key
-pub fn verify_signature(pubkey_hex: &str, data: &[u8], signature_bytes: &[u8]) -> bool {
+/// Verify data using a public key under a domain-separation context
+pub fn verify_signature(
+    pubkey_hex: &str,
+    context: &str,
+    data: &[u8],
+    signature_bytes: &[u8],
+) -> bool {
     let b_res = hex::decode(pubkey_hex);
     if let Ok(bytes) = b_res {
         if bytes.len() == 32 {
@@ -159,7 +228,9 @@ pub fn verify_signature(pubkey_hex: &str, data: &[u8], signature_bytes: &[u8]) -
                     let mut sig_bytes = [0u8; 64];
                     sig_bytes.copy_from_slice(signature_bytes);
                     if let Ok(signature) = Signature::from_slice(&sig_bytes) {
-                        return verifying_key.verify(data, &signature).is_ok();
+                        return verifying_key
+                            .verify(&domain_separated(context, data), &signature)
+                            .is_ok();
                     }
                 }
             }
@@ -167,3 +238,130 @@ pub fn verify_signature(pubkey_hex: &str, data: &[u8], signature_bytes: &[u8]) -
     }
     false
 }
+
+fn parse_verifying_key(pubkey_hex: &str) -> Option<VerifyingKey> {
+    let bytes = hex::decode(pubkey_hex).ok()?;
+    let key_bytes: [u8; 32] = bytes.try_into().ok()?;
+    VerifyingKey::from_bytes(&key_bytes).ok()
+}
+
+/// Verify many (pubkey, message, signature) triples together.
+///
+/// The all-valid case is checked with a single Ed25519 batch verification;
+/// if the batch fails each item is re-checked individually so the result
+/// pinpoints which signatures are bad. Slices must have equal length.
+pub fn verify_batch(
+    pubkeys_hex: &[&str],
+    context: &str,
+    messages: &[&[u8]],
+    signatures: &[&[u8]],
+) -> Vec<bool> {
+    let n = pubkeys_hex.len();
+    let mut results = vec![false; n];
+    if messages.len() != n || signatures.len() != n {
+        return results;
+    }
+
+    let mut indices = Vec::with_capacity(n);
+    let mut keys = Vec::with_capacity(n);
+    let mut sigs = Vec::with_capacity(n);
+    let mut msgs = Vec::with_capacity(n);
+    for i in 0..n {
+        let key = parse_verifying_key(pubkeys_hex[i]);
+        let sig = Signature::from_slice(signatures[i]).ok();
+        if let (Some(key), Some(sig)) = (key, sig) {
+            indices.push(i);
+            keys.push(key);
+            sigs.push(sig);
+            msgs.push(domain_separated(context, messages[i]));
+        }
+    }
+
+    if indices.is_empty() {
+        return results;
+    }
+
+    let msg_refs: Vec<&[u8]> = msgs.iter().map(|m| m.as_slice()).collect();
+    if ed25519_dalek::verify_batch(&msg_refs, &sigs, &keys).is_ok() {
+        for i in indices {
+            results[i] = true;
+        }
+    } else {
+        for (j, &i) in indices.iter().enumerate() {
+            results[i] = keys[j].verify(&msgs[j], &sigs[j]).is_ok();
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_verifies_under_same_context() {
+        let identity = AgentIdentity::generate();
+        let sig = sign_data(&identity, "experience-pack-v3", b"payload");
+        assert!(verify_signature(
+            &identity.pubkey,
+            "experience-pack-v3",
+            b"payload",
+            &sig
+        ));
+    }
+
+    #[test]
+    fn cross_context_signature_is_rejected() {
+        let identity = AgentIdentity::generate();
+        let sig = sign_data(&identity, "experience-pack-v3", b"payload");
+        assert!(!verify_signature(
+            &identity.pubkey,
+            "audit-event-v1",
+            b"payload",
+            &sig
+        ));
+
+        // Length prefix prevents shifting bytes between context and data
+        let sig = sign_data(&identity, "ab", b"c");
+        assert!(!verify_signature(&identity.pubkey, "a", b"bc", &sig));
+    }
+
+    #[test]
+    fn expired_key_is_rejected_and_pruned() {
+        let store = TrustStore::new();
+        let live = AgentIdentity::generate();
+        let lapsed = AgentIdentity::generate();
+
+        store
+            .add_trusted_agent("live".to_string(), live.pubkey.clone(), None)
+            .unwrap();
+        store
+            .add_trusted_agent("lapsed".to_string(), lapsed.pubkey.clone(), Some(1))
+            .unwrap();
+
+        assert!(store.is_trusted("live", &live.pubkey));
+        assert!(!store.is_trusted("lapsed", &lapsed.pubkey));
+
+        assert_eq!(store.prune_expired(), 1);
+        assert_eq!(
+            store.list_trusted(),
+            vec![("live".to_string(), live.pubkey.clone())]
+        );
+    }
+
+    #[test]
+    fn batch_verification_flags_only_bad_items() {
+        let a = AgentIdentity::generate();
+        let b = AgentIdentity::generate();
+        let sig_a = sign_data(&a, "ctx", b"one");
+        let sig_b = sign_data(&b, "ctx", b"two");
+
+        let results = verify_batch(
+            &[a.pubkey.as_str(), b.pubkey.as_str(), a.pubkey.as_str()],
+            "ctx",
+            &[&b"one"[..], &b"two"[..], &b"tampered"[..]],
+            &[sig_a.as_slice(), sig_b.as_slice(), sig_a.as_slice()],
+        );
+        assert_eq!(results, vec![true, true, false]);
+    }
+}
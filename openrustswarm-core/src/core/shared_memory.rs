@@ -3,13 +3,27 @@ use pyo3::prelude::*;
 use std::sync::Arc;
 use tracing::info;
 
+/// One keyed semantic memory stored via `add` and searched by `query`
+#[derive(Clone, Debug)]
+struct MemoryEntry {
+    id: String,
+    vector: Vec<f32>,
+    text: String,
+}
+
 /// A Zero-Copy memory store for sharing embeddings between agents.
 /// Follows memory-safe views over raw bytes principles.
 #[pyclass]
 pub struct SharedMemoryStore {
     buffer: Arc<RwLock<Vec<f32>>>,
+    /// Keyed semantic memory, shared by every fork
+    entries: Arc<RwLock<Vec<MemoryEntry>>>,
     vector_size: usize,
     capacity: usize,
+    /// `add` skips a new entry whose cosine similarity to a stored one exceeds
+    /// this value; 1.0 or more disables deduplication
+    #[pyo3(get, set)]
+    pub dedup_threshold: f32,
 }
 
 #[pymethods]
@@ -24,8 +38,10 @@ impl SharedMemoryStore {
 
         SharedMemoryStore {
             buffer: Arc::new(RwLock::new(vec![0.0; total_size])),
+            entries: Arc::new(RwLock::new(Vec::new())),
             vector_size,
             capacity,
+            dedup_threshold: 1.0,
         }
     }
 
@@ -76,6 +92,55 @@ impl SharedMemoryStore {
         Ok(data[start..end].to_vec())
     }
 
+    /// Store an embedding with its source text under `id`, replacing any
+    /// previous entry with the same id. Visible to every fork of this store.
+    ///
+    /// Returns `true` if a new entry was inserted, `false` if it replaced the
+    /// entry with the same id or was merged into a near-duplicate (similarity
+    /// above `dedup_threshold`), which is kept as is.
+    pub fn add(&self, id: String, vector: Vec<f32>, text: String) -> PyResult<bool> {
+        self.check_size(&vector)?;
+
+        let mut entries = self.entries.write();
+        let entry = MemoryEntry { id, vector, text };
+        if let Some(existing) = entries.iter_mut().find(|e| e.id == entry.id) {
+            *existing = entry;
+            return Ok(false);
+        }
+        if self.dedup_threshold < 1.0
+            && entries
+                .iter()
+                .any(|stored| cosine(&entry.vector, &stored.vector) as f32 > self.dedup_threshold)
+        {
+            return Ok(false);
+        }
+        entries.push(entry);
+        Ok(true)
+    }
+
+    /// Brute-force cosine search over stored entries.
+    /// Returns up to `top_k` `(id, similarity, text)` tuples, most similar first.
+    pub fn query(&self, vector: Vec<f32>, top_k: usize) -> PyResult<Vec<(String, f32, String)>> {
+        self.check_size(&vector)?;
+
+        let entries = self.entries.read();
+        let mut scored: Vec<(String, f32, String)> = entries
+            .iter()
+            .map(|e| {
+                let similarity = cosine(&vector, &e.vector) as f32;
+                (e.id.clone(), similarity, e.text.clone())
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(top_k);
+        Ok(scored)
+    }
+
+    /// Number of entries stored via `add`
+    pub fn entry_count(&self) -> usize {
+        self.entries.read().len()
+    }
+
     /// Get the total capacity (number of vectors)
     pub fn get_capacity(&self) -> usize {
         self.capacity
@@ -91,8 +156,10 @@ impl SharedMemoryStore {
     pub fn fork(&self) -> Self {
         SharedMemoryStore {
             buffer: self.buffer.clone(),
+            entries: self.entries.clone(),
             vector_size: self.vector_size,
             capacity: self.capacity,
+            dedup_threshold: self.dedup_threshold,
         }
     }
 
@@ -108,23 +175,103 @@ impl SharedMemoryStore {
         let start_a = index_a * self.vector_size;
         let start_b = index_b * self.vector_size;
 
-        let mut dot = 0.0_f64;
-        let mut mag_a = 0.0_f64;
-        let mut mag_b = 0.0_f64;
+        Ok(cosine(
+            &data[start_a..start_a + self.vector_size],
+            &data[start_b..start_b + self.vector_size],
+        ))
+    }
+}
 
-        for i in 0..self.vector_size {
-            let a = data[start_a + i] as f64;
-            let b = data[start_b + i] as f64;
-            dot += a * b;
-            mag_a += a * a;
-            mag_b += b * b;
+impl SharedMemoryStore {
+    fn check_size(&self, vector: &[f32]) -> PyResult<()> {
+        if vector.len() != self.vector_size {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Vector size mismatch: expected {}, got {}",
+                self.vector_size,
+                vector.len()
+            )));
         }
+        Ok(())
+    }
+}
 
-        let denom = mag_a.sqrt() * mag_b.sqrt();
-        if denom == 0.0 {
-            return Ok(0.0);
-        }
+/// Cosine similarity of two equal-length vectors (0.0 when either is zero),
+/// accumulated in f64. Shared by `cosine_similarity`, `add` and `query`.
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let mut dot = 0.0_f64;
+    let mut mag_a = 0.0_f64;
+    let mut mag_b = 0.0_f64;
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        mag_a += x * x;
+        mag_b += y * y;
+    }
+
+    let denom = mag_a.sqrt() * mag_b.sqrt();
+    if denom == 0.0 {
+        return 0.0;
+    }
+    dot / denom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_returns_nearest_neighbor() {
+        let store = SharedMemoryStore::new(10, 3);
+        store
+            .add(
+                "cats".to_string(),
+                vec![1.0, 0.1, 0.0],
+                "cats purr".to_string(),
+            )
+            .unwrap();
+        store
+            .add(
+                "cars".to_string(),
+                vec![0.0, 0.2, 1.0],
+                "cars honk".to_string(),
+            )
+            .unwrap();
+
+        // A fork shares the same semantic memory
+        let other_agent = store.fork();
+        let hits = other_agent.query(vec![0.9, 0.0, 0.1], 1).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "cats");
+        assert_eq!(hits[0].2, "cats purr");
+        assert!(hits[0].1 > 0.9);
+
+        store
+            .add(
+                "cats".to_string(),
+                vec![0.0, 0.0, 1.0],
+                "cats nap".to_string(),
+            )
+            .unwrap();
+        assert_eq!(store.entry_count(), 2);
+        let hits = store.query(vec![0.0, 0.0, 1.0], 5).unwrap();
+        assert_eq!(hits[0].2, "cats nap");
+        assert!(store.query(vec![1.0], 1).is_err());
+    }
+
+    #[test]
+    fn test_add_dedups_near_identical_vectors() {
+        let mut store = SharedMemoryStore::new(10, 3);
+        store.dedup_threshold = 0.99;
+
+        let first = store.add("a".to_string(), vec![1.0, 0.0, 0.0], "first".to_string());
+        let twin = store.add("b".to_string(), vec![1.0, 0.01, 0.0], "twin".to_string());
+        assert!(first.unwrap());
+        assert!(!twin.unwrap());
+        assert_eq!(store.entry_count(), 1);
+        assert_eq!(store.query(vec![1.0, 0.0, 0.0], 5).unwrap()[0].2, "first");
 
-        Ok(dot / denom)
+        let distinct = store.add("c".to_string(), vec![0.0, 1.0, 0.0], "other".to_string());
+        assert!(distinct.unwrap());
+        assert_eq!(store.entry_count(), 2);
     }
 }
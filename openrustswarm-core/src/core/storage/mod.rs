@@ -69,6 +69,21 @@ pub trait VectorStore: Send + Sync {
     /// Search for similar vectors
     async fn search(&self, vector: Vec<f32>, limit: usize) -> StorageResult<Vec<SearchResult>>;
 
+    /// Search for similar vectors, skipping the first `offset` hits.
+    ///
+    /// Results are ordered by cosine score, which is stable for an unchanged
+    /// collection, so consecutive pages are disjoint within a snapshot. Writes
+    /// between page requests can shift results across page boundaries.
+    async fn search_paged(
+        &self,
+        vector: Vec<f32>,
+        limit: usize,
+        offset: usize,
+    ) -> StorageResult<Vec<SearchResult>>;
+
+    /// Fetch a stored vector and its payload JSON by ID, or None if it does not exist
+    async fn get(&self, id: &str) -> StorageResult<Option<(Vec<f32>, Option<String>)>>;
+
     /// Delete a vector by ID
     async fn delete(&self, id: &str) -> StorageResult<()>;
 }
@@ -91,6 +106,30 @@ impl SearchResult {
     pub fn new(id: String, score: f32, payload: Option<String>) -> Self {
         SearchResult { id, score, payload }
     }
+
+    /// Parse the stored payload JSON into a Python dict.
+    ///
+    /// Returns None when there is no payload, it is malformed, or it is not an object.
+    pub fn payload_dict(&self, py: Python) -> PyObject {
+        let dict = match (self.payload_value(), &self.payload) {
+            (Some(serde_json::Value::Object(_)), Some(raw)) => py
+                .import_bound("json")
+                .and_then(|json| json.call_method1("loads", (raw.as_str(),)))
+                .map(|obj| obj.unbind())
+                .ok(),
+            _ => None,
+        };
+        dict.unwrap_or_else(|| py.None())
+    }
+}
+
+impl SearchResult {
+    /// Parsed payload, or None when it is missing or malformed
+    pub fn payload_value(&self) -> Option<serde_json::Value> {
+        self.payload
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+    }
 }
 
 /// Storage configuration for Python
@@ -132,3 +171,24 @@ impl StorageConfig {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_value_parses_or_returns_none() {
+        let result = SearchResult::new(
+            "mem-1".to_string(),
+            0.9,
+            Some(r#"{"text": "hello"}"#.to_string()),
+        );
+        assert_eq!(result.payload_value().unwrap()["text"], "hello");
+
+        let malformed = SearchResult::new("mem-2".to_string(), 0.5, Some("{not json".to_string()));
+        assert!(malformed.payload_value().is_none());
+
+        let empty = SearchResult::new("mem-3".to_string(), 0.1, None);
+        assert!(empty.payload_value().is_none());
+    }
+}
@@ -5,8 +5,10 @@
 
 use async_trait::async_trait;
 use pyo3::prelude::*;
+use qdrant_client::qdrant::vector_output::Vector;
+use qdrant_client::qdrant::vectors_output::VectorsOptions;
 use qdrant_client::qdrant::{
-    CreateCollectionBuilder, DeletePointsBuilder, Distance, PointId, PointStruct,
+    CreateCollectionBuilder, DeletePointsBuilder, Distance, GetPointsBuilder, PointId, PointStruct,
     SearchPointsBuilder, UpsertPointsBuilder, VectorParamsBuilder,
 };
 use qdrant_client::Qdrant;
@@ -121,11 +123,21 @@ impl VectorStore for VectorDbClient {
     }
 
     async fn search(&self, vector: Vec<f32>, limit: usize) -> StorageResult<Vec<SearchResult>> {
+        self.search_paged(vector, limit, 0).await
+    }
+
+    async fn search_paged(
+        &self,
+        vector: Vec<f32>,
+        limit: usize,
+        offset: usize,
+    ) -> StorageResult<Vec<SearchResult>> {
         let client = self.get_client().await?;
 
         let results = client
             .search_points(
                 SearchPointsBuilder::new(self.collection.clone(), vector, limit as u64)
+                    .offset(offset as u64)
                     .with_payload(true),
             )
             .await
@@ -150,6 +162,42 @@ impl VectorStore for VectorDbClient {
         Ok(search_results)
     }
 
+    async fn get(&self, id: &str) -> StorageResult<Option<(Vec<f32>, Option<String>)>> {
+        let client = self.get_client().await?;
+
+        let point_id: PointId = PointId::from(id.to_string());
+        let response = client
+            .get_points(
+                GetPointsBuilder::new(self.collection.clone(), vec![point_id])
+                    .with_vectors(true)
+                    .with_payload(true),
+            )
+            .await
+            .map_err(|e| StorageError::OperationFailed(e.to_string()))?;
+
+        let Some(point) = response.result.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let vector = match point.vectors.and_then(|v| v.vectors_options) {
+            Some(VectorsOptions::Vector(v)) => match v.into_vector() {
+                Vector::Dense(dense) => dense.data,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        let payload = if point.payload.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::to_string(&point.payload)
+                    .map_err(|e| StorageError::SerializationError(e.to_string()))?,
+            )
+        };
+
+        Ok(Some((vector, payload)))
+    }
+
     async fn delete(&self, id: &str) -> StorageResult<()> {
         let client = self.get_client().await?;
 
@@ -202,6 +250,28 @@ impl RemoteVectorStore {
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// Fetch the next page of similar vectors, skipping the first `offset` hits
+    #[pyo3(signature = (vector, limit, offset = 0))]
+    pub fn search_paged(
+        &self,
+        vector: Vec<f32>,
+        limit: usize,
+        offset: usize,
+    ) -> PyResult<Vec<SearchResult>> {
+        let client = self.client.clone();
+        self.runtime
+            .block_on(async move { client.search_paged(vector, limit, offset).await })
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Fetch a vector and its payload by ID, or None if it does not exist
+    pub fn get(&self, id: String) -> PyResult<Option<(Vec<f32>, Option<String>)>> {
+        let client = self.client.clone();
+        self.runtime
+            .block_on(async move { client.get(&id).await })
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
     /// Delete a vector by ID
     pub fn delete(&self, id: String) -> PyResult<()> {
         let client = self.client.clone();
@@ -210,3 +280,61 @@ impl RemoteVectorStore {
             .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn test_url() -> String {
+        std::env::var("VECTOR_DB_URL").unwrap_or_else(|_| "http://localhost:6334".to_string())
+    }
+
+    fn point_id(i: usize) -> String {
+        format!("00000000-0000-0000-0000-{:012}", i)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running vector database (VECTOR_DB_URL)"]
+    async fn second_page_is_disjoint_from_first() {
+        let collection = format!("cogops_paging_{}", std::process::id());
+        let store = VectorDbClient::new(&test_url(), &collection, 4);
+        for i in 0..10 {
+            let vector = vec![1.0, i as f32 * 0.1, 0.5, 0.25];
+            store.upsert(&point_id(i), vector, None).await.unwrap();
+        }
+
+        let query = vec![1.0, 0.0, 0.5, 0.25];
+        let page1 = store.search_paged(query.clone(), 4, 0).await.unwrap();
+        let page2 = store.search_paged(query, 4, 4).await.unwrap();
+
+        let first: HashSet<String> = page1.into_iter().map(|r| r.id).collect();
+        let second: HashSet<String> = page2.into_iter().map(|r| r.id).collect();
+        assert_eq!(first.len(), 4);
+        assert_eq!(second.len(), 4);
+        assert!(first.is_disjoint(&second));
+
+        let client = store.get_client().await.unwrap();
+        let _ = client.delete_collection(collection).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running vector database (VECTOR_DB_URL)"]
+    async fn get_returns_upserted_point() {
+        let collection = format!("cogops_get_{}", std::process::id());
+        let store = VectorDbClient::new(&test_url(), &collection, 4);
+        let payload = serde_json::json!({"text": "remember this"});
+        store
+            .upsert(&point_id(1), vec![0.5, 0.5, 0.5, 0.5], Some(payload))
+            .await
+            .unwrap();
+
+        let (vector, payload) = store.get(&point_id(1)).await.unwrap().unwrap();
+        assert_eq!(vector.len(), 4);
+        assert!(payload.unwrap().contains("remember this"));
+        assert!(store.get(&point_id(2)).await.unwrap().is_none());
+
+        let client = store.get_client().await.unwrap();
+        let _ = client.delete_collection(collection).await;
+    }
+}
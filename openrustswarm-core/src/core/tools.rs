@@ -3,13 +3,28 @@
 //! This module provides actual tool execution capabilities:
 //! - web_search: Search the web using Google Custom Search API
 //! - fetch_url: Fetch content from a URL
+//! - http_request: Call a JSON API with GET, POST or PUT
+//! - extract_json: Pull one value out of a JSON document by path
 //! - calculate: Evaluate mathematical expressions
+//! - run_python: Execute a vetted Python snippet (opt-in via `allow_code_execution`)
 //! - finish: Signal task completion with final answer
+//! - finish_json: Signal task completion with a structured JSON answer
 
-use reqwest::Client;
+use crate::core::config::CogOpsConfig;
+use crate::evolution::SafetySandbox;
+use parking_lot::Mutex;
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
-use tracing::info;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tracing::{info, warn};
 
 /// Tool execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +65,50 @@ pub fn get_tool_definitions() -> serde_json::Value {
                     "required": ["url"]
                 }
             },
+            {
+                "name": "http_request",
+                "description": "Call an HTTP API. Use for endpoints that need POST/PUT or custom headers; returns the status and response body.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "method": {
+                            "type": "string",
+                            "description": "HTTP method: GET, POST or PUT"
+                        },
+                        "url": {
+                            "type": "string",
+                            "description": "The URL to call"
+                        },
+                        "headers": {
+                            "type": "object",
+                            "description": "Request headers as a name -> value map"
+                        },
+                        "body": {
+                            "type": "string",
+                            "description": "Request body, e.g. a JSON document"
+                        }
+                    },
+                    "required": ["method", "url"]
+                }
+            },
+            {
+                "name": "extract_json",
+                "description": "Extract one value from a JSON document, e.g. the body returned by fetch_url or http_request.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "json": {
+                            "type": "string",
+                            "description": "The JSON document"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Dotted/bracket path to the value, e.g. 'data.items[0].price'"
+                        }
+                    },
+                    "required": ["json", "path"]
+                }
+            },
             {
                 "name": "calculate",
                 "description": "Evaluate a mathematical expression. Use for calculations like percentage growth.",
@@ -64,6 +123,20 @@ pub fn get_tool_definitions() -> serde_json::Value {
                     "required": ["expression"]
                 }
             },
+            {
+                "name": "run_python",
+                "description": "Run a short Python 3 script and return its stdout. Use print() for output. Imports like os, sys and subprocess are blocked.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "code": {
+                            "type": "string",
+                            "description": "Python source to execute"
+                        }
+                    },
+                    "required": ["code"]
+                }
+            },
             {
                 "name": "finish",
                 "description": "Call this when you have the final answer to the task. This ends the task loop.",
@@ -77,21 +150,139 @@ pub fn get_tool_definitions() -> serde_json::Value {
                     },
                     "required": ["answer"]
                 }
+            },
+            {
+                "name": "finish_json",
+                "description": "Call this instead of finish when the final answer is structured data. The answer must be a JSON object. This ends the task loop.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "answer": {
+                            "type": "object",
+                            "description": "The final answer as a JSON object"
+                        }
+                    },
+                    "required": ["answer"]
+                }
             }
         ]
     })
 }
 
+/// TTL cache of successful `web_search` results, keyed on the normalized query
+pub struct SearchCache {
+    entries: Mutex<HashMap<String, (Instant, ToolResult)>>,
+}
+
+impl SearchCache {
+    pub fn new() -> Self {
+        SearchCache {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn normalize(query: &str) -> String {
+        query.trim().to_lowercase()
+    }
+
+    /// Returns the cached result for `query` if it is younger than `ttl`.
+    pub fn get(&self, query: &str, ttl: Duration) -> Option<ToolResult> {
+        let entries = self.entries.lock();
+        entries
+            .get(&Self::normalize(query))
+            .filter(|(at, _)| at.elapsed() < ttl)
+            .map(|(_, result)| result.clone())
+    }
+
+    /// Stores `result`, dropping expired entries and then the oldest one when full.
+    pub fn insert(&self, query: &str, result: ToolResult, ttl: Duration, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (at, _)| at.elapsed() < ttl);
+        let key = Self::normalize(query);
+        if entries.len() >= capacity && !entries.contains_key(&key) {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (at, _))| *at)
+                .map(|(k, _)| k.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), result));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static SEARCH_CACHE: OnceLock<SearchCache> = OnceLock::new();
+
+/// Process-wide search cache shared by all agents
+pub fn search_cache() -> &'static SearchCache {
+    SEARCH_CACHE.get_or_init(SearchCache::new)
+}
+
+/// Serves `query` from `cache` when fresh, otherwise runs `search` and caches
+/// successful results. Errors are never cached.
+pub async fn cached_search<F, Fut>(
+    cache: &SearchCache,
+    config: &CogOpsConfig,
+    query: &str,
+    search: F,
+) -> ToolResult
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ToolResult>,
+{
+    let ttl = Duration::from_secs(config.search_cache_ttl_secs);
+    let enabled = config.search_cache_ttl_secs > 0 && config.search_cache_size > 0;
+
+    if enabled {
+        if let Some(hit) = cache.get(query, ttl) {
+            info!("🔍 [Tool] web_search cache hit: {}", query);
+            return hit;
+        }
+    }
+
+    let result = search().await;
+    if enabled && matches!(result, ToolResult::Success(_)) {
+        cache.insert(query, result.clone(), ttl, config.search_cache_size);
+    }
+    result
+}
+
 /// Execute web search using Google Custom Search API (or fallback to DuckDuckGo)
-pub async fn web_search(client: &Client, query: &str) -> ToolResult {
+pub async fn web_search(client: &Client, config: &CogOpsConfig, query: &str) -> ToolResult {
     info!("🔍 [Tool] web_search: {}", query);
 
     // If it's a URL, just fetch it directly
     if query.trim().starts_with("http") {
         info!("🔗 [Tool] Query looks like a URL, calling fetch_url instead...");
-        return fetch_url(client, query.trim()).await;
+        return fetch_url(config, query.trim()).await;
     }
 
+    cached_search(search_cache(), config, query, || {
+        search_sources(client, query)
+    })
+    .await
+}
+
+/// Queries Google, Yahoo Finance and DuckDuckGo in turn (uncached)
+async fn search_sources(client: &Client, query: &str) -> ToolResult {
     // FULLY DYNAMIC ticker detection - no hardcoded values!
     let query_lower = query.to_lowercase();
 
@@ -312,27 +503,254 @@ pub async fn web_search(client: &Client, query: &str) -> ToolResult {
     ToolResult::Success("All search sources blocked. Suggest using fetch_url on a direct link like 'https://www.marketwatch.com/investing/stock/amd' or 'https://www.cnbc.com/quotes/AMD'".to_string())
 }
 
-/// Fetch content from a URL
-pub async fn fetch_url(client: &Client, url: &str) -> ToolResult {
+/// Returns true for loopback, private, link-local (incl. cloud metadata) and other
+/// non-public addresses that must never be fetched.
+fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let o = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || o[0] == 0
+                || (o[0] == 100 && (o[1] & 0xc0) == 64) // 100.64.0.0/10 (CGNAT)
+                || (o[0] == 198 && (o[1] & 0xfe) == 18) // 198.18.0.0/15 (benchmarking)
+                || o[0] >= 240 // 240.0.0.0/4 (reserved)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = embedded_ipv4(v6) {
+                return is_blocked_ip(IpAddr::V4(v4));
+            }
+            let seg = v6.segments();
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (seg[0] & 0xfe00) == 0xfc00 // fc00::/7 (unique local)
+                || (seg[0] & 0xffc0) == 0xfe80 // fe80::/10 (link-local)
+        }
+    }
+}
+
+/// IPv4 address carried inside an IPv6 one that routes to it: IPv4-mapped
+/// `::ffff:a.b.c.d`, IPv4-compatible `::a.b.c.d`, NAT64 `64:ff9b::/96` and
+/// 6to4 `2002::/16`
+fn embedded_ipv4(v6: Ipv6Addr) -> Option<Ipv4Addr> {
+    let seg = v6.segments();
+    let from_segments = |hi: u16, lo: u16| {
+        let [a, b] = hi.to_be_bytes();
+        let [c, d] = lo.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    if seg[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return Some(from_segments(seg[6], seg[7]));
+    }
+    if seg[0] == 0x2002 {
+        return Some(from_segments(seg[1], seg[2]));
+    }
+    // Covers both the mapped and the (deprecated) compatible forms
+    v6.to_ipv4()
+}
+
+fn is_host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    allowed_hosts.is_empty()
+        || allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
+}
+
+/// SSRF guard: checks the host against the allowlist, then resolves it and
+/// rejects any private or metadata address.
+pub async fn check_url_host(url: &str, allowed_hosts: &[String]) -> Result<(), String> {
+    resolve_public_host(url, allowed_hosts).await.map(|_| ())
+}
+
+/// Runs the SSRF guard and returns the vetted host with the addresses it resolved to
+async fn resolve_public_host(
+    url: &str,
+    allowed_hosts: &[String],
+) -> Result<(String, Vec<SocketAddr>), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "blocked host: missing host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_lowercase();
+
+    if !is_host_allowed(&host, allowed_hosts) {
+        return Err(format!("blocked host: {} is not allowlisted", host));
+    }
+
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host.as_str(), port))
+            .await
+            .map_err(|e| format!("DNS resolution failed for {}: {}", host, e))?
+            .collect(),
+    };
+
+    if addrs.is_empty() {
+        return Err(format!("DNS resolution failed for {}: no addresses", host));
+    }
+    if addrs.iter().any(|a| is_blocked_ip(a.ip())) {
+        return Err(format!(
+            "blocked host: {} resolves to a private address",
+            host
+        ));
+    }
+    Ok((host, addrs))
+}
+
+/// Builds a client that only talks to the vetted `addrs`: DNS for `host` is
+/// pinned so a rebinding resolver cannot swap in a private address, and
+/// redirects are not followed so every hop goes back through the guard.
+fn pinned_client(host: &str, addrs: &[SocketAddr]) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .redirect(Policy::none())
+        .timeout(Duration::from_secs(30))
+        .gzip(true)
+        .brotli(true);
+    if host.parse::<IpAddr>().is_err() {
+        builder = builder.resolve_to_addrs(host, addrs);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Vets `url` and returns a client pinned to the addresses it resolved to
+async fn guarded_client(url: &str, allowed_hosts: &[String]) -> Result<Client, String> {
+    let (host, addrs) = resolve_public_host(url, allowed_hosts).await?;
+    pinned_client(&host, &addrs)
+}
+
+/// Redirects `fetch_url` follows before giving up
+const MAX_REDIRECTS: usize = 5;
+
+/// Fetch content from a URL, following redirects only to hosts that pass the guard
+pub async fn fetch_url(config: &CogOpsConfig, url: &str) -> ToolResult {
     info!("🌐 [Tool] fetch_url: {}", url);
 
-    match client
+    let allowed_hosts = config.allowed_url_hosts.as_slice();
+    fetch_following(url, config.max_fetch_bytes, move |hop: String| async move {
+        guarded_client(&hop, allowed_hosts).await
+    })
+    .await
+}
+
+/// Fetches `url` hop by hop, asking `guard` for a client before each request
+async fn fetch_following<F, Fut>(url: &str, max_bytes: usize, guard: F) -> ToolResult
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Client, String>>,
+{
+    let mut current = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let client = match guard(current.clone()).await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("🛡️ [Tool] fetch_url rejected: {}", e);
+                return ToolResult::Error(e);
+            }
+        };
+        match fetch_capped(&client, &current, max_bytes).await {
+            Fetched::Redirect(next) => {
+                info!("🌐 [Tool] fetch_url redirected to {}", next);
+                current = next;
+            }
+            Fetched::Done(result) => return result,
+        }
+    }
+    ToolResult::Error(format!("Too many redirects fetching {}", url))
+}
+
+/// Returns true for content types whose body is readable text.
+/// A missing header is treated as text.
+fn is_text_content_type(content_type: Option<&str>) -> bool {
+    match content_type {
+        None => true,
+        Some(ct) => {
+            let ct = ct.to_lowercase();
+            ct.starts_with("text/")
+                || ct.contains("json")
+                || ct.contains("xml")
+                || ct.contains("javascript")
+        }
+    }
+}
+
+/// Reads a response body chunk by chunk, stopping once `max_bytes` is reached.
+/// Returns the body and whether it was cut short.
+async fn read_capped_body(
+    mut resp: reqwest::Response,
+    max_bytes: usize,
+) -> Result<(Vec<u8>, bool), reqwest::Error> {
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        let remaining = max_bytes - body.len();
+        if chunk.len() >= remaining {
+            body.extend_from_slice(&chunk[..remaining]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+/// Outcome of a single `fetch_capped` request
+enum Fetched {
+    Done(ToolResult),
+    /// Absolute URL from the `Location` header of a 3xx response
+    Redirect(String),
+}
+
+/// Fetches `url` without the host guard, enforcing content-type and size limits.
+/// Redirects are handed back to the caller rather than followed.
+async fn fetch_capped(client: &Client, url: &str, max_bytes: usize) -> Fetched {
+    let result = match client
         .get(url)
         .header("User-Agent", "CogOps/1.0 (Research Agent)")
         .send()
         .await
     {
         Ok(resp) => {
+            let location = resp
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| resp.url().join(v).ok());
+            if let (true, Some(next)) = (resp.status().is_redirection(), location) {
+                return Fetched::Redirect(next.to_string());
+            }
             if resp.status().is_success() {
-                match resp.text().await {
-                    Ok(body) => {
-                        // Truncate to avoid overwhelming the context
-                        let truncated = if body.len() > 4000 {
-                            format!("{}... [truncated]", &body[..4000])
-                        } else {
-                            body
-                        };
-                        ToolResult::Success(format!("Content from {}:\n{}", url, truncated))
+                let content_type = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string());
+                if !is_text_content_type(content_type.as_deref()) {
+                    return Fetched::Done(ToolResult::Error(format!(
+                        "Unsupported content type: {}",
+                        content_type.unwrap_or_default()
+                    )));
+                }
+
+                match read_capped_body(resp, max_bytes).await {
+                    Ok((bytes, capped)) => {
+                        if capped {
+                            warn!("🌐 [Tool] fetch_url body capped at {} bytes", max_bytes);
+                        }
+                        let body = String::from_utf8_lossy(&bytes);
+                        ToolResult::Success(format!(
+                            "Content from {}:\n{}",
+                            url,
+                            truncate_output(&body)
+                        ))
                     }
                     Err(e) => ToolResult::Error(format!("Failed to read response: {}", e)),
                 }
@@ -341,6 +759,355 @@ pub async fn fetch_url(client: &Client, url: &str) -> ToolResult {
             }
         }
         Err(e) => ToolResult::Error(format!("Request failed: {}", e)),
+    };
+    Fetched::Done(result)
+}
+
+/// Methods `http_request` may use
+const HTTP_REQUEST_METHODS: [&str; 3] = ["GET", "POST", "PUT"];
+
+/// Headers the agent may not set: `Host` would retarget the pinned connection,
+/// and hop-by-hop and proxy headers belong to the transport, not the API call
+const DENIED_REQUEST_HEADERS: [&str; 14] = [
+    "host",
+    "connection",
+    "keep-alive",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "content-length",
+    "proxy-authorization",
+    "proxy-authenticate",
+    "proxy-connection",
+    "forwarded",
+    "via",
+    "x-real-ip",
+];
+
+fn check_request_headers(headers: &HashMap<String, String>) -> Result<(), String> {
+    for name in headers.keys() {
+        let lower = name.trim().to_lowercase();
+        if DENIED_REQUEST_HEADERS.contains(&lower.as_str())
+            || lower.starts_with("proxy-")
+            || lower.starts_with("x-forwarded-")
+        {
+            return Err(format!("Header not allowed: {}", name));
+        }
+    }
+    Ok(())
+}
+
+/// Call an HTTP API with an arbitrary GET/POST/PUT request.
+/// Redirects are returned to the agent rather than followed.
+pub async fn http_request(
+    config: &CogOpsConfig,
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: Option<&str>,
+) -> ToolResult {
+    info!("🌐 [Tool] http_request: {} {}", method, url);
+
+    if let Err(e) = check_request_headers(headers) {
+        warn!("🛡️ [Tool] http_request rejected: {}", e);
+        return ToolResult::Error(e);
+    }
+    let client = match guarded_client(url, &config.allowed_url_hosts).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("🛡️ [Tool] http_request rejected: {}", e);
+            return ToolResult::Error(e);
+        }
+    };
+
+    send_capped(&client, method, url, headers, body, config.max_fetch_bytes).await
+}
+
+/// Sends a request without the host guard, capping the response body.
+/// Non-2xx responses are still returned so the agent can read API errors.
+async fn send_capped(
+    client: &Client,
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: Option<&str>,
+    max_bytes: usize,
+) -> ToolResult {
+    let method = method.trim().to_uppercase();
+    if !HTTP_REQUEST_METHODS.contains(&method.as_str()) {
+        return ToolResult::Error(format!(
+            "Unsupported method: {} (allowed: {})",
+            method,
+            HTTP_REQUEST_METHODS.join(", ")
+        ));
+    }
+    let method = match reqwest::Method::from_bytes(method.as_bytes()) {
+        Ok(m) => m,
+        Err(e) => return ToolResult::Error(format!("Invalid method: {}", e)),
+    };
+
+    let mut request = client
+        .request(method, url)
+        .header("User-Agent", "CogOps/1.0 (Research Agent)");
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some(body) = body {
+        request = request.body(body.to_string());
+    }
+
+    match request.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            match read_capped_body(resp, max_bytes).await {
+                Ok((bytes, capped)) => {
+                    if capped {
+                        warn!("🌐 [Tool] http_request body capped at {} bytes", max_bytes);
+                    }
+                    let body = String::from_utf8_lossy(&bytes);
+                    ToolResult::Success(format!("HTTP {}\n{}", status, truncate_output(&body)))
+                }
+                Err(e) => ToolResult::Error(format!("Failed to read response: {}", e)),
+            }
+        }
+        Err(e) => ToolResult::Error(format!("Request failed: {}", e)),
+    }
+}
+
+/// Truncate tool output to avoid overwhelming the context
+fn truncate_output(body: &str) -> String {
+    if body.chars().count() > 4000 {
+        format!(
+            "{}... [truncated]",
+            body.chars().take(4000).collect::<String>()
+        )
+    } else {
+        body.to_string()
+    }
+}
+
+/// Most stdout or stderr bytes kept from a `run_python` child
+const MAX_CODE_OUTPUT_BYTES: u64 = 64 * 1024;
+
+/// Address-space cap for `run_python` children
+const CODE_MEMORY_LIMIT_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Caps CPU time, address space and process count for the current (forked,
+/// not yet exec'd) process.
+#[cfg(unix)]
+fn apply_child_rlimits(cpu_secs: u64) -> std::io::Result<()> {
+    let limits = [
+        (libc::RLIMIT_CPU, cpu_secs),
+        (libc::RLIMIT_AS, CODE_MEMORY_LIMIT_BYTES),
+        (libc::RLIMIT_NPROC, 0),
+    ];
+    for (resource, value) in limits {
+        let limit = libc::rlimit {
+            rlim_cur: value as libc::rlim_t,
+            rlim_max: value as libc::rlim_t,
+        };
+        // SAFETY: setrlimit is async-signal-safe and `limit` outlives the call
+        if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Execute a Python snippet in a child interpreter.
+/// The code must pass `SafetySandbox` first, and runs with isolated mode,
+/// no stdin, a `code_timeout_secs` wall-clock limit and, on Unix, CPU,
+/// memory and process-count rlimits. None of this is real isolation: keep
+/// `allow_code_execution` off unless the host adds seccomp, namespaces or a
+/// container around the process.
+pub async fn run_python(config: &CogOpsConfig, code: &str) -> ToolResult {
+    info!("🐍 [Tool] run_python ({} bytes)", code.len());
+
+    if !config.allow_code_execution {
+        return ToolResult::Error(
+            "Code execution is disabled (set allow_code_execution to enable run_python)"
+                .to_string(),
+        );
+    }
+
+    // The checker is a blocking child process; keep it off the async workers
+    let snippet = code.to_string();
+    let vetted = tokio::task::spawn_blocking(move || SafetySandbox::new(None).vet(&snippet))
+        .await
+        .unwrap_or_else(|e| Err(format!("checker failed: {}", e)));
+    if let Err(reason) = vetted {
+        warn!("🚫 [Tool] run_python rejected: {}", reason);
+        return ToolResult::Error(format!("Blocked by sandbox: {}", reason));
+    }
+
+    let mut command = tokio::process::Command::new("python3");
+    command
+        .arg("-I")
+        .arg("-c")
+        .arg(code)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    {
+        let cpu_secs = config.code_timeout_secs.max(1);
+        // SAFETY: the closure runs between fork and exec and only calls
+        // setrlimit, which is async-signal-safe; it allocates nothing
+        unsafe {
+            command.pre_exec(move || apply_child_rlimits(cpu_secs));
+        }
+    }
+    let child = command.spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => return ToolResult::Error(format!("Failed to start python3: {}", e)),
+    };
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return ToolResult::Error("Failed to capture python3 output".to_string());
+    };
+
+    let limit = Duration::from_secs(config.code_timeout_secs);
+    let run = async {
+        let (stdout, stderr, status) = tokio::join!(
+            read_capped(stdout, MAX_CODE_OUTPUT_BYTES),
+            read_capped(stderr, MAX_CODE_OUTPUT_BYTES),
+            child.wait()
+        );
+        Ok::<_, std::io::Error>((stdout?, stderr?, status?))
+    };
+    match tokio::time::timeout(limit, run).await {
+        Err(_) => ToolResult::Error(format!(
+            "Execution timed out after {}s",
+            config.code_timeout_secs
+        )),
+        Ok(Err(e)) => ToolResult::Error(format!("Execution failed: {}", e)),
+        Ok(Ok((stdout, stderr, status))) => {
+            let stdout = String::from_utf8_lossy(&stdout);
+            if status.success() {
+                ToolResult::Success(truncate_output(stdout.trim_end()))
+            } else {
+                let stderr = String::from_utf8_lossy(&stderr);
+                ToolResult::Error(format!(
+                    "Script exited with {}: {}",
+                    status,
+                    truncate_output(stderr.trim_end())
+                ))
+            }
+        }
+    }
+}
+
+/// Reads at most `cap` bytes from `pipe` and discards the rest, so memory
+/// stays bounded and the child never stalls on a full pipe
+async fn read_capped<R: AsyncRead + Unpin>(mut pipe: R, cap: u64) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    (&mut pipe).take(cap).read_to_end(&mut bytes).await?;
+    tokio::io::copy(&mut pipe, &mut tokio::io::sink()).await?;
+    Ok(bytes)
+}
+
+/// Extract the value at `path` (e.g. `data.items[0].price`) from a JSON document.
+/// Strings are returned bare; everything else as compact JSON.
+pub fn extract_json(json: &str, path: &str) -> ToolResult {
+    info!("🧩 [Tool] extract_json: {}", path);
+
+    let root: serde_json::Value = match serde_json::from_str(json) {
+        Ok(v) => v,
+        Err(e) => return ToolResult::Error(format!("Invalid JSON: {}", e)),
+    };
+    let segments = match parse_json_path(path) {
+        Ok(s) => s,
+        Err(e) => return ToolResult::Error(format!("Invalid path '{}': {}", path, e)),
+    };
+
+    let mut current = &root;
+    for (i, segment) in segments.iter().enumerate() {
+        let next = match segment {
+            PathSegment::Key(key) => current.get(key.as_str()),
+            PathSegment::Index(idx) => current.get(*idx),
+        };
+        current = match next {
+            Some(v) => v,
+            None => {
+                return ToolResult::Error(format!(
+                    "Path '{}' does not resolve: no {} at '{}'",
+                    path,
+                    segment,
+                    render_json_path(&segments[..i])
+                ))
+            }
+        };
+    }
+
+    match current {
+        serde_json::Value::String(s) => ToolResult::Success(s.clone()),
+        other => ToolResult::Success(other.to_string()),
+    }
+}
+
+/// One step of an `extract_json` path
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl std::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, "key '{}'", key),
+            PathSegment::Index(idx) => write!(f, "index [{}]", idx),
+        }
+    }
+}
+
+/// Splits `a.b[0].c` into keys and array indices
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut segments = Vec::new();
+    for part in path.trim().split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(pos) => (&part[..pos], &part[pos..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        } else if rest.is_empty() {
+            return Err("empty segment".to_string());
+        }
+        while !rest.is_empty() {
+            let close = rest
+                .find(']')
+                .filter(|_| rest.starts_with('['))
+                .ok_or_else(|| format!("malformed index in '{}'", part))?;
+            let idx = rest[1..close]
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid index '{}'", &rest[1..close]))?;
+            segments.push(PathSegment::Index(idx));
+            rest = &rest[close + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+fn render_json_path(segments: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            PathSegment::Key(key) if out.is_empty() => out.push_str(key),
+            PathSegment::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            PathSegment::Index(idx) => out.push_str(&format!("[{}]", idx)),
+        }
+    }
+    if out.is_empty() {
+        "$".to_string()
+    } else {
+        out
     }
 }
 
@@ -395,7 +1162,9 @@ fn eval_expr(expr: &str) -> Result<f64, String> {
         match c {
             '(' => depth += 1,
             ')' => depth -= 1,
-            '+' | '-' if depth == 0 && i > 0 => last_add_sub = Some(i),
+            '+' | '-' if depth == 0 && i > 0 && !is_exponent_sign(expr, i) => {
+                last_add_sub = Some(i)
+            }
             '*' | '/' if depth == 0 => last_mul_div = Some(i),
             _ => {}
         }
@@ -427,31 +1196,96 @@ fn eval_expr(expr: &str) -> Result<f64, String> {
         .map_err(|_| format!("Invalid number: {}", expr))
 }
 
+/// True when the sign at `pos` belongs to a scientific-notation exponent
+/// (the `-` in `1e-3`) rather than being a subtraction
+fn is_exponent_sign(expr: &str, pos: usize) -> bool {
+    let bytes = expr.as_bytes();
+    pos >= 2
+        && matches!(bytes[pos - 1], b'e' | b'E')
+        && (bytes[pos - 2].is_ascii_digit() || bytes[pos - 2] == b'.')
+}
+
 /// Signal task completion
 pub fn finish(answer: &str) -> ToolResult {
     info!("[Tool] finish: {}", answer);
     ToolResult::Success(answer.to_string())
 }
 
+/// Signal task completion with a structured answer.
+/// Accepts a JSON object or a string containing one; returns it as compact JSON.
+pub fn finish_json(answer: &serde_json::Value) -> ToolResult {
+    info!("[Tool] finish_json: {}", answer);
+    let parsed = match answer {
+        serde_json::Value::String(s) => match serde_json::from_str::<serde_json::Value>(s) {
+            Ok(v) => v,
+            Err(e) => {
+                return ToolResult::Error(format!(
+                    "Invalid JSON answer ({}). Call finish_json again with a valid JSON object.",
+                    e
+                ))
+            }
+        },
+        other => other.clone(),
+    };
+    if !parsed.is_object() {
+        return ToolResult::Error(
+            "finish_json answer must be a JSON object. Call finish_json again with a valid JSON object."
+                .to_string(),
+        );
+    }
+    ToolResult::Success(parsed.to_string())
+}
+
 /// Dispatch tool call by name
-pub async fn execute_tool(client: &Client, name: &str, args: &serde_json::Value) -> ToolResult {
+pub async fn execute_tool(
+    client: &Client,
+    config: &CogOpsConfig,
+    name: &str,
+    args: &serde_json::Value,
+) -> ToolResult {
     match name {
         "web_search" => {
             let query = args["query"].as_str().unwrap_or("");
-            web_search(client, query).await
+            web_search(client, config, query).await
         }
         "fetch_url" => {
             let url = args["url"].as_str().unwrap_or("");
-            fetch_url(client, url).await
+            fetch_url(config, url).await
+        }
+        "http_request" => {
+            let method = args["method"].as_str().unwrap_or("GET");
+            let url = args["url"].as_str().unwrap_or("");
+            let headers: HashMap<String, String> = args["headers"]
+                .as_object()
+                .map(|map| {
+                    map.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let body = args["body"].as_str();
+            http_request(config, method, url, &headers, body).await
+        }
+        "extract_json" => {
+            let path = args["path"].as_str().unwrap_or("");
+            match &args["json"] {
+                serde_json::Value::String(json) => extract_json(json, path),
+                other => extract_json(&other.to_string(), path),
+            }
         }
         "calculate" => {
             let expr = args["expression"].as_str().unwrap_or("");
             calculate(expr)
         }
+        "run_python" => {
+            let code = args["code"].as_str().unwrap_or("");
+            run_python(config, code).await
+        }
         "finish" => {
             let answer = args["answer"].as_str().unwrap_or("");
             finish(answer)
         }
+        "finish_json" => finish_json(&args["answer"]),
         _ => ToolResult::Error(format!("Unknown tool: {}", name)),
     }
 }
@@ -467,4 +1301,442 @@ mod tests {
             matches!(calculate("((145.20-125.90)/125.90)*100"), ToolResult::Success(s) if s.contains("15"))
         );
     }
+
+    #[test]
+    fn test_run_python_sandboxed() {
+        let mut config = CogOpsConfig::default();
+        assert!(matches!(
+            block_on(run_python(&config, "print(6 * 7)")),
+            ToolResult::Error(e) if e.contains("disabled")
+        ));
+
+        config.allow_code_execution = true;
+        assert!(matches!(
+            block_on(run_python(&config, "print(6 * 7)")),
+            ToolResult::Success(s) if s == "42"
+        ));
+        assert!(matches!(
+            block_on(run_python(&config, "import os; os.system('echo pwned')")),
+            ToolResult::Error(e) if e.contains("Blocked by sandbox")
+        ));
+        assert!(matches!(
+            block_on(run_python(&config, "__import__('os').system('echo pwned')")),
+            ToolResult::Error(e) if e.contains("Blocked by sandbox")
+        ));
+        #[cfg(unix)]
+        assert!(matches!(
+            block_on(run_python(&config, "x = bytearray(2 * 1024 ** 3)")),
+            ToolResult::Error(e) if e.contains("MemoryError")
+        ));
+
+        config.code_timeout_secs = 1;
+        assert!(matches!(
+            block_on(run_python(&config, "while True: pass")),
+            ToolResult::Error(e) if e.contains("timed out")
+        ));
+    }
+
+    #[test]
+    fn test_extract_json_paths() {
+        let doc = r#"{"data": {"name": "AMD", "items": [{"price": 145.2}, {"price": 150}]}}"#;
+        assert!(matches!(extract_json(doc, "data.name"), ToolResult::Success(s) if s == "AMD"));
+        assert!(
+            matches!(extract_json(doc, "data.items[1].price"), ToolResult::Success(s) if s == "150")
+        );
+        assert!(
+            matches!(extract_json(doc, "data.items[0]"), ToolResult::Success(s) if s == r#"{"price":145.2}"#)
+        );
+        assert!(
+            matches!(extract_json("[[1, 2], [3]]", "[1][0]"), ToolResult::Success(s) if s == "3")
+        );
+
+        assert!(matches!(
+            extract_json(doc, "data.items[5].price"),
+            ToolResult::Error(e) if e.contains("index [5]") && e.contains("data.items")
+        ));
+        assert!(matches!(
+            extract_json(doc, "data.missing"),
+            ToolResult::Error(e) if e.contains("key 'missing'")
+        ));
+        assert!(matches!(
+            extract_json(doc, "data..name"),
+            ToolResult::Error(_)
+        ));
+        assert!(
+            matches!(extract_json("{oops", "a"), ToolResult::Error(e) if e.starts_with("Invalid JSON"))
+        );
+    }
+
+    #[test]
+    fn test_calculate_scientific_notation() {
+        assert_eq!(eval_expr("1.5e3").unwrap(), 1500.0);
+        assert_eq!(eval_expr("1e-3").unwrap(), 0.001);
+        assert_eq!(eval_expr("2-1e-3").unwrap(), 1.999);
+        assert_eq!(eval_expr("1E+2+1").unwrap(), 101.0);
+        let avogadro = eval_expr("6.022e23*2").unwrap();
+        assert!((avogadro / 1.2044e24 - 1.0).abs() < 1e-12);
+        assert!(matches!(calculate("6.022e23 * 2"), ToolResult::Success(_)));
+    }
+
+    fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn test_fetch_url_blocks_private_hosts() {
+        let config = CogOpsConfig::default();
+        for url in [
+            "http://127.0.0.1:8080/admin",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://10.0.0.5/",
+            "http://[::1]/",
+            "http://198.18.0.1/",
+            "http://240.0.0.1/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[::10.0.0.5]/",
+            "http://[64:ff9b::a9fe:a9fe]/",
+            "http://[2002:7f00:1::]/",
+        ] {
+            let result = block_on(fetch_url(&config, url));
+            assert!(
+                matches!(&result, ToolResult::Error(e) if e.starts_with("blocked host")),
+                "{} was not blocked: {:?}",
+                url,
+                result
+            );
+        }
+    }
+
+    /// Serves one response with the given content type and body size.
+    fn spawn_body_server(content_type: &'static str, body_len: usize) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                content_type, body_len
+            );
+            if stream.write_all(header.as_bytes()).is_err() {
+                return;
+            }
+            let chunk = vec![b'a'; 64 * 1024];
+            let mut sent = 0;
+            while sent < body_len {
+                let n = chunk.len().min(body_len - sent);
+                if stream.write_all(&chunk[..n]).is_err() {
+                    return; // client stopped reading
+                }
+                sent += n;
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    /// Serves one `302 Found` pointing at `location`.
+    fn spawn_redirect_server(location: String) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                location
+            );
+            let _ = stream.write_all(response.as_bytes());
+        });
+        format!("http://{}/", addr)
+    }
+
+    #[test]
+    fn test_fetch_url_rechecks_redirect_targets() {
+        // A permissive guard follows the redirect to the body server
+        let start = spawn_redirect_server(spawn_body_server("text/plain", 16));
+        let followed = block_on(fetch_following(&start, 1024, |_| async {
+            pinned_client("127.0.0.1", &[])
+        }));
+        assert!(
+            matches!(&followed, ToolResult::Success(s) if s.ends_with("aaaa")),
+            "{:?}",
+            followed
+        );
+
+        // Let the test server through for the first hop only; the real guard
+        // runs on the redirect and must refuse the loopback target
+        let start = spawn_redirect_server(spawn_body_server("text/plain", 16));
+        let first_hop = start.clone();
+        let result = block_on(fetch_following(&start, 1024, move |hop: String| {
+            let first = hop == first_hop;
+            async move {
+                if first {
+                    pinned_client("127.0.0.1", &[])
+                } else {
+                    guarded_client(&hop, &[]).await
+                }
+            }
+        }));
+        assert!(
+            matches!(&result, ToolResult::Error(e) if e.starts_with("blocked host")),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_pinned_client_ignores_dns() {
+        let url = spawn_body_server("text/plain", 16);
+        let addr: SocketAddr = url
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .parse()
+            .unwrap();
+        let client = pinned_client("rebind.invalid", &[addr]).unwrap();
+        let pinned_url = format!("http://rebind.invalid:{}/", addr.port());
+        let result = block_on(fetch_capped(&client, &pinned_url, 1024));
+        assert!(matches!(result, Fetched::Done(ToolResult::Success(_))));
+    }
+
+    #[test]
+    fn test_read_capped_body_enforces_cap() {
+        let url = spawn_body_server("text/plain", 8 * 1024 * 1024);
+        let cap = 100 * 1024;
+        let (bytes, capped) = block_on(async {
+            let resp = Client::new().get(&url).send().await.unwrap();
+            read_capped_body(resp, cap).await.unwrap()
+        });
+        assert!(capped);
+        assert_eq!(bytes.len(), cap);
+    }
+
+    #[test]
+    fn test_fetch_rejects_binary_content() {
+        let url = spawn_body_server("image/png", 1024);
+        let result = block_on(fetch_capped(&Client::new(), &url, 1024 * 1024));
+        assert!(matches!(result, Fetched::Done(ToolResult::Error(e)) if e.contains("image/png")));
+
+        assert!(is_text_content_type(Some("text/html; charset=utf-8")));
+        assert!(is_text_content_type(Some("application/json")));
+        assert!(!is_text_content_type(Some("application/octet-stream")));
+    }
+
+    /// Serves one request and echoes its body back as `text/plain`.
+    fn spawn_echo_server() -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            let header_end = loop {
+                if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            };
+            let head = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            let body_len = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            while request.len() < header_end + body_len {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let method = head.split_whitespace().next().unwrap_or("").to_uppercase();
+            let body = String::from_utf8_lossy(&request[header_end..header_end + body_len]);
+            let reply = format!("{} {}", method, body);
+            let response = format!(
+                "HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            );
+            let _ = stream.write_all(response.as_bytes());
+        });
+        format!("http://{}/", addr)
+    }
+
+    #[test]
+    fn test_http_request_delivers_post_body() {
+        let url = spawn_echo_server();
+        let headers = HashMap::from([("Content-Type".to_string(), "application/json".to_string())]);
+        let result = block_on(send_capped(
+            &Client::new(),
+            "post",
+            &url,
+            &headers,
+            Some(r#"{"id":7}"#),
+            1024,
+        ));
+        assert!(
+            matches!(&result, ToolResult::Success(s) if s.starts_with("HTTP 201") && s.contains(r#"POST {"id":7}"#)),
+            "{:?}",
+            result
+        );
+
+        let rejected = block_on(send_capped(
+            &Client::new(),
+            "DELETE",
+            &url,
+            &headers,
+            None,
+            1024,
+        ));
+        assert!(matches!(rejected, ToolResult::Error(e) if e.contains("Unsupported method")));
+
+        let config = CogOpsConfig::default();
+        let guarded = block_on(http_request(&config, "POST", &url, &headers, None));
+        assert!(matches!(guarded, ToolResult::Error(e) if e.starts_with("blocked host")));
+    }
+
+    #[test]
+    fn test_http_request_rejects_transport_headers() {
+        let config = CogOpsConfig::default();
+        for name in [
+            "Host",
+            "connection",
+            "Transfer-Encoding",
+            "Proxy-Authorization",
+            "X-Forwarded-For",
+        ] {
+            let headers = HashMap::from([(name.to_string(), "x".to_string())]);
+            let result = block_on(http_request(
+                &config,
+                "GET",
+                "https://example.com/",
+                &headers,
+                None,
+            ));
+            assert!(
+                matches!(&result, ToolResult::Error(e) if e.starts_with("Header not allowed")),
+                "{} was accepted: {:?}",
+                name,
+                result
+            );
+        }
+        let headers = HashMap::from([("Authorization".to_string(), "Bearer t".to_string())]);
+        assert!(check_request_headers(&headers).is_ok());
+    }
+
+    #[test]
+    fn test_http_request_does_not_follow_redirects() {
+        // The guarded client hands 3xx back instead of following it to loopback
+        let start = spawn_redirect_server("http://127.0.0.1:9/admin".to_string());
+        let client = pinned_client("127.0.0.1", &[]).unwrap();
+        let result = block_on(send_capped(
+            &client,
+            "GET",
+            &start,
+            &HashMap::new(),
+            None,
+            1024,
+        ));
+        assert!(
+            matches!(&result, ToolResult::Success(s) if s.starts_with("HTTP 302")),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_finish_json_validation() {
+        let ok = finish_json(&serde_json::json!({"total": 42}));
+        assert!(matches!(ok, ToolResult::Success(s) if s == r#"{"total":42}"#));
+
+        let from_str = finish_json(&serde_json::json!(r#"{"items": [1, 2]}"#));
+        assert!(matches!(from_str, ToolResult::Success(s) if s == r#"{"items":[1,2]}"#));
+
+        assert!(matches!(
+            finish_json(&serde_json::json!("{not json")),
+            ToolResult::Error(_)
+        ));
+        assert!(matches!(
+            finish_json(&serde_json::json!([1, 2])),
+            ToolResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_cached_search_skips_repeat_requests() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache = SearchCache::new();
+        let config = CogOpsConfig::default();
+        let counter = AtomicUsize::new(0);
+        let calls = &counter;
+        let search = move || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            ToolResult::Success("results".to_string())
+        };
+
+        block_on(cached_search(&cache, &config, "AMD stock", search));
+        let second = block_on(cached_search(&cache, &config, "  amd STOCK ", search));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(second, ToolResult::Success(s) if s == "results"));
+    }
+
+    #[test]
+    fn test_search_cache_expiry_and_capacity() {
+        let cache = SearchCache::new();
+        let ttl = Duration::from_secs(60);
+        cache.insert("a", ToolResult::Success("1".into()), ttl, 2);
+        cache.insert("b", ToolResult::Success("2".into()), ttl, 2);
+        cache.insert("c", ToolResult::Success("3".into()), ttl, 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("c", ttl).is_some());
+        assert!(cache.get("c", Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_check_url_host_allowlist() {
+        let allowed = vec!["8.8.8.8".to_string(), "example.com".to_string()];
+        assert!(block_on(check_url_host("https://8.8.8.8/resolve", &allowed)).is_ok());
+        assert!(block_on(check_url_host("http://127.0.0.1/", &allowed)).is_err());
+        // Embedded public addresses stay reachable
+        assert!(!is_blocked_ip("64:ff9b::808:808".parse().unwrap()));
+        assert!(!is_blocked_ip("2002:808:808::".parse().unwrap()));
+
+        let err = block_on(check_url_host("https://other.org/", &allowed)).unwrap_err();
+        assert!(err.starts_with("blocked host"));
+        assert!(is_host_allowed("api.example.com", &allowed));
+        assert!(!is_host_allowed("notexample.com", &allowed));
+    }
 }
@@ -1,6 +1,8 @@
 use crate::{HistoryBuffer, TrajectoryPoint};
 use pyo3::prelude::*;
-use tracing::info;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use tracing::{info, warn};
 
 /// A specialized agent that executes a list of sub-agents in a linear sequence.
 ///
@@ -13,13 +15,21 @@ pub struct SequentialAgent {
     pub name: String,
     /// Ordered list of agent personas to invoke
     pub sub_agents: Vec<String>,
+    /// Extra attempts per step before the pipeline fails (0 disables retries)
+    #[pyo3(get, set)]
+    pub max_retries_per_step: usize,
 }
 
 #[pymethods]
 impl SequentialAgent {
     #[new]
-    pub fn new(name: String, sub_agents: Vec<String>) -> Self {
-        SequentialAgent { name, sub_agents }
+    #[pyo3(signature = (name, sub_agents, max_retries_per_step = 0))]
+    pub fn new(name: String, sub_agents: Vec<String>, max_retries_per_step: usize) -> Self {
+        SequentialAgent {
+            name,
+            sub_agents,
+            max_retries_per_step,
+        }
     }
 
     pub fn add_agent(&mut self, agent_name: String) {
@@ -37,11 +47,52 @@ impl SequentialAgent {
             self.name
         );
 
+        // Run task with specific agent persona
+        self.run_steps(buffer, |agent_name| {
+            graph
+                .run_task(task_id.clone(), buffer, Some(agent_name.to_string()))
+                .map(|_| ())
+        })?;
+
+        info!("[SequentialAgent] Pipeline finished.");
+        Ok(())
+    }
+}
+
+impl SequentialAgent {
+    /// Runs each sub-agent through `run_step`, retrying a failed step up to
+    /// `max_retries_per_step` times and recording a `StepRetry` point per retry.
+    fn run_steps<E, F>(&self, buffer: &HistoryBuffer, mut run_step: F) -> Result<(), E>
+    where
+        E: std::fmt::Display,
+        F: FnMut(&str) -> Result<(), E>,
+    {
         for agent_name in &self.sub_agents {
             info!("   ⬇️ Step: {}", agent_name);
 
-            // Run task with specific agent persona
-            let _ = graph.run_task(task_id.clone(), buffer, Some(agent_name.clone()))?;
+            let mut retries = 0;
+            loop {
+                match run_step(agent_name) {
+                    Ok(()) => break,
+                    Err(e) if retries < self.max_retries_per_step => {
+                        retries += 1;
+                        warn!(
+                            "   🔁 Step {} failed ({}), retry {}/{}",
+                            agent_name, e, retries, self.max_retries_per_step
+                        );
+                        let step = buffer.len() as u32 + 1;
+                        buffer.add(TrajectoryPoint::new(
+                            step,
+                            "StepRetry".to_string(),
+                            format!(
+                                "{} failed: {} (retry {}/{})",
+                                agent_name, e, retries, self.max_retries_per_step
+                            ),
+                        ));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
 
             // Add result marker
             let step = buffer.len() as u32 + 1;
@@ -51,8 +102,6 @@ impl SequentialAgent {
                 format!("Output: Step {} complete", step),
             ));
         }
-
-        info!("[SequentialAgent] Pipeline finished.");
         Ok(())
     }
 }
@@ -60,7 +109,8 @@ impl SequentialAgent {
 /// A specialized agent that initiates concurrent execution of multiple sub-agents.
 ///
 /// Leverages the zero-copy architecture to fork the state into independent
-/// branches for parallel simulation.
+/// branches for parallel simulation. Branch answers are combined into a single
+/// `AggregatedResult` point according to `strategy`.
 #[pyclass]
 pub struct ParallelAgent {
     /// Name of the workflow
@@ -68,31 +118,57 @@ pub struct ParallelAgent {
     pub name: String,
     /// List of agent personas to spawn concurrently
     pub sub_agents: Vec<String>,
+    /// How branch answers are combined: "first", "best" (highest score) or "vote"
+    #[pyo3(get, set)]
+    pub strategy: String,
 }
 
+/// Strategies accepted by `ParallelAgent::strategy`
+const PARALLEL_STRATEGIES: [&str; 3] = ["first", "best", "vote"];
+
 #[pymethods]
 impl ParallelAgent {
     #[new]
-    pub fn new(name: String, sub_agents: Vec<String>) -> Self {
-        ParallelAgent { name, sub_agents }
+    #[pyo3(signature = (name, sub_agents, strategy = "first".to_string()))]
+    pub fn new(name: String, sub_agents: Vec<String>, strategy: String) -> Self {
+        ParallelAgent {
+            name,
+            sub_agents,
+            strategy,
+        }
     }
 
     pub fn add_agent(&mut self, agent_name: String) {
         self.sub_agents.push(agent_name);
     }
 
+    /// Runs every branch, then aggregates their answers. `scorer`, if given, is
+    /// called with each answer and must return a float; otherwise answers are
+    /// scored by length.
+    #[pyo3(signature = (graph, task_id, buffer, scorer = None))]
     pub fn run(
         &self,
+        py: Python<'_>,
         graph: &crate::core::runner::AgentGraphPy,
         task_id: String,
         buffer: &HistoryBuffer,
+        scorer: Option<PyObject>,
     ) -> PyResult<()> {
+        if !PARALLEL_STRATEGIES.contains(&self.strategy.as_str()) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown strategy '{}' (expected one of: {})",
+                self.strategy,
+                PARALLEL_STRATEGIES.join(", ")
+            )));
+        }
+
         info!(
             "[ParallelAgent: {}] Spawning {} agents...",
             self.name,
             self.sub_agents.len()
         );
 
+        let mut answers = Vec::new();
         let mut _handles: Vec<()> = Vec::new();
         for agent_name in self.sub_agents.clone() {
             // Cloned sub_agents to allow iteration and move agent_name
@@ -105,8 +181,9 @@ impl ParallelAgent {
             let task_id_branch = format!("{}-{}", task_id, agent_name);
 
             // Concurrent execution (logic simulated sequentially for sync wrapper)
-            let _ =
+            let ctx =
                 graph_clone.run_task(task_id_branch, &branch_buffer, Some(agent_name.clone()))?;
+            answers.push(ctx.final_answer.unwrap_or_default());
 
             // Merge result back
             let step = buffer.len() as u32 + 1;
@@ -118,10 +195,81 @@ impl ParallelAgent {
         }
 
         info!("[ParallelAgent] All threads joined.");
+        if answers.is_empty() {
+            return Ok(());
+        }
+
+        let (winner, scores) =
+            aggregate_answers(&self.strategy, &answers, |answer| match &scorer {
+                Some(f) => f.call1(py, (answer,))?.extract::<f64>(py),
+                None => Ok(answer.chars().count() as f64),
+            })?;
+        let branches: Vec<serde_json::Value> = self
+            .sub_agents
+            .iter()
+            .zip(&scores)
+            .map(|(agent, score)| serde_json::json!({ "agent": agent, "score": score }))
+            .collect();
+        let summary = serde_json::json!({
+            "strategy": self.strategy,
+            "winner": self.sub_agents[winner],
+            "answer": answers[winner],
+            "branches": branches,
+        });
+
+        info!(
+            "   🏆 [ParallelAgent] {} selected {}",
+            self.strategy, self.sub_agents[winner]
+        );
+        let step = buffer.len() as u32 + 1;
+        buffer.add(TrajectoryPoint::new(
+            step,
+            "AggregatedResult".to_string(),
+            summary.to_string(),
+        ));
         Ok(())
     }
 }
 
+/// Scores every branch answer and picks one according to `strategy`:
+/// "first" keeps the first branch, "best" the highest score, and "vote" the
+/// most common answer (ties broken by score). Returns the winner and all scores.
+fn aggregate_answers<E, F>(
+    strategy: &str,
+    answers: &[String],
+    mut score: F,
+) -> Result<(usize, Vec<f64>), E>
+where
+    F: FnMut(&str) -> Result<f64, E>,
+{
+    let scores = answers
+        .iter()
+        .map(|a| score(a))
+        .collect::<Result<Vec<f64>, E>>()?;
+
+    let rank = |i: usize| -> (usize, f64) {
+        let votes = match strategy {
+            "vote" => answers
+                .iter()
+                .filter(|a| a.trim() == answers[i].trim())
+                .count(),
+            _ => 0,
+        };
+        (votes, scores[i])
+    };
+
+    let mut winner = 0;
+    if strategy != "first" {
+        for i in 1..answers.len() {
+            // Strictly greater, so ties keep the earlier branch
+            if rank(i).partial_cmp(&rank(winner)) == Some(std::cmp::Ordering::Greater) {
+                winner = i;
+            }
+        }
+    }
+    Ok((winner, scores))
+}
+
 /// A specialized agent that executes a target persona iteratively.
 ///
 /// The loop continues until the specified maximum number of iterations is
@@ -186,3 +334,377 @@ impl LoopAgent {
         Ok(())
     }
 }
+
+/// A specialized agent that executes sub-agents along a directed acyclic graph.
+///
+/// Nodes run in topological order; independent nodes of the same level run
+/// concurrently, each on its own copy of the trajectory. A join node sees the
+/// base history plus the output of every ancestor, merged in topological order.
+#[pyclass]
+pub struct GraphWorkflow {
+    /// Name of the workflow
+    #[pyo3(get)]
+    pub name: String,
+    /// Node name -> agent persona, in insertion order
+    pub nodes: Vec<(String, String)>,
+    /// Dependency edges as (from, to)
+    pub edges: Vec<(String, String)>,
+}
+
+#[pymethods]
+impl GraphWorkflow {
+    #[new]
+    pub fn new(name: String) -> Self {
+        GraphWorkflow {
+            name,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, name: String, agent_name: String) -> PyResult<()> {
+        if self.agent_for(&name).is_some() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Duplicate node: {}",
+                name
+            )));
+        }
+        self.nodes.push((name, agent_name));
+        Ok(())
+    }
+
+    /// Adds a dependency `from -> to`; rejects unknown nodes and edges that close a cycle.
+    pub fn add_edge(&mut self, from: String, to: String) -> PyResult<()> {
+        for node in [&from, &to] {
+            if self.agent_for(node).is_none() {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown node: {}",
+                    node
+                )));
+            }
+        }
+        self.edges.push((from, to));
+        if let Err(e) = self.levels() {
+            self.edges.pop();
+            return Err(pyo3::exceptions::PyValueError::new_err(e));
+        }
+        Ok(())
+    }
+
+    pub fn run(
+        &self,
+        py: Python<'_>,
+        graph: &crate::core::runner::AgentGraphPy,
+        task_id: String,
+        buffer: &HistoryBuffer,
+    ) -> PyResult<()> {
+        let levels = self
+            .levels()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        info!(
+            "🕸️ [GraphWorkflow: {}] Executing {} nodes in {} levels...",
+            self.name,
+            self.nodes.len(),
+            levels.len()
+        );
+
+        let inner = graph.shared();
+        let runtime = inner.runtime.clone();
+        // Release the GIL so Python middleware on the worker threads can run
+        py.allow_threads(|| {
+            runtime.block_on(self.execute(
+                &levels,
+                &task_id,
+                buffer,
+                move |node_task, agent, branch| {
+                    let graph = inner.clone();
+                    async move {
+                        graph
+                            .run_task(&node_task, &branch, Some(agent.as_str()))
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    }
+                },
+            ))
+        })
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+
+        info!("[GraphWorkflow] All nodes complete.");
+        Ok(())
+    }
+}
+
+impl GraphWorkflow {
+    /// Groups nodes into levels whose members only depend on earlier levels.
+    /// Fails when the edges contain a cycle.
+    pub fn levels(&self) -> Result<Vec<Vec<String>>, String> {
+        let mut in_degree: HashMap<&str, usize> =
+            self.nodes.iter().map(|(n, _)| (n.as_str(), 0)).collect();
+        for (_, to) in &self.edges {
+            *in_degree.entry(to.as_str()).or_insert(0) += 1;
+        }
+
+        let mut remaining: Vec<&str> = self.nodes.iter().map(|(n, _)| n.as_str()).collect();
+        let mut levels = Vec::new();
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<&str>, Vec<&str>) =
+                remaining.iter().partition(|n| in_degree[*n] == 0);
+            if ready.is_empty() {
+                return Err(format!(
+                    "Cycle detected among nodes: {}",
+                    blocked.join(", ")
+                ));
+            }
+            for (from, to) in &self.edges {
+                if ready.contains(&from.as_str()) {
+                    if let Some(d) = in_degree.get_mut(to.as_str()) {
+                        *d -= 1;
+                    }
+                }
+            }
+            levels.push(ready.iter().map(|n| n.to_string()).collect());
+            remaining = blocked;
+        }
+        Ok(levels)
+    }
+
+    fn agent_for(&self, node: &str) -> Option<&String> {
+        self.nodes.iter().find(|(n, _)| n == node).map(|(_, a)| a)
+    }
+
+    /// Transitive predecessors of every node
+    fn ancestors(&self) -> HashMap<String, HashSet<String>> {
+        let mut result = HashMap::new();
+        for (node, _) in &self.nodes {
+            let mut seen = HashSet::new();
+            let mut stack = vec![node.as_str()];
+            while let Some(current) = stack.pop() {
+                for (from, to) in &self.edges {
+                    if to == current && seen.insert(from.clone()) {
+                        stack.push(from.as_str());
+                    }
+                }
+            }
+            result.insert(node.clone(), seen);
+        }
+        result
+    }
+
+    /// Runs every level concurrently through `run_node`, then appends each
+    /// node's output to `buffer` in topological order.
+    async fn execute<F, Fut>(
+        &self,
+        levels: &[Vec<String>],
+        task_id: &str,
+        buffer: &HistoryBuffer,
+        run_node: F,
+    ) -> Result<(), String>
+    where
+        F: Fn(String, String, HistoryBuffer) -> Fut,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let base = buffer.get_raw();
+        let ancestors = self.ancestors();
+        let order: Vec<&String> = levels.iter().flatten().collect();
+        let mut produced: HashMap<String, Vec<TrajectoryPoint>> = HashMap::new();
+
+        for level in levels {
+            let mut running = Vec::new();
+            for node in level {
+                let agent = self.agent_for(node).cloned().unwrap_or_default();
+                info!("   🔀 Node: {} ({})", node, agent);
+
+                // `fork()` shares storage, so each branch gets its own copy
+                let branch = HistoryBuffer::new();
+                branch.append_renumbered(base.clone());
+                for prior in order.iter().filter(|p| ancestors[node].contains(**p)) {
+                    branch.append_renumbered(produced[*prior].clone());
+                }
+                let start = branch.len();
+
+                let task = run_node(
+                    format!("{}-{}", task_id, node),
+                    agent.clone(),
+                    branch.clone(),
+                );
+                running.push((node, agent, start, branch, tokio::spawn(task)));
+            }
+
+            let mut first_error = None;
+            for (node, agent, start, branch, handle) in running {
+                let outcome = match handle.await {
+                    Ok(result) => result,
+                    Err(e) => Err(format!("task panicked: {}", e)),
+                };
+                if let Err(e) = outcome {
+                    if first_error.is_none() {
+                        first_error = Some(format!("Node '{}' failed: {}", node, e));
+                    }
+                    continue;
+                }
+
+                let step = branch.len() as u32 + 1;
+                branch.add(TrajectoryPoint::new(
+                    step,
+                    format!("ResultFrom_{}", agent),
+                    format!("Graph node {} complete", node),
+                ));
+                produced.insert(node.clone(), branch.get_raw().split_off(start));
+            }
+            if let Some(e) = first_error {
+                return Err(e);
+            }
+        }
+
+        for node in order {
+            buffer.append_renumbered(produced.remove(node).unwrap_or_default());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diamond() -> GraphWorkflow {
+        let mut wf = GraphWorkflow::new("diamond".to_string());
+        for node in ["A", "B", "C", "D"] {
+            wf.add_node(node.to_string(), format!("agent_{}", node))
+                .unwrap();
+        }
+        for (from, to) in [("A", "B"), ("A", "C"), ("B", "D"), ("C", "D")] {
+            wf.add_edge(from.to_string(), to.to_string()).unwrap();
+        }
+        wf
+    }
+
+    #[test]
+    fn test_graph_workflow_diamond() {
+        let wf = diamond();
+        let levels = wf.levels().unwrap();
+        assert_eq!(levels, vec![vec!["A"], vec!["B", "C"], vec!["D"]]);
+
+        let buffer = HistoryBuffer::new();
+        buffer.add(TrajectoryPoint::new(
+            0,
+            "Task".to_string(),
+            "start".to_string(),
+        ));
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(
+                wf.execute(&levels, "t1", &buffer, |task, agent, branch| async move {
+                    let seen: Vec<String> =
+                        branch.get_raw().into_iter().map(|p| p.action).collect();
+                    branch.add(TrajectoryPoint::new(
+                        0,
+                        format!("Ran_{}", task),
+                        format!("{} saw {}", agent, seen.join(",")),
+                    ));
+                    Ok(())
+                }),
+            )
+            .unwrap();
+
+        let points = buffer.get_raw();
+        let actions: Vec<&str> = points.iter().map(|p| p.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                "Task",
+                "Ran_t1-A",
+                "ResultFrom_agent_A",
+                "Ran_t1-B",
+                "ResultFrom_agent_B",
+                "Ran_t1-C",
+                "ResultFrom_agent_C",
+                "Ran_t1-D",
+                "ResultFrom_agent_D",
+            ]
+        );
+        // Branches are isolated; the join node sees both of them exactly once
+        assert!(points[5]
+            .thought
+            .ends_with("saw Task,Ran_t1-A,ResultFrom_agent_A"));
+        assert!(points[7].thought.ends_with(
+            "saw Task,Ran_t1-A,ResultFrom_agent_A,Ran_t1-B,ResultFrom_agent_B,Ran_t1-C,ResultFrom_agent_C"
+        ));
+        assert!(points.iter().enumerate().all(|(i, p)| p.step == i as u32));
+    }
+
+    #[test]
+    fn test_sequential_agent_retries_failed_step() {
+        let agent = SequentialAgent::new(
+            "pipeline".to_string(),
+            vec!["flaky".to_string(), "steady".to_string()],
+            2,
+        );
+        let buffer = HistoryBuffer::new();
+        let mut calls: HashMap<String, usize> = HashMap::new();
+        agent
+            .run_steps(&buffer, |name| {
+                let count = calls.entry(name.to_string()).or_insert(0);
+                *count += 1;
+                if name == "flaky" && *count == 1 {
+                    Err("model call failed".to_string())
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap();
+
+        assert_eq!(calls["flaky"], 2);
+        assert_eq!(calls["steady"], 1);
+        let actions: Vec<String> = buffer.get_raw().into_iter().map(|p| p.action).collect();
+        assert_eq!(
+            actions,
+            vec!["StepRetry", "ResultFrom_flaky", "ResultFrom_steady"]
+        );
+
+        let no_retry = SequentialAgent::new("strict".to_string(), vec!["flaky".to_string()], 0);
+        let result = no_retry.run_steps(&HistoryBuffer::new(), |_| Err("down".to_string()));
+        assert_eq!(result, Err("down".to_string()));
+    }
+
+    #[test]
+    fn test_aggregate_answers_strategies() {
+        let answers: Vec<String> = ["42", "about 41 or 42", "42", "unsure"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let by_length = |a: &str| Ok::<f64, String>(a.chars().count() as f64);
+
+        let (winner, scores) = aggregate_answers("best", &answers, by_length).unwrap();
+        assert_eq!(winner, 1);
+        assert_eq!(scores, vec![2.0, 14.0, 2.0, 6.0]);
+
+        let confidence = |a: &str| Ok::<f64, String>(if a == "unsure" { 0.9 } else { 0.1 });
+        let (winner, _) = aggregate_answers("best", &answers, confidence).unwrap();
+        assert_eq!(winner, 3);
+
+        let (winner, _) = aggregate_answers("vote", &answers, by_length).unwrap();
+        assert_eq!(winner, 0);
+        let (winner, _) = aggregate_answers("first", &answers, by_length).unwrap();
+        assert_eq!(winner, 0);
+
+        let failing = |_: &str| Err::<f64, String>("scorer raised".to_string());
+        assert!(aggregate_answers("best", &answers, failing).is_err());
+    }
+
+    #[test]
+    fn test_graph_workflow_rejects_cycles() {
+        let mut wf = diamond();
+        assert!(wf.add_edge("D".to_string(), "A".to_string()).is_err());
+        assert_eq!(wf.edges.len(), 4);
+        assert!(wf.add_edge("A".to_string(), "Z".to_string()).is_err());
+
+        wf.edges.push(("D".to_string(), "B".to_string()));
+        let err = wf.levels().unwrap_err();
+        assert!(err.contains("Cycle detected") && err.contains("B") && err.contains("D"));
+    }
+}
@@ -36,18 +36,22 @@ pub struct EvolutionConfig {
     pub population_size: usize,
     #[pyo3(get, set)]
     pub mutation_rate: f32,
+    /// Seed for reproducible evolution runs (None = OS entropy)
+    #[pyo3(get, set)]
+    pub seed: Option<u64>,
 }
 
 #[pymethods]
 impl EvolutionConfig {
     #[new]
-    #[pyo3(signature = (allow_synthesis = true, safety_level = "strict", max_tools = 50, population_size = 5, mutation_rate = 0.1))]
+    #[pyo3(signature = (allow_synthesis = true, safety_level = "strict", max_tools = 50, population_size = 5, mutation_rate = 0.1, seed = None))]
     pub fn new(
         allow_synthesis: bool,
         safety_level: &str,
         max_tools: usize,
         population_size: usize,
         mutation_rate: f32,
+        seed: Option<u64>,
     ) -> Self {
         EvolutionConfig {
             allow_synthesis,
@@ -55,13 +59,14 @@ impl EvolutionConfig {
             max_tools,
             population_size,
             mutation_rate,
+            seed,
         }
     }
 }
 
 impl Default for EvolutionConfig {
     fn default() -> Self {
-        Self::new(true, "strict", 50, 5, 0.1)
+        Self::new(true, "strict", 50, 5, 0.1, None)
     }
 }
 
@@ -25,6 +25,10 @@ pub struct AgentGenome {
     pub temperature: f32,
     #[pyo3(get)]
     pub fitness_score: f32,
+    /// Every score recorded for this lineage, oldest first
+    #[pyo3(get)]
+    #[serde(default)]
+    pub fitness_history: Vec<f32>,
 }
 
 #[pymethods]
@@ -38,7 +42,43 @@ impl AgentGenome {
             tools,
             temperature: 0.7,
             fitness_score: 0.0,
+            fitness_history: Vec::new(),
+        }
+    }
+
+    /// Record a new score, keeping `fitness_score` as the latest
+    pub fn record_fitness(&mut self, score: f32) {
+        self.fitness_score = score;
+        self.fitness_history.push(score);
+    }
+
+    /// Highest score ever recorded (0.0 without history)
+    pub fn best_fitness(&self) -> f32 {
+        self.fitness_history
+            .iter()
+            .copied()
+            .fold(None, |best: Option<f32>, s| {
+                Some(best.map_or(s, |b| b.max(s)))
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Least-squares slope of fitness over history (0.0 with < 2 points)
+    pub fn trend(&self) -> f32 {
+        let n = self.fitness_history.len();
+        if n < 2 {
+            return 0.0;
         }
+        let mean_x = (n - 1) as f32 / 2.0;
+        let mean_y = self.fitness_history.iter().sum::<f32>() / n as f32;
+        let mut num = 0.0;
+        let mut den = 0.0;
+        for (i, &y) in self.fitness_history.iter().enumerate() {
+            let dx = i as f32 - mean_x;
+            num += dx * (y - mean_y);
+            den += dx * dx;
+        }
+        num / den
     }
 
     pub fn __repr__(&self) -> String {
@@ -54,23 +94,36 @@ impl AgentGenome {
 pub struct PopulationEngine {
     config: EvolutionConfig,
     population: Vec<AgentGenome>,
+    /// Tool pool that skillset mutation draws from
+    available_tools: Vec<String>,
+    /// Drives mutation and selection; seeded from `config.seed` when set
+    rng: StdRng,
 }
 
 #[pymethods]
 impl PopulationEngine {
     #[new]
-    #[pyo3(signature = (config = None))]
-    pub fn new(config: Option<EvolutionConfig>) -> Self {
+    #[pyo3(signature = (config = None, available_tools = None))]
+    pub fn new(config: Option<EvolutionConfig>, available_tools: Option<Vec<String>>) -> Self {
         let cfg = config.unwrap_or_default();
         info!(
             "🧬 [PopulationEngine] Initialized (pop_size={}, mutation={:.2})",
             cfg.population_size, cfg.mutation_rate
         );
 
-        PopulationEngine {
+        let rng = match cfg.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut engine = PopulationEngine {
             config: cfg,
             population: Vec::new(),
-        }
+            available_tools: Vec::new(),
+            rng,
+        };
+        engine.set_available_tools(available_tools.unwrap_or_default());
+        engine
     }
 
     /// Initialize population from a base genome
@@ -119,12 +172,11 @@ impl PopulationEngine {
         next_gen.push(elite);
 
         // Generate rest from top 50%
-        let mut rng = thread_rng();
         let parent_pool_size = (self.config.population_size + 1) / 2;
 
         while next_gen.len() < self.config.population_size {
             // Select random score-weighted parent
-            let parent_idx = rng.gen_range(0..parent_pool_size);
+            let parent_idx = self.rng.gen_range(0..parent_pool_size);
             let mut child = self.population[parent_idx].clone();
 
             child.id = format!("gen{}_v{}", child.generation + 1, next_gen.len());
@@ -142,8 +194,19 @@ impl PopulationEngine {
     /// Score a genome (external benchmark callback)
     pub fn update_fitness(&mut self, genome_id: String, score: f32) {
         if let Some(genome) = self.population.iter_mut().find(|g| g.id == genome_id) {
-            genome.fitness_score = score;
+            genome.record_fitness(score);
+        }
+    }
+
+    /// Set the tool pool used for skillset mutation (deduplicated)
+    pub fn set_available_tools(&mut self, tools: Vec<String>) {
+        let mut pool: Vec<String> = Vec::with_capacity(tools.len());
+        for tool in tools {
+            if !pool.contains(&tool) {
+                pool.push(tool);
+            }
         }
+        self.available_tools = pool;
     }
 
     /// Get current population
@@ -153,8 +216,8 @@ impl PopulationEngine {
 }
 
 impl PopulationEngine {
-    fn mutate(&self, genome: &mut AgentGenome) {
-        let mut rng = thread_rng();
+    fn mutate(&mut self, genome: &mut AgentGenome) {
+        let rng = &mut self.rng;
 
         // Mutate Temperature (Hyperparameter)
         if rng.gen::<f32>() < self.config.mutation_rate {
@@ -174,14 +237,117 @@ impl PopulationEngine {
             if additions.is_empty() {
                 return;
             }
-            let variant = additions.choose(&mut rng).unwrap_or(&additions[0]);
+            let variant = additions.choose(rng).unwrap_or(&additions[0]);
 
             if !genome.system_prompt.contains(variant) {
                 genome.system_prompt.push_str(variant);
             }
         }
 
-        // Mutate Tools (Skillset) -- simple toggle for demo
-        // In real system, this would add/remove tools from registry
+        // Mutate Tools (Skillset): add an unused pool tool or drop one
+        if !self.available_tools.is_empty() && rng.gen::<f32>() < self.config.mutation_rate {
+            let unused: Vec<&String> = self
+                .available_tools
+                .iter()
+                .filter(|t| !genome.tools.contains(t))
+                .collect();
+            let add = !unused.is_empty() && (genome.tools.is_empty() || rng.gen_bool(0.5));
+
+            if add {
+                if let Some(tool) = unused.choose(rng) {
+                    genome.tools.push((*tool).clone());
+                }
+            } else if !genome.tools.is_empty() {
+                let idx = rng.gen_range(0..genome.tools.len());
+                genome.tools.remove(idx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_run(seed: u64) -> String {
+        let config = EvolutionConfig::new(true, "strict", 50, 6, 0.9, Some(seed));
+        let mut engine = PopulationEngine::new(Some(config), None);
+        let base = AgentGenome::new(
+            "base".to_string(),
+            "You are helpful.".to_string(),
+            vec!["web_search".to_string()],
+        );
+        engine.init_population(&base);
+        for (i, genome) in engine.get_population().iter().enumerate() {
+            engine.update_fitness(genome.id.clone(), i as f32 * 0.1);
+        }
+        engine.evolve_generation();
+        serde_json::to_string(&engine.get_population()).unwrap()
+    }
+
+    #[test]
+    fn same_seed_reproduces_population() {
+        assert_eq!(seeded_run(42), seeded_run(42));
+    }
+
+    #[test]
+    fn tool_mutation_stays_within_pool() {
+        let pool: Vec<String> = ["web_search", "fetch_url", "calculate", "finish"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let config = EvolutionConfig::new(true, "strict", 50, 8, 0.8, Some(7));
+        let mut engine = PopulationEngine::new(Some(config), Some(pool.clone()));
+        let base = AgentGenome::new(
+            "base".to_string(),
+            "You are helpful.".to_string(),
+            vec!["web_search".to_string()],
+        );
+        engine.init_population(&base);
+
+        let mut seen_change = false;
+        for _ in 0..20 {
+            for genome in engine.get_population() {
+                engine.update_fitness(genome.id.clone(), genome.tools.len() as f32);
+            }
+            engine.evolve_generation();
+
+            for genome in engine.get_population() {
+                assert!(genome.tools.iter().all(|t| pool.contains(t)));
+                let mut deduped = genome.tools.clone();
+                deduped.sort();
+                deduped.dedup();
+                assert_eq!(deduped.len(), genome.tools.len());
+                if genome.tools != base.tools {
+                    seen_change = true;
+                }
+            }
+        }
+        assert!(seen_change);
+    }
+
+    #[test]
+    fn fitness_history_accumulates() {
+        let mut engine = PopulationEngine::new(None, None);
+        let base = AgentGenome::new("base".to_string(), "prompt".to_string(), Vec::new());
+        engine.init_population(&base);
+
+        for score in [0.2, 0.5, 0.4, 0.9] {
+            engine.update_fitness("base_elite".to_string(), score);
+        }
+        let genome = engine
+            .get_population()
+            .into_iter()
+            .find(|g| g.id == "base_elite")
+            .unwrap();
+
+        assert_eq!(genome.fitness_history, vec![0.2, 0.5, 0.4, 0.9]);
+        assert_eq!(genome.fitness_score, 0.9);
+        assert_eq!(genome.best_fitness(), 0.9);
+        assert!(genome.trend() > 0.0);
+
+        let restored: AgentGenome =
+            serde_json::from_str(&serde_json::to_string(&genome).unwrap()).unwrap();
+        assert_eq!(restored.fitness_history, genome.fitness_history);
     }
 }
@@ -1,16 +1,104 @@
 //! Safety Sandbox
 //!
 //! Verifies generated code to prevent dangerous operations.
+//!
+//! Code is parsed with Python's own `ast` module in a child interpreter and
+//! rejected unless every import is on an allowlist of pure-computation
+//! modules. Dangerous builtins, private attributes and attributes that reach
+//! other modules (e.g. `statistics.sys`) are rejected too.
+//! This is a static filter, not isolation: run vetted code under seccomp,
+//! namespaces or a container before exposing it to untrusted input.
 
 use super::{EvolutionConfig, GeneratedTool};
 use pyo3::prelude::*;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+/// Walks the AST of the code on stdin and prints the first violation, if any.
+/// `sys.argv[1]` holds the comma-separated importable top-level modules.
+const AST_CHECKER: &str = r#"
+import ast, sys
+allowed_modules = set(sys.argv[1].split(","))
+# Module names reachable as attributes of allowed modules (json.codecs,
+# statistics.sys, ...) are as dangerous as importing them
+blocked_attrs = (set(getattr(sys, "stdlib_module_names", ())) | {
+    "os", "sys", "posix", "nt", "subprocess", "builtins", "importlib", "codecs",
+}) - allowed_modules
+banned_names = {
+    "eval", "exec", "compile", "open", "input", "breakpoint", "__import__",
+    "getattr", "setattr", "delattr", "globals", "locals", "vars", "__builtins__",
+}
+def violation(code):
+    try:
+        tree = ast.parse(code)
+    except SyntaxError as e:
+        return "syntax error: %s" % e.msg
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            modules = [alias.name for alias in node.names]
+        elif isinstance(node, ast.ImportFrom):
+            if node.level or node.module is None:
+                return "relative import"
+            modules = [node.module]
+            for alias in node.names:
+                if alias.name == "*":
+                    return "star import from '%s'" % node.module
+                if alias.name.startswith("_") or alias.name in blocked_attrs:
+                    return "import of '%s' from '%s'" % (alias.name, node.module)
+        else:
+            modules = []
+        for module in modules:
+            if module.split(".")[0] not in allowed_modules:
+                return "import '%s' is not allowed" % module
+        if isinstance(node, ast.Name) and node.id in banned_names:
+            return "forbidden name '%s'" % node.id
+        if isinstance(node, ast.Attribute):
+            if node.attr.startswith("_"):
+                return "private attribute '%s'" % node.attr
+            if node.attr in blocked_attrs:
+                return "attribute '%s' reaches a blocked module" % node.attr
+    return ""
+sys.stdout.write(violation(sys.stdin.read()))
+"#;
+
+/// Modules vetted code may import: pure computation, no I/O or process control
+const ALLOWED_IMPORTS: [&str; 22] = [
+    "abc",
+    "bisect",
+    "cmath",
+    "collections",
+    "copy",
+    "datetime",
+    "decimal",
+    "enum",
+    "fractions",
+    "functools",
+    "heapq",
+    "itertools",
+    "json",
+    "math",
+    "numbers",
+    "operator",
+    "random",
+    "re",
+    "statistics",
+    "string",
+    "textwrap",
+    "time",
+];
+
+/// Wall-clock limit for one checker run
+const CHECKER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most checker output read back; a violation message is far shorter
+const MAX_CHECKER_OUTPUT: u64 = 4096;
+
 /// Sandbox for verifying tool safety
 #[pyclass]
 pub struct SafetySandbox {
     config: EvolutionConfig,
-    banned_imports: Vec<String>,
+    allowed_imports: Vec<String>,
 }
 
 #[pymethods]
@@ -20,47 +108,131 @@ impl SafetySandbox {
     pub fn new(config: Option<EvolutionConfig>) -> Self {
         let cfg = config.unwrap_or_default();
 
-        // Define dangerous imports
-        let banned = vec![
-            "os".to_string(),
-            "sys".to_string(),
-            "subprocess".to_string(),
-            "shutil".to_string(),
-            "socket".to_string(),
-            "requests".to_string(), // In strict mode
-        ];
-
         info!("[Sandbox] Initialized checks");
 
         SafetySandbox {
             config: cfg,
-            banned_imports: banned,
+            allowed_imports: ALLOWED_IMPORTS.iter().map(|m| m.to_string()).collect(),
         }
     }
 
     /// Verify tool code for safety
     pub fn verify(&self, tool: &GeneratedTool) -> bool {
-        // 1. Check for banned imports
-        for banned in &self.banned_imports {
-            if tool.code.contains(&format!("import {}", banned))
-                || tool.code.contains(&format!("from {} import", banned))
-            {
-                warn!(
-                    "🚫 [Sandbox] Rejected '{}': contains banned import '{}'",
-                    tool.name, banned
-                );
-                return false;
+        match self.vet(&tool.code) {
+            Ok(()) => {
+                // Execution verification (Docker containers required for production)
+                info!("[Sandbox] Verified '{}': Code looks safe", tool.name);
+                true
+            }
+            Err(reason) => {
+                warn!("🚫 [Sandbox] Rejected '{}': {}", tool.name, reason);
+                false
+            }
+        }
+    }
+}
+
+impl SafetySandbox {
+    /// Parses `code` with Python's `ast` module and returns the first violation.
+    /// Blocks for up to `CHECKER_TIMEOUT`; async callers should run it on a
+    /// blocking thread. Fails closed when the checker cannot run or times out.
+    pub fn vet(&self, code: &str) -> Result<(), String> {
+        let unavailable = |e: std::io::Error| format!("checker unavailable: {}", e);
+        let mut child = Command::new("python3")
+            .arg("-I")
+            .arg("-c")
+            .arg(AST_CHECKER)
+            .arg(self.allowed_imports.join(","))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(unavailable)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(code.as_bytes()) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(unavailable(e));
             }
         }
 
-        // 2. Check for dangerous calls (naive check)
-        if tool.code.contains("eval(") || tool.code.contains("exec(") {
-            warn!("🚫 [Sandbox] Rejected '{}': contains eval/exec", tool.name);
-            return false;
+        let deadline = Instant::now() + CHECKER_TIMEOUT;
+        let status = loop {
+            match child.try_wait().map_err(unavailable)? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "checker timed out after {}s",
+                        CHECKER_TIMEOUT.as_secs()
+                    ));
+                }
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        if !status.success() {
+            return Err(format!("checker exited with {}", status));
         }
 
-        // 3. Execution verification (Docker containers required for production)
-        info!("[Sandbox] Verified '{}': Code looks safe", tool.name);
-        true
+        let mut violation = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            let mut bytes = Vec::new();
+            stdout
+                .take(MAX_CHECKER_OUTPUT)
+                .read_to_end(&mut bytes)
+                .map_err(unavailable)?;
+            violation = String::from_utf8_lossy(&bytes).into_owned();
+        }
+        if violation.is_empty() {
+            Ok(())
+        } else {
+            Err(violation)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ast_check_catches_obfuscated_escapes() {
+        let sandbox = SafetySandbox::new(None);
+        for code in [
+            "import os",
+            "import  os",
+            "import os.path as p",
+            "from subprocess import run",
+            "__import__('os').system('id')",
+            "print(open('/etc/passwd').read())",
+            "getattr(__builtins__, 'ev' + 'al')('1')",
+            "f = eval\nf('1')",
+            "().__class__.__bases__[0].__subclasses__()",
+            "def broken(:",
+            "import posix; posix.system('id')",
+            "import _posixsubprocess",
+            "import pickle",
+            "import marshal",
+            "from . import sibling",
+            "from math import *",
+            "import statistics\nstatistics.sys.modules['os'].system('id')",
+            "from fractions import sys",
+            "import json\njson.codecs.open('/tmp/x', 'w')",
+            "import random\nrandom._os.system('id')",
+        ] {
+            assert!(sandbox.vet(code).is_err(), "accepted: {}", code);
+        }
+        for code in [
+            "import math\nprint(math.sqrt(16) * 2)",
+            "print('import os is just text')",
+            "import json, re\nprint(json.dumps(re.findall(r'\\d+', 'a1b22')))",
+            "from datetime import datetime, timedelta\nprint(datetime(2024, 1, 1) + timedelta(days=1))",
+            "from collections import Counter\nprint(Counter('banana').most_common(1))",
+            "import datetime\nprint(datetime.time(12, 30))",
+            "xs = [3, 1, 2]\nys = xs.copy()\nys.sort()\nprint(ys)",
+        ] {
+            assert!(sandbox.vet(code).is_ok(), "rejected: {}", code);
+        }
     }
 }
@@ -3,26 +3,43 @@
 //! Generates code for new tools based on agent requirements.
 
 use super::GeneratedTool;
+use crate::intel::reviewer::CodeQualityGuard;
 use pyo3::prelude::*;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Synthesizes new tools from descriptions
 #[pyclass]
 pub struct ToolSynthesizer {
     model_name: String,
+    guard: CodeQualityGuard,
 }
 
 #[pymethods]
 impl ToolSynthesizer {
     #[new]
-    #[pyo3(signature = (model_name = "gpt-4-turbo"))]
-    pub fn new(model_name: &str) -> Self {
+    #[pyo3(signature = (model_name = "gpt-4-turbo", allowed_hosts = None))]
+    pub fn new(model_name: &str, allowed_hosts: Option<Vec<String>>) -> Self {
         info!("🧬 [Synthesizer] Initialized with backend: {}", model_name);
         ToolSynthesizer {
             model_name: model_name.to_string(),
+            guard: CodeQualityGuard::new(allowed_hosts),
         }
     }
 
+    /// Review a generated tool's code, marking it verified only on a clean review.
+    /// Returns the updated tool and the review findings.
+    pub fn verify_tool(&self, mut tool: GeneratedTool) -> (GeneratedTool, Vec<String>) {
+        let (approved, findings) = self.guard.review(tool.code.clone());
+        if !approved {
+            warn!(
+                "🧬 [Synthesizer] Tool '{}' failed review: {:?}",
+                tool.name, findings
+            );
+        }
+        tool.verified = approved;
+        (tool, findings)
+    }
+
     /// Simulate synthesis of a tool (In production, this calls an LLM)
     pub fn synthesize(
         &self,
@@ -36,3 +53,31 @@ impl ToolSynthesizer {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(code: &str) -> GeneratedTool {
+        GeneratedTool {
+            name: "t".to_string(),
+            code: code.to_string(),
+            description: String::new(),
+            created_at: 0,
+            verified: true,
+        }
+    }
+
+    #[test]
+    fn test_verify_tool_gates_on_review() {
+        let synth = ToolSynthesizer::new("gpt-4-turbo", None);
+
+        let (clean, findings) = synth.verify_tool(tool("def f(x):\n    return x * 2\n"));
+        assert!(clean.verified);
+        assert!(findings.is_empty());
+
+        let (dirty, findings) = synth.verify_tool(tool("import os\nos.system('ls')\n"));
+        assert!(!dirty.verified);
+        assert_eq!(findings.len(), 1);
+    }
+}
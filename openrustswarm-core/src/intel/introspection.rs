@@ -1,8 +1,13 @@
 use crate::core::runner::AgentGraphPy;
 use crate::{HistoryBuffer, TrajectoryPoint};
 use pyo3::prelude::*;
+use serde_json::json;
+use std::collections::BTreeMap;
 use tracing::{info, warn};
 
+/// Consecutive repeats of the same action that count as a loop
+const REPETITION_THRESHOLD: usize = 3;
+
 /// Implements a self-correction pattern (Academic research).
 /// Wraps a Generator Agent and a Critic Agent in a self-correction loop.
 #[pyclass]
@@ -18,6 +23,54 @@ impl IntrospectionEngine {
         IntrospectionEngine { max_attempts }
     }
 
+    /// Summarizes a trajectory as JSON: action counts, tool errors,
+    /// repetition detection and average thought length.
+    pub fn analyze_buffer(&self, buffer: &HistoryBuffer) -> String {
+        let points = buffer.get_raw();
+
+        let mut action_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut max_run = 0;
+        let mut run = 0;
+        let mut repeated_action: Option<&str> = None;
+        let mut prev: Option<&str> = None;
+        for p in &points {
+            *action_counts.entry(p.action.as_str()).or_insert(0) += 1;
+            run = if prev == Some(p.action.as_str()) {
+                run + 1
+            } else {
+                1
+            };
+            if run > max_run {
+                max_run = run;
+                repeated_action = Some(p.action.as_str());
+            }
+            prev = Some(p.action.as_str());
+        }
+
+        let tool_errors = action_counts.get("ToolError").copied().unwrap_or(0);
+        let avg_thought_len = if points.is_empty() {
+            0.0
+        } else {
+            points
+                .iter()
+                .map(|p| p.thought.chars().count())
+                .sum::<usize>() as f64
+                / points.len() as f64
+        };
+        let repetition_detected = max_run >= REPETITION_THRESHOLD;
+
+        json!({
+            "total_steps": points.len(),
+            "action_counts": action_counts,
+            "tool_errors": tool_errors,
+            "repetition_detected": repetition_detected,
+            "max_repeat_run": max_run,
+            "repeated_action": if repetition_detected { repeated_action } else { None },
+            "avg_thought_length": avg_thought_len,
+        })
+        .to_string()
+    }
+
     /// Runs the task with a self-correction loop.
     pub fn run_with_self_correction(
         &self,
@@ -103,3 +156,48 @@ impl IntrospectionEngine {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(step: u32, action: &str, thought: &str) -> TrajectoryPoint {
+        TrajectoryPoint::new(step, action.to_string(), thought.to_string())
+    }
+
+    #[test]
+    fn test_analyze_buffer_flags_repetition() {
+        let buffer = HistoryBuffer::new();
+        buffer.add(point(1, "Task", "find the file"));
+        for i in 0..4 {
+            buffer.add(point(2 + i, "Search", "look again"));
+        }
+        buffer.add(point(6, "ToolError", "not found"));
+
+        let engine = IntrospectionEngine::new(3);
+        let report: serde_json::Value =
+            serde_json::from_str(&engine.analyze_buffer(&buffer)).unwrap();
+
+        assert_eq!(report["total_steps"], 6);
+        assert_eq!(report["action_counts"]["Search"], 4);
+        assert_eq!(report["tool_errors"], 1);
+        assert_eq!(report["repetition_detected"], true);
+        assert_eq!(report["max_repeat_run"], 4);
+        assert_eq!(report["repeated_action"], "Search");
+    }
+
+    #[test]
+    fn test_analyze_buffer_no_repetition() {
+        let buffer = HistoryBuffer::new();
+        buffer.add(point(1, "Task", "abcd"));
+        buffer.add(point(2, "Search", "ab"));
+
+        let engine = IntrospectionEngine::new(3);
+        let report: serde_json::Value =
+            serde_json::from_str(&engine.analyze_buffer(&buffer)).unwrap();
+
+        assert_eq!(report["repetition_detected"], false);
+        assert!(report["repeated_action"].is_null());
+        assert_eq!(report["avg_thought_length"], 3.0);
+    }
+}
@@ -1,8 +1,17 @@
-use crate::core::security::{sign_data, verify_signature, AgentIdentity, TrustStore};
+use crate::compliance::pii::PIIRedactor;
+use crate::core::security::{sign_data, verify_batch, verify_signature, AgentIdentity, TrustStore};
+use crate::worldmodel::LatentEncoder;
 use crate::TrajectoryPoint;
+use parking_lot::Mutex;
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+/// Domain-separation tag for experience pack signatures
+pub const EXPERIENCE_PACK_CONTEXT: &str = "experience-pack-v3";
 
 /// Failure context for cross-pollination
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -19,6 +28,10 @@ pub struct FailureContext {
     #[pyo3(get, set)]
     pub tags: Vec<String>,
     pub trajectory: Vec<TrajectoryPoint>,
+    /// Compact stand-in for `trajectory` when exported with `compress`
+    #[pyo3(get)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<TrajectorySummary>,
 }
 
 #[pymethods]
@@ -32,6 +45,7 @@ impl FailureContext {
             error: None,
             tags: Vec::new(),
             trajectory: Vec::new(),
+            summary: None,
         }
     }
 
@@ -40,6 +54,105 @@ impl FailureContext {
     }
 }
 
+/// Latent summary of a trajectory, shipped instead of the raw steps.
+/// The latent vector is quantized to `i8` with a shared `scale`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[pyclass]
+pub struct TrajectorySummary {
+    pub latent: Vec<i8>,
+    #[pyo3(get)]
+    pub scale: f32,
+    #[pyo3(get)]
+    pub steps: usize,
+    #[pyo3(get)]
+    pub digest: String,
+    /// Action sequence with repeats collapsed, capped at `MAX_SUMMARY_ACTIONS`
+    #[pyo3(get)]
+    #[serde(default)]
+    pub actions: Vec<String>,
+}
+
+/// Most collapsed actions a `TrajectorySummary` keeps
+const MAX_SUMMARY_ACTIONS: usize = 8;
+
+#[pymethods]
+impl TrajectorySummary {
+    /// Dequantized latent vector
+    pub fn vector(&self) -> Vec<f32> {
+        self.latent.iter().map(|&q| q as f32 * self.scale).collect()
+    }
+
+    /// Stand-in for the raw steps: one point per summarized action, the
+    /// last carrying the digest
+    pub fn to_trajectory(&self) -> Vec<TrajectoryPoint> {
+        let last = self.actions.len().saturating_sub(1);
+        self.actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let thought = if i == last {
+                    self.digest.clone()
+                } else {
+                    String::new()
+                };
+                TrajectoryPoint::new(i as u32 + 1, action.clone(), thought)
+            })
+            .collect()
+    }
+}
+
+impl TrajectorySummary {
+    pub fn new(trajectory: &[TrajectoryPoint], latent: &[f32]) -> Self {
+        let max = latent.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+        let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+        TrajectorySummary {
+            latent: latent
+                .iter()
+                .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+                .collect(),
+            scale,
+            steps: trajectory.len(),
+            digest: trajectory_digest(trajectory),
+            actions: collapsed_actions(trajectory)
+                .into_iter()
+                .take(MAX_SUMMARY_ACTIONS)
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Action sequence with consecutive repeats collapsed
+fn collapsed_actions(trajectory: &[TrajectoryPoint]) -> Vec<&str> {
+    let mut actions: Vec<&str> = Vec::new();
+    for point in trajectory {
+        if actions.last() != Some(&point.action.as_str()) {
+            actions.push(&point.action);
+        }
+    }
+    actions
+}
+
+/// One-line digest: the collapsed action sequence (capped at
+/// `MAX_SUMMARY_ACTIONS`) and the tail of the final thought
+fn trajectory_digest(trajectory: &[TrajectoryPoint]) -> String {
+    let actions = collapsed_actions(trajectory);
+    let mut flow = actions
+        .iter()
+        .take(MAX_SUMMARY_ACTIONS)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    if actions.len() > MAX_SUMMARY_ACTIONS {
+        flow.push_str(" -> ...");
+    }
+    let last_thought: String = trajectory
+        .last()
+        .map(|p| p.thought.chars().take(120).collect())
+        .unwrap_or_default();
+    format!("{} steps: {} | {}", trajectory.len(), flow, last_thought)
+}
+
 /// Experience Pack for sharing learnings across instances
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[pyclass]
@@ -82,6 +195,17 @@ impl ExperiencePack {
         self.lessons.len()
     }
 
+    /// Hex SHA-256 of `source` and the serialized lessons. Signature,
+    /// sender key, version and timestamp are excluded, so re-signed or
+    /// re-stamped copies of the same lessons hash identically.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.source.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(serde_json::to_string(&self.lessons).unwrap_or_default());
+        hex::encode(hasher.finalize())
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).unwrap_or_default()
     }
@@ -92,37 +216,54 @@ impl ExperiencePack {
 pub struct CrossPollination {
     identity: Option<AgentIdentity>,
     trust_store: Option<Py<TrustStore>>,
+    /// Redact PII from exported lessons before sharing
+    anonymize: bool,
+    /// Content hashes of packs already ingested by `pollinate`
+    seen: Mutex<HashSet<String>>,
+    /// Built on the first compressed export; None if it failed to load
+    encoder: OnceLock<Option<LatentEncoder>>,
 }
 
 #[pymethods]
 impl CrossPollination {
     #[new]
-    #[pyo3(signature = (identity = None, trust_store = None))]
-    pub fn new(identity: Option<AgentIdentity>, trust_store: Option<Py<TrustStore>>) -> Self {
+    #[pyo3(signature = (identity = None, trust_store = None, anonymize = true))]
+    pub fn new(
+        identity: Option<AgentIdentity>,
+        trust_store: Option<Py<TrustStore>>,
+        anonymize: bool,
+    ) -> Self {
         CrossPollination {
             identity,
             trust_store,
+            anonymize,
+            seen: Mutex::new(HashSet::new()),
+            encoder: OnceLock::new(),
         }
     }
 
-    /// Export experience from a source
-    pub fn export_experience(&self, source_id: String, lessons_json: String) -> ExperiencePack {
+    /// Export experience from a source. With `compress`, each trajectory is
+    /// replaced by a `TrajectorySummary` (quantized latent + text digest).
+    #[pyo3(signature = (source_id, lessons_json, compress = false))]
+    pub fn export_experience(
+        &self,
+        source_id: String,
+        lessons_json: String,
+        compress: bool,
+    ) -> ExperiencePack {
         info!("Exporting lessons from {}...", source_id);
 
         let lessons_res: Result<Vec<FailureContext>, _> = serde_json::from_str(&lessons_json);
-        let lessons = lessons_res.unwrap_or_default();
-
-        // Anonymize data
-        let anonymized: Vec<FailureContext> = lessons
-            .into_iter()
-            .map(|mut l| {
-                l.input = "[ANONYMIZED]".to_string();
-                l
-            })
-            .collect();
+        let mut lessons = lessons_res.unwrap_or_default();
+        if self.anonymize {
+            lessons = lessons.into_iter().map(Self::anonymize_lesson).collect();
+        }
+        if compress {
+            lessons = self.compress_lessons(lessons, &source_id);
+        }
 
         let mut pack = ExperiencePack::new(source_id);
-        for lesson in anonymized {
+        for lesson in lessons {
             pack.add_lesson(lesson);
         }
 
@@ -132,7 +273,7 @@ impl CrossPollination {
 
             // Sign the serialized content (excluding signature field)
             let serialized = pack.to_json();
-            let sig = sign_data(id, serialized.as_bytes());
+            let sig = sign_data(id, EXPERIENCE_PACK_CONTEXT, serialized.as_bytes());
             pack.signature = Some(sig);
             info!("🔒 Signed experience pack with pubkey: {}", id.pubkey);
         }
@@ -140,7 +281,8 @@ impl CrossPollination {
         pack
     }
 
-    /// Ingest experience pack into local memory (via safety shield)
+    /// Ingest experience pack into local memory (via safety shield).
+    /// Packs whose content was already ingested yield an empty list.
     pub fn pollinate(&self, py: Python, pack_json: String) -> PyResult<Vec<String>> {
         let pack: ExperiencePack = serde_json::from_str(&pack_json).map_err(|e| {
             pyo3::exceptions::PyValueError::new_err(format!("Invalid pack JSON: {}", e))
@@ -159,11 +301,9 @@ impl CrossPollination {
             })?;
 
             // Reconstruct pack without signature to verify
-            let mut check_pack = pack.clone();
-            check_pack.signature = None;
-            let serialized = check_pack.to_json();
+            let serialized = Self::signed_payload(&pack);
 
-            if !verify_signature(pubkey, serialized.as_bytes(), sig) {
+            if !verify_signature(pubkey, EXPERIENCE_PACK_CONTEXT, serialized.as_bytes(), sig) {
                 return Err(pyo3::exceptions::PyValueError::new_err(
                     "Invalid experience pack signature",
                 ));
@@ -180,6 +320,92 @@ impl CrossPollination {
             info!("Verified signature from trusted agent: {}", pack.source);
         }
 
+        Ok(self.ingest(&pack))
+    }
+
+    /// Number of distinct packs ingested so far
+    pub fn seen_count(&self) -> usize {
+        self.seen.lock().len()
+    }
+
+    /// Ingest many experience packs, verifying all signatures in one batch.
+    /// Returns one entry per pack: its trajectory JSONs (empty if the content
+    /// was already ingested), or None if the pack was malformed, failed
+    /// verification, or came from an untrusted sender.
+    pub fn pollinate_batch(&self, py: Python, packs_json: Vec<String>) -> Vec<Option<Vec<String>>> {
+        match self.trust_store {
+            Some(ref py_store) => {
+                let store = py_store.borrow(py);
+                self.pollinate_batch_with(Some(&*store), &packs_json)
+            }
+            None => self.pollinate_batch_with(None, &packs_json),
+        }
+    }
+}
+
+impl CrossPollination {
+    /// Strip the raw input and redact PII from every other free-text field
+    fn anonymize_lesson(mut lesson: FailureContext) -> FailureContext {
+        let redactor = PIIRedactor::default();
+        lesson.input = "[ANONYMIZED]".to_string();
+        lesson.output = redactor.redact(&lesson.output);
+        lesson.error = lesson.error.map(|e| redactor.redact(&e));
+        for point in lesson.trajectory.iter_mut() {
+            point.thought = redactor.redact(&point.thought);
+        }
+        lesson
+    }
+
+    /// Summarize every lesson's trajectory with one batched encoder call.
+    /// Lessons are left intact if the encoder is unavailable.
+    fn compress_lessons(
+        &self,
+        lessons: Vec<FailureContext>,
+        source_id: &str,
+    ) -> Vec<FailureContext> {
+        let encoder = self.encoder.get_or_init(|| {
+            LatentEncoder::new(None)
+                .map_err(|e| warn!("Trajectory compression disabled: {}", e))
+                .ok()
+        });
+        let Some(encoder) = encoder else {
+            return lessons;
+        };
+
+        let trajectory_jsons: Vec<String> = lessons
+            .iter()
+            .map(|l| serde_json::to_string(&l.trajectory).unwrap_or_default())
+            .collect();
+        let states = encoder.encode_batch(trajectory_jsons, source_id.to_string());
+        lessons
+            .into_iter()
+            .zip(states)
+            .map(|(lesson, state)| Self::compress_lesson(lesson, &state.vector))
+            .collect()
+    }
+
+    /// Swap the raw trajectory for its summary
+    fn compress_lesson(mut lesson: FailureContext, latent: &[f32]) -> FailureContext {
+        let trajectory = std::mem::take(&mut lesson.trajectory);
+        lesson.summary = Some(TrajectorySummary::new(&trajectory, latent));
+        lesson
+    }
+
+    /// Serialized pack content covered by the signature
+    fn signed_payload(pack: &ExperiencePack) -> String {
+        let mut check_pack = pack.clone();
+        check_pack.signature = None;
+        check_pack.to_json()
+    }
+
+    /// Trajectory JSONs of a verified pack, or nothing if its content was
+    /// already ingested
+    fn ingest(&self, pack: &ExperiencePack) -> Vec<String> {
+        if !self.seen.lock().insert(pack.content_hash()) {
+            info!("Skipping duplicate experience pack from {}", pack.source);
+            return Vec::new();
+        }
+
         info!(
             "🌸 Ingesting {} lessons from {}...",
             pack.lesson_count(),
@@ -187,10 +413,272 @@ impl CrossPollination {
         );
 
         // Return trajectory JSONs for loading into safety shield
-        Ok(pack
-            .lessons
+        Self::lesson_trajectories(pack)
+    }
+
+    /// Trajectory JSON of every lesson; compressed lessons are expanded from
+    /// their summary so consumers never see an empty trajectory
+    fn lesson_trajectories(pack: &ExperiencePack) -> Vec<String> {
+        pack.lessons
             .iter()
-            .map(|l| serde_json::to_string(&l.trajectory).unwrap_or_default())
-            .collect())
+            .map(|l| match &l.summary {
+                Some(summary) if l.trajectory.is_empty() => {
+                    serde_json::to_string(&summary.to_trajectory())
+                }
+                _ => serde_json::to_string(&l.trajectory),
+            })
+            .map(|json| json.unwrap_or_default())
+            .collect()
+    }
+
+    /// Batch ingestion against an explicit trust store
+    pub fn pollinate_batch_with(
+        &self,
+        store: Option<&TrustStore>,
+        packs_json: &[String],
+    ) -> Vec<Option<Vec<String>>> {
+        let packs: Vec<Option<ExperiencePack>> = packs_json
+            .iter()
+            .map(|json| serde_json::from_str(json).ok())
+            .collect();
+
+        let store = match store {
+            Some(store) => store,
+            None => {
+                return packs
+                    .iter()
+                    .map(|p| p.as_ref().map(|pack| self.ingest(pack)))
+                    .collect()
+            }
+        };
+
+        let mut indices = Vec::new();
+        let mut pubkeys = Vec::new();
+        let mut payloads = Vec::new();
+        let mut signatures = Vec::new();
+        for (i, pack) in packs.iter().enumerate() {
+            if let Some(pack) = pack {
+                if let (Some(pubkey), Some(sig)) = (&pack.sender_pubkey, &pack.signature) {
+                    indices.push(i);
+                    pubkeys.push(pubkey.as_str());
+                    payloads.push(Self::signed_payload(pack));
+                    signatures.push(sig.as_slice());
+                }
+            }
+        }
+
+        let payload_refs: Vec<&[u8]> = payloads.iter().map(|p| p.as_bytes()).collect();
+        let verified = verify_batch(
+            &pubkeys,
+            EXPERIENCE_PACK_CONTEXT,
+            &payload_refs,
+            &signatures,
+        );
+
+        let mut results = vec![None; packs.len()];
+        for (j, &i) in indices.iter().enumerate() {
+            let pack = match &packs[i] {
+                Some(pack) => pack,
+                None => continue,
+            };
+            if !verified[j] {
+                warn!(
+                    "Rejected experience pack {} from {}: bad signature",
+                    i, pack.source
+                );
+                continue;
+            }
+            if !store.is_trusted(&pack.source, pubkeys[j]) {
+                warn!(
+                    "Rejected experience pack {} from untrusted sender {}",
+                    i, pack.source
+                );
+                continue;
+            }
+            results[i] = Some(self.ingest(pack));
+        }
+
+        info!(
+            "🌸 Batch-ingested {}/{} experience packs",
+            results.iter().filter(|r| r.is_some()).count(),
+            packs.len()
+        );
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_pollination_rejects_only_tampered_pack() {
+        let identity = AgentIdentity::generate();
+        let store = TrustStore::new();
+        store
+            .add_trusted_agent("agent-a".to_string(), identity.pubkey.clone(), None)
+            .unwrap();
+
+        let exporter = CrossPollination::new(Some(identity), None, true);
+        let lessons = serde_json::to_string(&vec![FailureContext::new(
+            "t1".to_string(),
+            "input".to_string(),
+            "output".to_string(),
+        )])
+        .unwrap();
+
+        let good = exporter
+            .export_experience("agent-a".to_string(), lessons.clone(), false)
+            .to_json();
+        let mut tampered = exporter.export_experience("agent-a".to_string(), lessons, false);
+        tampered.lessons[0].output = "poisoned".to_string();
+
+        let ingester = CrossPollination::new(None, None, true);
+        let results =
+            ingester.pollinate_batch_with(Some(&store), &[good.clone(), tampered.to_json(), good]);
+
+        assert_eq!(results[0].as_ref().unwrap().len(), 1);
+        assert!(results[1].is_none());
+        // The repeated good pack is accepted but its lessons are not re-ingested
+        assert!(results[2].as_ref().unwrap().is_empty());
+        assert_eq!(ingester.seen_count(), 1);
+    }
+
+    #[test]
+    fn batch_pollination_skips_seen_packs_without_trust_store() {
+        let lessons = serde_json::to_string(&vec![FailureContext::new(
+            "t1".to_string(),
+            "input".to_string(),
+            "output".to_string(),
+        )])
+        .unwrap();
+        let pack = CrossPollination::new(None, None, true)
+            .export_experience("agent-a".to_string(), lessons, false)
+            .to_json();
+
+        let ingester = CrossPollination::new(None, None, true);
+        let results = ingester.pollinate_batch_with(None, &[pack.clone(), pack]);
+        assert_eq!(results[0].as_ref().unwrap().len(), 1);
+        assert!(results[1].as_ref().unwrap().is_empty());
+        assert_eq!(ingester.seen_count(), 1);
+    }
+
+    #[test]
+    fn export_redacts_pii_in_trajectory() {
+        let mut lesson = FailureContext::new(
+            "t1".to_string(),
+            "input".to_string(),
+            "mail jane@example.com".to_string(),
+        );
+        lesson.trajectory.push(TrajectoryPoint::new(
+            1,
+            "Thought".to_string(),
+            "contact jane@example.com for access".to_string(),
+        ));
+        let lessons = serde_json::to_string(&vec![lesson]).unwrap();
+
+        let pack = CrossPollination::new(None, None, true).export_experience(
+            "agent-a".to_string(),
+            lessons.clone(),
+            false,
+        );
+        let exported = &pack.lessons[0];
+        assert_eq!(exported.input, "[ANONYMIZED]");
+        assert!(!exported.output.contains("jane@example.com"));
+        assert!(!exported.trajectory[0].thought.contains("jane@example.com"));
+
+        let raw = CrossPollination::new(None, None, false).export_experience(
+            "agent-a".to_string(),
+            lessons,
+            false,
+        );
+        assert!(raw.lessons[0].trajectory[0]
+            .thought
+            .contains("jane@example.com"));
+    }
+
+    #[test]
+    fn duplicate_pack_is_ingested_once() {
+        let lessons = serde_json::to_string(&vec![FailureContext::new(
+            "t1".to_string(),
+            "input".to_string(),
+            "output".to_string(),
+        )])
+        .unwrap();
+        let exporter = CrossPollination::new(Some(AgentIdentity::generate()), None, true);
+        let pack = exporter.export_experience("agent-a".to_string(), lessons.clone(), false);
+
+        // A later re-export of the same lessons differs only in signature/timestamp
+        let mut resent = exporter.export_experience("agent-a".to_string(), lessons, false);
+        resent.timestamp += 60;
+        assert_eq!(pack.content_hash(), resent.content_hash());
+        assert_eq!(pack.content_hash().len(), 64);
+
+        let ingester = CrossPollination::new(None, None, true);
+        assert_eq!(ingester.ingest(&pack).len(), 1);
+        assert!(ingester.ingest(&resent).is_empty());
+        assert_eq!(ingester.seen_count(), 1);
+
+        let mut other = pack.clone();
+        other.source = "agent-b".to_string();
+        assert_eq!(ingester.ingest(&other).len(), 1);
+        assert_eq!(ingester.seen_count(), 2);
+    }
+
+    #[test]
+    fn compressed_pack_is_much_smaller() {
+        let mut lesson =
+            FailureContext::new("t1".to_string(), "input".to_string(), "output".to_string());
+        for step in 0..128 {
+            let action = if step % 4 == 3 {
+                "ToolError"
+            } else {
+                "CallTool"
+            };
+            lesson.trajectory.push(TrajectoryPoint::new(
+                step,
+                action.to_string(),
+                format!(
+                    "Step {}: the inventory API returned a paginated result, so fetch \
+                     the next cursor and reconcile it against the cached price table",
+                    step
+                ),
+            ));
+        }
+        let latent: Vec<f32> = (0..768).map(|i| ((i as f32) * 0.37).sin() / 27.7).collect();
+
+        let mut raw = ExperiencePack::new("agent-a".to_string());
+        raw.add_lesson(lesson.clone());
+        let mut compressed = ExperiencePack::new("agent-a".to_string());
+        compressed.add_lesson(CrossPollination::compress_lesson(lesson, &latent));
+
+        let (raw_len, compressed_len) = (raw.to_json().len(), compressed.to_json().len());
+        assert!(
+            compressed_len * 3 < raw_len,
+            "{} vs {}",
+            compressed_len,
+            raw_len
+        );
+
+        let summary = compressed.lessons[0].summary.as_ref().unwrap();
+        assert!(compressed.lessons[0].trajectory.is_empty());
+        assert_eq!(summary.steps, 128);
+        assert!(summary
+            .digest
+            .starts_with("128 steps: CallTool -> ToolError -> CallTool"));
+        for (restored, original) in summary.vector().iter().zip(&latent) {
+            assert!((restored - original).abs() <= summary.scale);
+        }
+
+        // Uncompressed lessons serialize exactly as before
+        assert!(!raw.to_json().contains("summary"));
+
+        // Ingesting the compressed pack yields the summarized action flow
+        let ingested = CrossPollination::new(None, None, true).ingest(&compressed);
+        let trajectory: Vec<TrajectoryPoint> = serde_json::from_str(&ingested[0]).unwrap();
+        let actions: Vec<&str> = trajectory.iter().map(|p| p.action.as_str()).collect();
+        assert_eq!(actions.len(), MAX_SUMMARY_ACTIONS);
+        assert_eq!(&actions[..3], ["CallTool", "ToolError", "CallTool"]);
+        assert_eq!(trajectory.last().unwrap().thought, summary.digest);
     }
 }
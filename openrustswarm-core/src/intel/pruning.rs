@@ -1,6 +1,8 @@
 use crate::core::middleware::{CogOpsContext, Middleware};
+use crate::{HistoryBuffer, TrajectoryPoint};
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tracing::info;
 
 /// Features for context scoring
@@ -65,7 +67,7 @@ impl ContextFragment {
 }
 
 /// Policy weights for pruning
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PruningPolicy {
     pub recency_weight: f64,
     pub relevance_weight: f64,
@@ -86,6 +88,49 @@ impl Default for PruningPolicy {
     }
 }
 
+/// Unit in which a pruning budget is measured
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BudgetUnit {
+    /// UTF-8 bytes (the original behavior)
+    Bytes,
+    /// Unicode scalar values
+    Chars,
+    /// Approximate LLM tokens (~4 chars per token)
+    Tokens,
+}
+
+impl BudgetUnit {
+    /// Parse "bytes" | "chars" | "tokens"; anything else is a `ValueError`
+    pub fn parse(unit: &str) -> PyResult<Self> {
+        match unit {
+            "bytes" => Ok(BudgetUnit::Bytes),
+            "chars" => Ok(BudgetUnit::Chars),
+            "tokens" => Ok(BudgetUnit::Tokens),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown budget unit '{}': expected bytes, chars or tokens",
+                other
+            ))),
+        }
+    }
+
+    /// Length of `text` in this unit
+    pub fn measure(&self, text: &str) -> usize {
+        match self {
+            BudgetUnit::Bytes => text.len(),
+            BudgetUnit::Chars => text.chars().count(),
+            BudgetUnit::Tokens => text.chars().count().div_ceil(4),
+        }
+    }
+}
+
+/// Outcome of pruning a single fragment
+#[derive(Clone, Debug, Serialize)]
+struct PruneDecision {
+    id: String,
+    score: f64,
+    kept: bool,
+}
+
 /// Adaptive Pruner using RL-style weight updates
 #[pyclass]
 pub struct AdaptivePruner {
@@ -115,37 +160,60 @@ impl AdaptivePruner {
             + (complexity * self.policy.complexity_weight)
     }
 
-    /// Prune fragments to fit within target length
-    pub fn prune(&self, fragments_json: String, target_length: usize) -> String {
-        let fragments: Vec<ContextFragment> =
-            serde_json::from_str(&fragments_json).unwrap_or_default();
+    /// Prune fragments to fit within target length, measured in
+    /// `budget_unit` ("bytes" | "chars" | "tokens", default "bytes").
+    /// Raises `ValueError` for any other unit.
+    #[pyo3(signature = (fragments_json, target_length, budget_unit = None))]
+    pub fn prune(
+        &self,
+        fragments_json: String,
+        target_length: usize,
+        budget_unit: Option<String>,
+    ) -> PyResult<String> {
+        let unit = BudgetUnit::parse(budget_unit.as_deref().unwrap_or("bytes"))?;
+        Ok(self.select(&fragments_json, target_length, unit).0)
+    }
 
-        // Score and sort
-        let mut scored: Vec<(ContextFragment, f64)> = fragments
-            .into_iter()
-            .map(|f| {
-                let score = self.score_fragment(
-                    f.features.recency,
-                    f.features.relevance,
-                    f.features.historical_success,
-                    f.features.complexity,
-                );
-                (f, score)
-            })
-            .collect();
+    /// Prune fragments and report the decision for every input fragment.
+    /// Returns JSON `{"text": ..., "fragments": [{id, score, kept}]}` with
+    /// fragments in input order.
+    #[pyo3(signature = (fragments_json, target_length, budget_unit = None))]
+    pub fn prune_detailed(
+        &self,
+        fragments_json: String,
+        target_length: usize,
+        budget_unit: Option<String>,
+    ) -> PyResult<String> {
+        let unit = BudgetUnit::parse(budget_unit.as_deref().unwrap_or("bytes"))?;
+        let (text, decisions) = self.select(&fragments_json, target_length, unit);
+        Ok(serde_json::json!({
+            "text": text,
+            "fragments": decisions,
+        })
+        .to_string())
+    }
 
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    /// Export the learned policy (four weights + learning rate) as JSON
+    pub fn export_policy(&self) -> String {
+        serde_json::to_string(&self.policy).unwrap_or_default()
+    }
 
-        // Select top fragments within limit
-        let mut result = String::new();
-        for (fragment, _) in scored {
-            if result.len() + fragment.text.len() <= target_length {
-                result.push_str(&fragment.text);
-                result.push_str("\n---\n");
-            }
-        }
+    /// Restore a policy previously produced by `export_policy`
+    pub fn import_policy(&mut self, json: String) -> PyResult<()> {
+        self.policy = serde_json::from_str(&json).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid policy JSON: {}", e))
+        })?;
+        Ok(())
+    }
 
-        result
+    /// Current (recency, relevance, historical_success, complexity) weights
+    pub fn get_weights(&self) -> (f64, f64, f64, f64) {
+        (
+            self.policy.recency_weight,
+            self.policy.relevance_weight,
+            self.policy.historical_success_weight,
+            self.policy.complexity_weight,
+        )
     }
 
     /// Update policy based on feedback (simplified RL)
@@ -181,6 +249,127 @@ impl AdaptivePruner {
             self.policy.complexity_weight
         );
     }
+
+    /// Reinforce the policy from a finished task: derive the mean features of
+    /// its trajectory and apply `update_policy` with +1 on success, -1 on failure
+    pub fn learn_from_buffer(&mut self, buffer: &HistoryBuffer, success: bool) {
+        let Some(f) = trajectory_features(&buffer.get_raw()) else {
+            return;
+        };
+        let feedback = if success { 1.0 } else { -1.0 };
+        self.update_policy(
+            feedback,
+            f.recency,
+            f.relevance,
+            f.historical_success,
+            f.complexity,
+        );
+    }
+}
+
+/// Thoughts at or beyond this many characters count as maximally complex
+const COMPLEXITY_CHARS: f64 = 1000.0;
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Mean per-point features of a trajectory, each in [0, 1]:
+/// - recency: position in the trajectory (later points are more recent)
+/// - relevance: share of the point's words that appear in the first `Task`
+/// - historical_success: share of points that are not `ToolError`s
+/// - complexity: thought length relative to `COMPLEXITY_CHARS`
+///
+/// Returns None for an empty trajectory.
+fn trajectory_features(points: &[TrajectoryPoint]) -> Option<ContextFeatures> {
+    if points.is_empty() {
+        return None;
+    }
+    let n = points.len() as f64;
+    let task_words = points
+        .iter()
+        .find(|p| p.action == "Task")
+        .map(|p| words(&p.thought))
+        .unwrap_or_default();
+
+    let mut sum = ContextFeatures::new(0.0, 0.0, 0.0, 0.0);
+    for (i, point) in points.iter().enumerate() {
+        let point_words = words(&point.thought);
+        sum.recency += (i + 1) as f64 / n;
+        if !point_words.is_empty() {
+            sum.relevance +=
+                point_words.intersection(&task_words).count() as f64 / point_words.len() as f64;
+        }
+        if point.action != "ToolError" {
+            sum.historical_success += 1.0;
+        }
+        sum.complexity += (point.thought.chars().count() as f64 / COMPLEXITY_CHARS).min(1.0);
+    }
+
+    Some(ContextFeatures::new(
+        sum.recency / n,
+        sum.relevance / n,
+        sum.historical_success / n,
+        sum.complexity / n,
+    ))
+}
+
+impl AdaptivePruner {
+    /// Score fragments, greedily keep the best within `target_length`
+    /// (measured in `unit`), and return the concatenated text plus
+    /// per-fragment decisions
+    fn select(
+        &self,
+        fragments_json: &str,
+        target_length: usize,
+        unit: BudgetUnit,
+    ) -> (String, Vec<PruneDecision>) {
+        let fragments: Vec<ContextFragment> =
+            serde_json::from_str(fragments_json).unwrap_or_default();
+
+        let mut decisions: Vec<PruneDecision> = fragments
+            .iter()
+            .map(|f| PruneDecision {
+                id: f.id.clone(),
+                score: self.score_fragment(
+                    f.features.recency,
+                    f.features.relevance,
+                    f.features.historical_success,
+                    f.features.complexity,
+                ),
+                kept: false,
+            })
+            .collect();
+
+        // Score and sort
+        let mut order: Vec<usize> = (0..fragments.len()).collect();
+        order.sort_by(|&a, &b| {
+            decisions[b]
+                .score
+                .partial_cmp(&decisions[a].score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        // Select top fragments within limit
+        let separator = "\n---\n";
+        let mut result = String::new();
+        let mut used = 0;
+        for i in order {
+            let fragment = &fragments[i];
+            let size = unit.measure(&fragment.text);
+            if used + size <= target_length {
+                result.push_str(&fragment.text);
+                result.push_str(separator);
+                used += size + unit.measure(separator);
+                decisions[i].kept = true;
+            }
+        }
+
+        (result, decisions)
+    }
 }
 
 impl Middleware for AdaptivePruner {
@@ -197,3 +386,128 @@ impl Middleware for AdaptivePruner {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(id: &str, text: &str, relevance: f64) -> ContextFragment {
+        ContextFragment::new(id.to_string(), text.to_string(), 0.5, relevance, 0.5, 0.0)
+    }
+
+    #[test]
+    fn prune_detailed_reports_every_fragment() {
+        let pruner = AdaptivePruner::new();
+        let fragments = vec![
+            fragment("low", "aaaaaaaaaa", 0.1),
+            fragment("high", "bbbbbbbbbb", 0.9),
+        ];
+        let json = serde_json::to_string(&fragments).unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&pruner.prune_detailed(json.clone(), 12, None).unwrap()).unwrap();
+        let decisions = report["fragments"].as_array().unwrap();
+
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0]["id"], "low");
+        assert_eq!(decisions[0]["kept"], false);
+        assert_eq!(decisions[1]["id"], "high");
+        assert_eq!(decisions[1]["kept"], true);
+        assert_eq!(report["text"], pruner.prune(json, 12, None).unwrap());
+    }
+
+    #[test]
+    fn budget_unit_changes_what_fits() {
+        let pruner = AdaptivePruner::new();
+        let fragments: Vec<ContextFragment> = (0..5)
+            .map(|i| fragment(&format!("f{}", i), &"word ".repeat(20), 0.5))
+            .collect();
+        let json = serde_json::to_string(&fragments).unwrap();
+        let kept = |unit: &str| {
+            let report: serde_json::Value = serde_json::from_str(
+                &pruner
+                    .prune_detailed(json.clone(), 150, Some(unit.to_string()))
+                    .unwrap(),
+            )
+            .unwrap();
+            report["fragments"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|d| d["kept"] == true)
+                .count()
+        };
+
+        // 100-byte fragments are ~25 tokens, so a budget of 150 holds one
+        // fragment in bytes but five in tokens
+        assert_eq!(kept("bytes"), 1);
+        assert_eq!(kept("tokens"), 5);
+        assert_eq!(BudgetUnit::Tokens.measure("héllo wörld"), 3);
+        assert_eq!(BudgetUnit::Chars.measure("héllo"), 5);
+        assert_eq!(BudgetUnit::Bytes.measure("héllo"), 6);
+        assert!(BudgetUnit::parse("words").is_err());
+        assert!(pruner.prune(json, 150, Some("Tokens".to_string())).is_err());
+    }
+
+    #[test]
+    fn policy_round_trips_through_json() {
+        let mut trained = AdaptivePruner::new();
+        for _ in 0..10 {
+            trained.update_policy(1.0, 0.3, 0.8, 0.1, 0.5);
+        }
+        let exported = trained.export_policy();
+
+        let mut restored = AdaptivePruner::new();
+        assert_ne!(restored.get_weights(), trained.get_weights());
+        restored.import_policy(exported).unwrap();
+        assert_eq!(restored.get_weights(), trained.get_weights());
+
+        assert!(restored.import_policy("not json".to_string()).is_err());
+    }
+
+    #[test]
+    fn learn_from_buffer_follows_task_outcome() {
+        let buffer = HistoryBuffer::new();
+        buffer.add(TrajectoryPoint::new(
+            1,
+            "Task".to_string(),
+            "Find the NVIDIA stock price".to_string(),
+        ));
+        buffer.add(TrajectoryPoint::new(
+            2,
+            "ToolCall".to_string(),
+            "web_search(NVIDIA stock price)".to_string(),
+        ));
+        buffer.add(TrajectoryPoint::new(
+            3,
+            "ToolError".to_string(),
+            "timeout".to_string(),
+        ));
+
+        let features = trajectory_features(&buffer.get_raw()).unwrap();
+        assert!((features.recency - 2.0 / 3.0).abs() < 1e-9);
+        assert!((features.historical_success - 2.0 / 3.0).abs() < 1e-9);
+        assert!(features.relevance > 0.5);
+
+        let baseline = AdaptivePruner::new().get_weights();
+        let mut pruner = AdaptivePruner::new();
+        pruner.learn_from_buffer(&buffer, true);
+        let up = pruner.get_weights();
+        assert!(up.0 > baseline.0 && up.1 > baseline.1 && up.2 > baseline.2 && up.3 > baseline.3);
+
+        let mut pruner = AdaptivePruner::new();
+        pruner.learn_from_buffer(&buffer, false);
+        let down = pruner.get_weights();
+        assert!(
+            down.0 < baseline.0
+                && down.1 < baseline.1
+                && down.2 < baseline.2
+                && down.3 < baseline.3
+        );
+
+        // Nothing to learn from an empty trajectory
+        let mut pruner = AdaptivePruner::new();
+        pruner.learn_from_buffer(&HistoryBuffer::new(), true);
+        assert_eq!(pruner.get_weights(), baseline);
+    }
+}
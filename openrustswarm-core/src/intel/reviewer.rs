@@ -3,15 +3,59 @@ use pyo3::prelude::*;
 use regex::Regex;
 use tracing::info;
 
+/// Red-flag patterns for synthesized tool code, paired with their finding
+const RED_FLAGS: &[(&str, &str)] = &[
+    (r"\bos\.system\s*\(", "Shell execution via os.system"),
+    (r"\beval\s*\(", "Dynamic code execution via eval"),
+    (r"\bexec\s*\(", "Dynamic code execution via exec"),
+    (r"\bsubprocess\b", "Process spawning via subprocess"),
+    (
+        r"\b(os\.remove|os\.unlink|os\.rmdir|shutil\.rmtree)\s*\(",
+        "File deletion",
+    ),
+];
+
 /// Code Quality Guard - Reviews code for secrets, TODOs, and console spam.
 #[pyclass]
-pub struct CodeQualityGuard {}
+pub struct CodeQualityGuard {
+    /// Hosts synthesized code may contact (subdomains included)
+    #[pyo3(get, set)]
+    pub allowed_hosts: Vec<String>,
+}
 
 #[pymethods]
 impl CodeQualityGuard {
     #[new]
-    pub fn new() -> Self {
-        CodeQualityGuard {}
+    #[pyo3(signature = (allowed_hosts = None))]
+    pub fn new(allowed_hosts: Option<Vec<String>>) -> Self {
+        CodeQualityGuard {
+            allowed_hosts: allowed_hosts.unwrap_or_default(),
+        }
+    }
+
+    /// Review synthesized tool code for dangerous calls.
+    /// Returns (approved, findings); approved only when there are no findings.
+    pub fn review(&self, code: String) -> (bool, Vec<String>) {
+        let mut findings: Vec<String> = Vec::new();
+
+        for (pattern, finding) in RED_FLAGS {
+            if let Ok(re) = Regex::new(pattern) {
+                if re.is_match(&code) {
+                    findings.push(finding.to_string());
+                }
+            }
+        }
+
+        if let Ok(re) = Regex::new(r#"https?://([^/\s'":?#]+)"#) {
+            for cap in re.captures_iter(&code) {
+                let host = cap[1].to_lowercase();
+                if !self.is_host_allowed(&host) {
+                    findings.push(format!("Network call to non-allowlisted host: {}", host));
+                }
+            }
+        }
+
+        (findings.is_empty(), findings)
     }
 
     /// Review code content for issues
@@ -68,6 +112,15 @@ impl CodeQualityGuard {
     }
 }
 
+impl CodeQualityGuard {
+    fn is_host_allowed(&self, host: &str) -> bool {
+        self.allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
+    }
+}
+
 impl Middleware for CodeQualityGuard {
     fn name(&self) -> &str {
         "CodeQualityGuard"
@@ -78,3 +131,41 @@ impl Middleware for CodeQualityGuard {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_benign_function() {
+        let guard = CodeQualityGuard::new(None);
+        let code = "def add(a, b):\n    return a + b\n";
+        let (approved, findings) = guard.review(code.to_string());
+        assert!(approved);
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_review_flags_os_system() {
+        let guard = CodeQualityGuard::new(None);
+        let code = "import os\ndef clean():\n    os.system('rm -rf /tmp/x')\n";
+        let (approved, findings) = guard.review(code.to_string());
+        assert!(!approved);
+        assert_eq!(findings, vec!["Shell execution via os.system".to_string()]);
+    }
+
+    #[test]
+    fn test_review_network_allowlist() {
+        let guard = CodeQualityGuard::new(Some(vec!["example.com".to_string()]));
+        let ok = "requests.get('https://api.example.com/v1')";
+        assert!(guard.review(ok.to_string()).0);
+
+        let bad = "requests.get('http://evil.net:8080/x')";
+        let (approved, findings) = guard.review(bad.to_string());
+        assert!(!approved);
+        assert_eq!(
+            findings,
+            vec!["Network call to non-allowlisted host: evil.net".to_string()]
+        );
+    }
+}
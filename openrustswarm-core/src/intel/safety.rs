@@ -2,7 +2,10 @@ use crate::core::middleware::{CogOpsContext, Middleware};
 use crate::TrajectoryPoint;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::warn;
 
 /// A predictive safety mechanism that evaluates agent intentions against
@@ -14,8 +17,14 @@ use tracing::warn;
 pub struct PredictiveSafetyShield {
     /// In-memory cache of identified failure trajectories
     failure_trajectories: RwLock<HashMap<String, Vec<TrajectoryPoint>>>,
-    /// The threshold score [0.0 - 1.0] above which an action is blocked
+    /// The threshold score [0.0 - 1.0] at or above which an action is blocked.
+    /// Compared against the risk score from `analyze_risk`: the fraction of a
+    /// known failure trajectory's actions matched step-by-step by the current one.
     risk_threshold: f64,
+    /// Number of safety checks performed
+    checks: AtomicU64,
+    /// Number of checks that resulted in a block
+    blocks: AtomicU64,
 }
 
 #[pymethods]
@@ -26,9 +35,59 @@ impl PredictiveSafetyShield {
         PredictiveSafetyShield {
             failure_trajectories: RwLock::new(HashMap::new()),
             risk_threshold,
+            checks: AtomicU64::new(0),
+            blocks: AtomicU64::new(0),
         }
     }
 
+    /// Sets the blocking threshold; must lie in [0.0, 1.0].
+    /// Lower values block more trajectories.
+    pub fn set_threshold(&mut self, threshold: f32) -> PyResult<()> {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "threshold must be in [0.0, 1.0], got {}",
+                threshold
+            )));
+        }
+        self.risk_threshold = f64::from(threshold);
+        Ok(())
+    }
+
+    /// Returns the current blocking threshold
+    pub fn get_threshold(&self) -> f32 {
+        self.risk_threshold as f32
+    }
+
+    /// Checks a trajectory against the threshold, recording it in `stats`.
+    /// Returns (blocked, risk_score, reason).
+    pub fn check(&self, trajectory_json: String) -> (bool, f64, Option<String>) {
+        let (risk_score, reason) = self.analyze_risk(trajectory_json);
+        let blocked = risk_score >= self.risk_threshold;
+        self.checks.fetch_add(1, Ordering::Relaxed);
+        if blocked {
+            self.blocks.fetch_add(1, Ordering::Relaxed);
+        }
+        (blocked, risk_score, reason)
+    }
+
+    /// Returns (checks, blocks, block_rate) since creation or the last `reset_stats`
+    pub fn stats(&self) -> (u64, u64, f32) {
+        let checks = self.checks.load(Ordering::Relaxed);
+        let blocks = self.blocks.load(Ordering::Relaxed);
+        let rate = if checks == 0 {
+            0.0
+        } else {
+            blocks as f32 / checks as f32
+        };
+        (checks, blocks, rate)
+    }
+
+    /// Resets the check/block counters
+    pub fn reset_stats(&self) {
+        self.checks.store(0, Ordering::Relaxed);
+        self.blocks.store(0, Ordering::Relaxed);
+    }
+
     /// Ingests a labeled failure trajectory for use in pattern matching.
     pub fn add_failure_pattern(&self, id: String, trajectory_json: String) {
         if let Ok(traj) = serde_json::from_str::<Vec<TrajectoryPoint>>(&trajectory_json) {
@@ -37,6 +96,33 @@ impl PredictiveSafetyShield {
         }
     }
 
+    /// Loads known-bad trajectories as failure patterns, e.g. the output of
+    /// `CrossPollination.pollinate`. Each entry is a JSON `TrajectoryPoint` list;
+    /// invalid or empty entries are skipped and duplicates are stored once.
+    /// Returns the number of new patterns added.
+    pub fn load_trajectories(&self, trajectory_jsons: Vec<String>) -> usize {
+        let mut failures = self.failure_trajectories.write();
+        let mut loaded = 0;
+        for json in &trajectory_jsons {
+            let Ok(traj) = serde_json::from_str::<Vec<TrajectoryPoint>>(json) else {
+                warn!("[SafetyShield] Skipping unparseable trajectory");
+                continue;
+            };
+            if traj.is_empty() {
+                continue;
+            }
+            let mut hasher = DefaultHasher::new();
+            for point in &traj {
+                point.action.hash(&mut hasher);
+            }
+            let id = format!("pollinated-{:016x}", hasher.finish());
+            if failures.insert(id, traj).is_none() {
+                loaded += 1;
+            }
+        }
+        loaded
+    }
+
     /// Get the number of loaded failure patterns
     pub fn pattern_count(&self) -> usize {
         let failures = self.failure_trajectories.read();
@@ -96,13 +182,10 @@ impl Middleware for PredictiveSafetyShield {
 
     fn before_step(&self, ctx: &mut CogOpsContext) -> Result<(), String> {
         let trajectory_json = ctx.get_trajectory_json();
-        let (risk_score, reason) = self.analyze_risk(trajectory_json);
+        let (blocked, risk_score, reason) = self.check(trajectory_json);
 
-        if risk_score >= self.risk_threshold {
-            warn!(
-                "[SafetyShield] BLOCKING EXECUTION. Risk: {:.2}",
-                risk_score
-            );
+        if blocked {
+            warn!("[SafetyShield] BLOCKING EXECUTION. Risk: {:.2}", risk_score);
             if let Some(ref r) = reason {
                 warn!("   Reason: {}", r);
             }
@@ -114,3 +197,78 @@ impl Middleware for PredictiveSafetyShield {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trajectory(actions: &[&str]) -> String {
+        let points: Vec<TrajectoryPoint> = actions
+            .iter()
+            .enumerate()
+            .map(|(i, a)| TrajectoryPoint::new(i as u32, a.to_string(), String::new()))
+            .collect();
+        serde_json::to_string(&points).unwrap()
+    }
+
+    #[test]
+    fn test_lower_threshold_blocks_more() {
+        let inputs = [
+            trajectory(&["Search", "Delete", "Delete", "Exfiltrate"]), // risk 1.0
+            trajectory(&["Search", "Delete", "Read", "Read"]),         // risk 0.5
+            trajectory(&["Search", "Read", "Read", "Read"]),           // risk 0.25
+            trajectory(&["Read", "Read", "Read", "Read"]),             // risk 0.0
+        ];
+        let mut shield = PredictiveSafetyShield::new(0.9);
+        shield.add_failure_pattern(
+            "wipe".to_string(),
+            trajectory(&["Search", "Delete", "Delete", "Exfiltrate"]),
+        );
+
+        for input in &inputs {
+            shield.check(input.clone());
+        }
+        let (checks, strict_blocks, strict_rate) = shield.stats();
+        assert_eq!((checks, strict_blocks), (4, 1));
+
+        shield.reset_stats();
+        shield.set_threshold(0.3).unwrap();
+        for input in &inputs {
+            shield.check(input.clone());
+        }
+        let (_, loose_blocks, loose_rate) = shield.stats();
+        assert_eq!(loose_blocks, 2);
+        assert!(loose_rate > strict_rate);
+
+        assert!(shield.set_threshold(1.5).is_err());
+    }
+
+    #[test]
+    fn test_loaded_trajectories_flag_matches() {
+        let shield = PredictiveSafetyShield::new(0.75);
+        let risky = trajectory(&["ToolCall", "ToolError", "ToolCall", "ToolError"]);
+        assert!(!shield.check(risky.clone()).0);
+
+        let loaded = shield.load_trajectories(vec![
+            risky.clone(),
+            risky.clone(),
+            "not json".to_string(),
+            "[]".to_string(),
+        ]);
+        assert_eq!(loaded, 1);
+        assert_eq!(shield.pattern_count(), 1);
+
+        let (blocked, risk, reason) = shield.check(risky);
+        assert!(blocked);
+        assert_eq!(risk, 1.0);
+        assert_eq!(
+            reason.as_deref(),
+            Some("Pattern matches historical failure pollinat")
+        );
+        assert!(
+            !shield
+                .check(trajectory(&["Search", "Read", "Read", "Read"]))
+                .0
+        );
+    }
+}
@@ -83,6 +83,29 @@ impl HistoryBuffer {
         data.len()
     }
 
+    pub fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    /// `buffer[i]`, with negative indices counting from the end
+    pub fn __getitem__(&self, index: isize) -> PyResult<TrajectoryPoint> {
+        self.get(index).ok_or_else(|| {
+            pyo3::exceptions::PyIndexError::new_err(format!(
+                "HistoryBuffer index {} out of range (len {})",
+                index,
+                self.len()
+            ))
+        })
+    }
+
+    /// Iterate point by point, cloning each one only when it is reached
+    pub fn __iter__(&self) -> HistoryIter {
+        HistoryIter {
+            buffer: self.fork(),
+            index: 0,
+        }
+    }
+
     /// Creates a shallow copy (Zero-Copy fork)
     pub fn fork(&self) -> Self {
         HistoryBuffer {
@@ -117,6 +140,95 @@ impl HistoryBuffer {
             self.add(last);
         }
     }
+
+    /// Append every point from another buffer, renumbering steps so the
+    /// merged trajectory stays monotonic.
+    pub fn merge_all(&self, other: &HistoryBuffer) {
+        self.merge_since(other, 0);
+    }
+
+    /// Append the points another buffer gained after `from_index`, e.g. the
+    /// length of the trajectory at the time it was forked.
+    pub fn merge_since(&self, other: &HistoryBuffer, from_index: usize) {
+        // Copy first: `other` may share our lock if it is a fork of this buffer
+        let points: Vec<TrajectoryPoint> = {
+            let data = other.inner.read();
+            data.iter().skip(from_index).cloned().collect()
+        };
+        self.append_renumbered(points);
+    }
+
+    /// Compare two trajectories by `(action, thought)` and report where they split.
+    ///
+    /// Returns JSON with `diverged_at` (first differing index, or null when the
+    /// buffers are identical), `common_prefix`, and the trailing points unique
+    /// to each side as `only_self` / `only_other`.
+    pub fn diff(&self, other: &HistoryBuffer) -> String {
+        let ours = self.get_raw();
+        let theirs = other.get_raw();
+
+        let common_prefix = ours
+            .iter()
+            .zip(theirs.iter())
+            .take_while(|(a, b)| a.action == b.action && a.thought == b.thought)
+            .count();
+        let diverged_at = if common_prefix == ours.len() && common_prefix == theirs.len() {
+            None
+        } else {
+            Some(common_prefix)
+        };
+
+        serde_json::json!({
+            "diverged_at": diverged_at,
+            "common_prefix": common_prefix,
+            "only_self": &ours[common_prefix..],
+            "only_other": &theirs[common_prefix..],
+        })
+        .to_string()
+    }
+}
+
+impl HistoryBuffer {
+    /// Point at `index` (negative counts from the end), or None if out of range
+    pub fn get(&self, index: isize) -> Option<TrajectoryPoint> {
+        let data = self.inner.read();
+        let resolved = if index < 0 {
+            data.len().checked_sub(index.unsigned_abs())?
+        } else {
+            index as usize
+        };
+        data.get(resolved).cloned()
+    }
+
+    fn append_renumbered(&self, points: Vec<TrajectoryPoint>) {
+        let mut data = self.inner.write();
+        let next = data.last().map(|p| p.step + 1).unwrap_or(0);
+        for (mut point, step) in points.into_iter().zip(next..) {
+            point.step = step;
+            data.push(point);
+        }
+    }
+}
+
+/// Iterator returned by `iter(HistoryBuffer)`. Reads the shared buffer
+/// lazily, so points appended during iteration are also yielded.
+#[pyclass]
+pub struct HistoryIter {
+    buffer: HistoryBuffer,
+    index: usize,
+}
+
+#[pymethods]
+impl HistoryIter {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<TrajectoryPoint> {
+        let point = self.buffer.inner.read().get(self.index).cloned()?;
+        self.index += 1;
+        Some(point)
+    }
 }
 
 /// Initialize tracing for the library.
@@ -132,6 +244,7 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Core types
     m.add_class::<TrajectoryPoint>()?;
     m.add_class::<HistoryBuffer>()?;
+    m.add_class::<HistoryIter>()?;
 
     // Configuration
     m.add_class::<core::config::CogOpsConfig>()?;
@@ -140,11 +253,13 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<core::agent::Agent>()?;
     m.add_class::<core::agent::AgentRegistry>()?;
     m.add_class::<core::runner::AgentGraphPy>()?;
+    core::error::register_exceptions(m)?;
 
     // Workflows
     m.add_class::<core::workflow::SequentialAgent>()?;
     m.add_class::<core::workflow::ParallelAgent>()?;
     m.add_class::<core::workflow::LoopAgent>()?;
+    m.add_class::<core::workflow::GraphWorkflow>()?;
 
     // Middleware
     m.add_class::<core::middleware::CogOpsContext>()?;
@@ -163,6 +278,7 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<intel::pruning::ContextFeatures>()?;
     m.add_class::<intel::pruning::ContextFragment>()?;
     m.add_class::<intel::pollination::FailureContext>()?;
+    m.add_class::<intel::pollination::TrajectorySummary>()?;
     m.add_class::<intel::pollination::ExperiencePack>()?;
     m.add_class::<intel::pollination::CrossPollination>()?;
     m.add_class::<utils::benchmark::AgentBenchmark>()?;
@@ -193,6 +309,8 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<compliance::escalation::EscalationResult>()?;
     m.add_class::<compliance::sanitizer::InputSanitizer>()?;
     m.add_class::<compliance::sanitizer::SanitizeResult>()?;
+    m.add_class::<compliance::gate::SecurityGate>()?;
+    m.add_class::<compliance::gate::GateDecision>()?;
 
     // Security (Secure Multi-Agent Trust)
     m.add_class::<core::security::AgentIdentity>()?;
@@ -233,6 +351,8 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<swarm::SwarmConfig>()?;
     m.add_class::<swarm::TensorSwarm>()?;
     m.add_class::<swarm::tensor_engine::TensorSwarm>()?;
+    m.add_class::<swarm::tensor_engine::SwarmMetrics>()?;
+    m.add_class::<swarm::tensor_engine::PromotionReason>()?;
     m.add_class::<swarm::promoter::PromotionLogic>()?;
     m.add_class::<swarm::pollination::PollinatorState>()?;
     m.add_class::<swarm::ProductionTensorSwarm>()?;
@@ -244,3 +364,99 @@ fn cogops_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_with(actions: &[&str]) -> HistoryBuffer {
+        let buffer = HistoryBuffer::new();
+        for (i, action) in actions.iter().enumerate() {
+            buffer.add(TrajectoryPoint::new(
+                i as u32,
+                action.to_string(),
+                String::new(),
+            ));
+        }
+        buffer
+    }
+
+    #[test]
+    fn merge_all_appends_and_renumbers() {
+        let main = buffer_with(&["plan", "search"]);
+        let branch = buffer_with(&["fetch", "summarize"]);
+
+        main.merge_all(&branch);
+
+        let raw = main.get_raw();
+        let actions: Vec<&str> = raw.iter().map(|p| p.action.as_str()).collect();
+        assert_eq!(actions, ["plan", "search", "fetch", "summarize"]);
+        let steps: Vec<u32> = raw.iter().map(|p| p.step).collect();
+        assert_eq!(steps, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn merge_since_skips_shared_prefix() {
+        let main = buffer_with(&["plan"]);
+        let branch = buffer_with(&["plan", "search", "answer"]);
+
+        main.merge_since(&branch, 1);
+        let raw = main.get_raw();
+        let actions: Vec<&str> = raw.iter().map(|p| p.action.as_str()).collect();
+        assert_eq!(actions, ["plan", "search", "answer"]);
+        assert_eq!(raw[2].step, 2);
+
+        main.merge_since(&branch, 10);
+        assert_eq!(main.len(), 3);
+
+        // A fork shares storage, so merging it into itself must not deadlock
+        let fork = main.fork();
+        main.merge_since(&fork, 2);
+        assert_eq!(main.len(), 4);
+        assert_eq!(main.last().unwrap().step, 3);
+    }
+
+    #[test]
+    fn diff_reports_divergence_point() {
+        let left = buffer_with(&["plan", "search", "fetch", "answer"]);
+        let right = buffer_with(&["plan", "search", "fetch", "retry", "answer"]);
+
+        let diff: serde_json::Value = serde_json::from_str(&left.diff(&right)).unwrap();
+        assert_eq!(diff["diverged_at"], 3);
+        assert_eq!(diff["common_prefix"], 3);
+        assert_eq!(diff["only_self"].as_array().unwrap().len(), 1);
+        assert_eq!(diff["only_self"][0]["action"], "answer");
+        assert_eq!(diff["only_other"].as_array().unwrap().len(), 2);
+        assert_eq!(diff["only_other"][0]["action"], "retry");
+
+        let same: serde_json::Value = serde_json::from_str(&left.diff(&left.fork())).unwrap();
+        assert!(same["diverged_at"].is_null());
+        assert!(same["only_other"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn indexing_supports_negative_and_rejects_out_of_range() {
+        let buffer = buffer_with(&["plan", "search", "answer"]);
+        assert_eq!(buffer.__len__(), 3);
+        assert_eq!(buffer.__getitem__(0).unwrap().action, "plan");
+        assert_eq!(buffer.__getitem__(2).unwrap().action, "answer");
+        assert_eq!(buffer.__getitem__(-1).unwrap().action, "answer");
+        assert_eq!(buffer.__getitem__(-3).unwrap().action, "plan");
+        assert!(buffer.__getitem__(3).is_err());
+        assert!(buffer.__getitem__(-4).is_err());
+        assert!(HistoryBuffer::new().get(0).is_none());
+    }
+
+    #[test]
+    fn iteration_yields_points_in_order() {
+        let buffer = buffer_with(&["plan", "search"]);
+        let mut iter = buffer.__iter__();
+        assert_eq!(iter.__next__().unwrap().action, "plan");
+
+        // The iterator reads the live buffer, so later appends are seen
+        buffer.add(TrajectoryPoint::new(2, "answer".to_string(), String::new()));
+        assert_eq!(iter.__next__().unwrap().action, "search");
+        assert_eq!(iter.__next__().unwrap().action, "answer");
+        assert!(iter.__next__().is_none());
+    }
+}
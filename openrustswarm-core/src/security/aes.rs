@@ -2,13 +2,50 @@ use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
 };
+use argon2::Argon2;
 use hex;
 use pyo3::prelude::*;
 use rand::RngCore;
 
+/// Result type for vault operations
+pub type VaultResult<T> = Result<T, VaultError>;
+
+/// Vault error types
+#[derive(Debug, Clone, PartialEq)]
+pub enum VaultError {
+    /// The ciphertext is not `[salt:]nonce:ciphertext` hex
+    InvalidFormat(String),
+    /// AES-GCM failed (wrong key, tampered data, ...)
+    Crypto(String),
+}
+
+impl std::fmt::Display for VaultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultError::InvalidFormat(msg) => write!(f, "{}", msg),
+            VaultError::Crypto(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+impl From<VaultError> for PyErr {
+    fn from(err: VaultError) -> PyErr {
+        match err {
+            VaultError::InvalidFormat(_) => {
+                pyo3::exceptions::PyValueError::new_err(err.to_string())
+            }
+            VaultError::Crypto(_) => pyo3::exceptions::PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
 #[pyclass]
 pub struct SecureVault {
     cipher: Aes256Gcm,
+    /// Salt used to derive the key from a passphrase (None for raw keys)
+    salt: Option<Vec<u8>>,
 }
 
 #[pymethods]
@@ -36,7 +73,47 @@ impl SecureVault {
         let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
         let cipher = Aes256Gcm::new(key);
 
-        Ok(SecureVault { cipher })
+        Ok(SecureVault { cipher, salt: None })
+    }
+
+    /// Derive a vault key from a passphrase using Argon2id.
+    /// The salt is embedded in every ciphertext so the key can be re-derived.
+    #[staticmethod]
+    pub fn from_passphrase(passphrase: String, salt: Vec<u8>) -> PyResult<Self> {
+        let mut key_bytes = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key_bytes)
+            .map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!("Key derivation failed: {}", e))
+            })?;
+
+        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+        let cipher = Aes256Gcm::new(key);
+
+        Ok(SecureVault {
+            cipher,
+            salt: Some(salt),
+        })
+    }
+
+    /// Generate a fresh 16-byte salt for passphrase derivation
+    #[staticmethod]
+    pub fn generate_salt() -> Vec<u8> {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        salt.to_vec()
+    }
+
+    /// Extract the embedded salt from a passphrase-sealed ciphertext
+    #[staticmethod]
+    pub fn extract_salt(encrypted_data: &str) -> PyResult<Option<Vec<u8>>> {
+        let parts: Vec<&str> = encrypted_data.split(':').collect();
+        if parts.len() != 3 {
+            return Ok(None);
+        }
+        hex::decode(parts[0]).map(Some).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid salt hex: {}", e))
+        })
     }
 
     /// Generate a fresh 32-byte key as hex
@@ -48,52 +125,148 @@ impl SecureVault {
     }
 
     /// Encrypt plaintext using AES-256-GCM
-    /// Returns "nonce_hex:ciphertext_hex"
+    /// Returns "nonce_hex:ciphertext_hex", prefixed with "salt_hex:" for
+    /// passphrase-derived vaults
     pub fn encrypt(&self, plaintext: &str) -> PyResult<String> {
+        Ok(self.seal(plaintext.as_bytes())?)
+    }
+
+    /// Decrypt ciphertext using AES-256-GCM
+    pub fn decrypt(&self, encrypted_data: &str) -> PyResult<String> {
+        let plaintext_bytes = self.open(encrypted_data)?;
+
+        String::from_utf8(plaintext_bytes).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid UTF-8 plaintext: {}", e))
+        })
+    }
+
+    /// Re-encrypt a ciphertext blob (the bytes of an `encrypt` result) under
+    /// another vault's key (key rotation). The plaintext never leaves Rust.
+    pub fn reencrypt(&self, ciphertext: Vec<u8>, new_vault: &SecureVault) -> PyResult<Vec<u8>> {
+        Ok(self.rotate(&ciphertext, new_vault)?)
+    }
+}
+
+impl SecureVault {
+    /// GIL-free body of `reencrypt`
+    pub fn rotate(&self, ciphertext: &[u8], new_vault: &SecureVault) -> VaultResult<Vec<u8>> {
+        let encrypted_data = std::str::from_utf8(ciphertext).map_err(|e| {
+            VaultError::InvalidFormat(format!("Ciphertext is not valid UTF-8: {}", e))
+        })?;
+        let plaintext_bytes = self.open(encrypted_data).map_err(|e| {
+            VaultError::Crypto(format!(
+                "Re-encryption failed, original ciphertext did not authenticate: {}",
+                e
+            ))
+        })?;
+        Ok(new_vault.seal(&plaintext_bytes)?.into_bytes())
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> VaultResult<String> {
         let mut nonce_bytes = [0u8; 12];
         rand::thread_rng().fill_bytes(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
 
         let ciphertext = self
             .cipher
-            .encrypt(nonce, plaintext.as_bytes())
-            .map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(format!("Encryption failed: {}", e))
-            })?;
+            .encrypt(nonce, plaintext)
+            .map_err(|e| VaultError::Crypto(format!("Encryption failed: {}", e)))?;
 
-        Ok(format!(
-            "{}:{}",
-            hex::encode(nonce_bytes),
-            hex::encode(ciphertext)
-        ))
+        match &self.salt {
+            Some(salt) => Ok(format!(
+                "{}:{}:{}",
+                hex::encode(salt),
+                hex::encode(nonce_bytes),
+                hex::encode(ciphertext)
+            )),
+            None => Ok(format!(
+                "{}:{}",
+                hex::encode(nonce_bytes),
+                hex::encode(ciphertext)
+            )),
+        }
     }
 
-    /// Decrypt ciphertext using AES-256-GCM
-    pub fn decrypt(&self, encrypted_data: &str) -> PyResult<String> {
-        let parts: Vec<&str> = encrypted_data.split(':').collect();
+    fn open(&self, encrypted_data: &str) -> VaultResult<Vec<u8>> {
+        let mut parts: Vec<&str> = encrypted_data.split(':').collect();
+        if parts.len() == 3 {
+            let salt = hex::decode(parts[0])
+                .map_err(|e| VaultError::InvalidFormat(format!("Invalid salt hex: {}", e)))?;
+            if let Some(own_salt) = &self.salt {
+                if own_salt != &salt {
+                    return Err(VaultError::InvalidFormat(
+                        "Salt mismatch: ciphertext was sealed with a different derived key"
+                            .to_string(),
+                    ));
+                }
+            }
+            parts.remove(0);
+        }
         if parts.len() != 2 {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Invalid encrypted format. Expected 'nonce:ciphertext'",
+            return Err(VaultError::InvalidFormat(
+                "Invalid encrypted format. Expected '[salt:]nonce:ciphertext'".to_string(),
             ));
         }
 
-        let nonce_bytes = hex::decode(parts[0]).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid nonce hex: {}", e))
-        })?;
-        let ciphertext = hex::decode(parts[1]).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid ciphertext hex: {}", e))
-        })?;
+        let nonce_bytes = hex::decode(parts[0])
+            .map_err(|e| VaultError::InvalidFormat(format!("Invalid nonce hex: {}", e)))?;
+        if nonce_bytes.len() != 12 {
+            return Err(VaultError::InvalidFormat(
+                "Invalid nonce length, expected 12 bytes".to_string(),
+            ));
+        }
+        let ciphertext = hex::decode(parts[1])
+            .map_err(|e| VaultError::InvalidFormat(format!("Invalid ciphertext hex: {}", e)))?;
 
         let nonce = Nonce::from_slice(&nonce_bytes);
-        let plaintext_bytes = self
-            .cipher
+        self.cipher
             .decrypt(nonce, ciphertext.as_ref())
-            .map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(format!("Decryption failed: {}", e))
-            })?;
+            .map_err(|e| VaultError::Crypto(format!("Decryption failed: {}", e)))
+    }
+}
 
-        String::from_utf8(plaintext_bytes).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!("Invalid UTF-8 plaintext: {}", e))
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_roundtrip_and_wrong_passphrase() {
+        let salt = SecureVault::generate_salt();
+        let vault =
+            SecureVault::from_passphrase("correct horse".to_string(), salt.clone()).unwrap();
+        let sealed = vault.encrypt("top secret").unwrap();
+
+        assert_eq!(
+            SecureVault::extract_salt(&sealed).unwrap(),
+            Some(salt.clone())
+        );
+
+        let same = SecureVault::from_passphrase("correct horse".to_string(), salt.clone()).unwrap();
+        assert_eq!(same.decrypt(&sealed).unwrap(), "top secret");
+
+        let wrong = SecureVault::from_passphrase("battery staple".to_string(), salt).unwrap();
+        assert!(wrong.decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn reencrypt_rotates_to_new_key() {
+        let old_vault = SecureVault::new(None).unwrap();
+        let new_vault = SecureVault::new(None).unwrap();
+
+        let sealed = old_vault
+            .encrypt("quarterly rotation")
+            .unwrap()
+            .into_bytes();
+        let rotated = old_vault.rotate(&sealed, &new_vault).unwrap();
+        let rotated = String::from_utf8(rotated).unwrap();
+
+        assert_eq!(new_vault.decrypt(&rotated).unwrap(), "quarterly rotation");
+        assert!(old_vault.open(&rotated).is_err());
+
+        // Ciphertext from the wrong vault must not authenticate
+        assert!(matches!(
+            new_vault.rotate(&sealed, &old_vault),
+            Err(VaultError::Crypto(_))
+        ));
     }
 }
@@ -55,7 +55,7 @@ mod tests {
             let t = Instant::now();
 
             // Snapshot BEFORE tick
-            let (count, mean_dist, peak_s, spatial_std) = measure_surprise_state(&engine, origin);
+            let (count, mean_dist, peak_s, spatial_std) = engine.surprise_stats(origin);
 
             if tick == 0 {
                 prev_count = count;
@@ -72,7 +72,7 @@ mod tests {
             let elapsed = t.elapsed();
 
             // Snapshot AFTER tick
-            let (new_count, new_mean_dist, new_peak_s, new_spatial_std) = measure_surprise_state(&engine, origin);
+            let (new_count, new_mean_dist, new_peak_s, new_spatial_std) = engine.surprise_stats(origin);
 
             let delta = new_count as i64 - prev_count as i64;
             let growth = if prev_delta != 0 {
@@ -96,7 +96,7 @@ mod tests {
         }
 
         // Final analysis
-        let (final_count, final_mean_dist, final_peak, _) = measure_surprise_state(&engine, origin);
+        let (final_count, final_mean_dist, final_peak, _) = engine.surprise_stats(origin);
 
         println!("\n{}", sep);
         println!("  ANALYSIS");
@@ -118,46 +118,4 @@ mod tests {
         println!("  Peak surprise remaining: {:.4}", final_peak);
         println!("{}\n", sep);
     }
-
-    /// Measure surprise state: count, mean distance from origin, peak value, spatial std
-    fn measure_surprise_state(engine: &SwarmEngineMaster, origin: (f32, f32)) -> (u64, f64, f32, f64) {
-        let x = engine.pool.x.as_slice();
-        let y = engine.pool.y.as_slice();
-        let surprise = engine.pool.surprise.as_slice();
-
-        let threshold = 0.1;
-        let mut count = 0u64;
-        let mut sum_dist = 0.0f64;
-        let mut peak = 0.0f32;
-
-        for i in 0..engine.pool.n_agents {
-            if surprise[i] > threshold {
-                count += 1;
-                let dx = (x[i] - origin.0) as f64;
-                let dy = (y[i] - origin.1) as f64;
-                sum_dist += (dx * dx + dy * dy).sqrt();
-                if surprise[i] > peak {
-                    peak = surprise[i];
-                }
-            }
-        }
-
-        let mean_dist = if count > 0 { sum_dist / count as f64 } else { 0.0 };
-
-        // Spatial std dev
-        let mut sum_sq = 0.0f64;
-        if count > 0 {
-            for i in 0..engine.pool.n_agents {
-                if surprise[i] > threshold {
-                    let dx = (x[i] - origin.0) as f64;
-                    let dy = (y[i] - origin.1) as f64;
-                    let d = (dx * dx + dy * dy).sqrt();
-                    sum_sq += (d - mean_dist) * (d - mean_dist);
-                }
-            }
-        }
-        let std_dev = if count > 1 { (sum_sq / (count - 1) as f64).sqrt() } else { 0.0 };
-
-        (count, mean_dist, peak, std_dev)
-    }
 }
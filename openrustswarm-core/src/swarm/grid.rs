@@ -164,6 +164,48 @@ impl SpatialHashGrid {
         }
     }
 
+    /// Query all candidates in the rectangle spanned by (x0, y0) and (x1, y1).
+    ///
+    /// Each bucket is visited once, so every candidate is reported exactly once
+    /// even when several cells of the rectangle hash to the same bucket.
+    /// A rectangle spanning at least `table_size` cells scans every bucket
+    /// instead of walking its cells. Callers MUST still perform an exact
+    /// bounds check.
+    pub fn query_rect<F>(&self, x0: f32, y0: f32, x1: f32, y1: f32, mut callback: F)
+    where
+        F: FnMut(u32),
+    {
+        let (cx0, cy0) = self.world_to_cell(x0.min(x1), y0.min(y1));
+        let (cx1, cy1) = self.world_to_cell(x0.max(x1), y0.max(y1));
+
+        let cells = (cx1 as i64 - cx0 as i64 + 1).saturating_mul(cy1 as i64 - cy0 as i64 + 1);
+        if cells >= self.table_size as i64 {
+            for h in 0..self.table_size {
+                let start = self.offsets[h] as usize;
+                let end   = start + self.counts[h] as usize;
+                for &idx in &self.data[start..end] {
+                    callback(idx);
+                }
+            }
+            return;
+        }
+
+        let mut visited = std::collections::HashSet::new();
+        for cy in cy0..=cy1 {
+            for cx in cx0..=cx1 {
+                let h = self.hash(cx, cy);
+                if !visited.insert(h) {
+                    continue;
+                }
+                let start = self.offsets[h] as usize;
+                let end   = start + self.counts[h] as usize;
+                for &idx in &self.data[start..end] {
+                    callback(idx);
+                }
+            }
+        }
+    }
+
     /// Same as `query_radius` but skips `self_idx`.
     #[inline]
     pub fn query_neighbors<F>(
@@ -222,4 +264,15 @@ mod tests {
                 |j| assert_ne!(j, i));
         }
     }
+
+    #[test]
+    fn huge_rect_scans_every_bucket_once() {
+        let pool = make_pool_circle(200, 5.0);
+        let mut grid = SpatialHashGrid::new(256, 1.0, [-10.0, -10.0]);
+        grid.rebuild(&pool);
+
+        let mut seen = vec![0u32; pool.n_agents];
+        grid.query_rect(-1e30, -1e30, 1e30, 1e30, |i| seen[i as usize] += 1);
+        assert!(seen.iter().all(|&c| c == 1));
+    }
 }
@@ -88,7 +88,12 @@ pub struct ProductionTensorSwarm {
     global_triggers: u64,
 
     // Global simulation clock
+    #[pyo3(get)]
     pub tick_count: u64,
+
+    /// While set, `tick` (and therefore `run`) leaves the simulation untouched
+    #[pyo3(get, set)]
+    pub paused: bool,
 }
 
 #[pymethods]
@@ -106,6 +111,7 @@ impl ProductionTensorSwarm {
             active: TensorSwarm::new(agent_count, world_config, config),
             global_triggers: 0,
             tick_count: 0,
+            paused: false,
         }
     }
 
@@ -121,6 +127,10 @@ impl ProductionTensorSwarm {
 
     /// Primary execution loop. Distributes clock cycles across the Tiers.
     pub fn tick(&mut self) {
+        if self.paused {
+            return;
+        }
+
         // 1. Update dormant agents (extremely fast bitflag checks)
         self.check_dormant_wakeups();
 
@@ -140,6 +150,19 @@ impl ProductionTensorSwarm {
         self.tick_count += 1;
     }
 
+    /// Advance `ticks` steps without the GIL, then drain the promotion queue
+    pub fn run(&mut self, py: Python<'_>, ticks: usize) -> Vec<u32> {
+        py.allow_threads(|| self.run_ticks(ticks))
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
     /// Checks if dormant agents need to wake up
     fn check_dormant_wakeups(&mut self) {
         // Very fast bitwise operation using SIMD/Iterator
@@ -180,4 +203,40 @@ impl ProductionTensorSwarm {
     pub fn pop_promotions(&mut self) -> Vec<u32> {
         self.active.pop_promotions()
     }
+
+    /// Heavy agents retired by `max_heavy_agents` since the last call
+    pub fn pop_retired(&mut self) -> Vec<u32> {
+        self.active.pop_retired()
+    }
+}
+
+impl ProductionTensorSwarm {
+    /// GIL-free body of `run`
+    pub fn run_ticks(&mut self, ticks: usize) -> Vec<u32> {
+        for _ in 0..ticks {
+            self.tick();
+        }
+        self.pop_promotions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_advances_tick_count_unless_paused() {
+        let mut swarm = ProductionTensorSwarm::new(16, None, None);
+        swarm.run_ticks(100);
+        assert_eq!(swarm.tick_count, 100);
+        assert_eq!(swarm.active.global_tick, 100);
+
+        swarm.pause();
+        swarm.run_ticks(50);
+        assert_eq!(swarm.tick_count, 100);
+
+        swarm.resume();
+        swarm.run_ticks(1);
+        assert_eq!(swarm.tick_count, 101);
+    }
 }
@@ -1,8 +1,100 @@
 use super::mmap_pool::MmapSwarmPool;
 use super::pheromone::PheromoneField;
 use super::grid::SpatialHashGrid;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
 use std::time::Instant;
 
+/// Observer invoked at the end of every tick with the pool and the tick number.
+/// The pool is only borrowed for the duration of the call.
+pub type TickHook = Box<dyn FnMut(&MmapSwarmPool, u64) + Send>;
+
+/// Surprise above which `surprise_stats` counts an agent as surprised
+pub const SURPRISED_THRESHOLD: f32 = 0.1;
+
+/// Steering weights used by the neighbor physics each tick
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FlockingParams {
+    /// Fraction of the previous velocity kept each tick
+    pub momentum: f32,
+    /// Pull toward the neighbors' center of mass
+    pub cohesion: f32,
+    /// Push away from each close neighbor
+    pub separation: f32,
+    /// Attraction up the trail (channel 2) gradient
+    pub trail_weight: f32,
+    /// Repulsion down the danger (channel 1) gradient
+    pub danger_weight: f32,
+    /// Amplitude of the uniform random exploration force
+    pub exploration: f32,
+    /// Velocity magnitude cap
+    pub max_speed: f32,
+}
+
+impl Default for FlockingParams {
+    fn default() -> Self {
+        Self {
+            momentum: 0.9,
+            cohesion: 0.01,
+            separation: 0.05,
+            trail_weight: 0.3,
+            danger_weight: 0.5,
+            exploration: 0.1,
+            max_speed: 2.0,
+        }
+    }
+}
+
+/// Scalar state stored in `engine.json` by `SwarmEngineMaster::save`
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    n_agents: usize,
+    width: f32,
+    height: f32,
+    perception_radius: f32,
+    global_tick: u64,
+    deposit_stride: usize,
+    trail_amount: f32,
+    danger_threshold: f32,
+    goal_weight: f32,
+    #[serde(default)]
+    flocking: FlockingParams,
+    pheromones: PheromoneCheckpoint,
+}
+
+/// Pheromone field parameters; the cell values live in `pheromones.bin`
+/// and the obstacle mask in `obstacles.bin`
+#[derive(Serialize, Deserialize)]
+struct PheromoneCheckpoint {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    decay_rates: [f32; 7],
+    diffusion: [f32; 7],
+    sense_radius: Vec<f32>,
+    goal: Option<(usize, usize)>,
+    max_per_cell: Option<[f32; 7]>,
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// The spatial grid uses the radius as its cell size, so it must be a
+/// finite, positive length
+fn check_perception_radius(radius: f32) -> Result<(), String> {
+    if radius.is_finite() && radius > 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "perception radius must be finite and positive, got {}",
+            radius
+        ))
+    }
+}
+
 /// The master orchestrator for the 100-Million Agent Swarm.
 ///
 /// v3.1.0 Architecture:
@@ -20,10 +112,32 @@ pub struct SwarmEngineMaster {
     pub height: f32,
     pub perception_radius: f32,
     pub global_tick: u64,
+
+    // Pheromone deposit tuning
+    /// Only every `deposit_stride`-th agent deposits each tick
+    pub deposit_stride: usize,
+    /// Trail amount (channel 2) left by each depositing agent
+    pub trail_amount: f32,
+    /// Surprise above which an agent emits danger (channel 1), scaled by surprise²
+    pub danger_threshold: f32,
+
+    /// Steering strength toward the goal set with `set_goal`
+    pub goal_weight: f32,
+
+    /// Cohesion, separation, pheromone and speed tuning for the physics step
+    pub flocking: FlockingParams,
+
+    /// Optional per-tick observer (e.g. live visualization)
+    pub on_tick: Option<TickHook>,
 }
 
 impl SwarmEngineMaster {
     pub fn new(n_agents: usize, width: f32, height: f32) -> Self {
+        Self::with_flocking(n_agents, width, height, FlockingParams::default())
+    }
+
+    /// Like `new`, with custom steering weights.
+    pub fn with_flocking(n_agents: usize, width: f32, height: f32, flocking: FlockingParams) -> Self {
         let mut pool = MmapSwarmPool::new(n_agents);
         pool.randomize_positions(width, height);
 
@@ -31,19 +145,24 @@ impl SwarmEngineMaster {
         let pheromone_res = if n_agents >= 10_000_000 { 2048 } else { 1024 };
         let perception = 10.0; // agents perceive neighbors within 10 units
 
-        Self {
+        let mut engine = Self {
             pool,
             pheromones: PheromoneField::new(pheromone_res, pheromone_res, width / pheromone_res as f32),
-            grid: SpatialHashGrid::new(
-                if n_agents >= 10_000_000 { 1 << 20 } else { 1 << 18 },
-                perception, // cell_size = perception_radius for optimal 3x3 query
-                [0.0, 0.0],
-            ),
+            grid: Self::build_grid(n_agents, perception),
             width,
             height,
             perception_radius: perception,
             global_tick: 0,
-        }
+            deposit_stride: 100,
+            trail_amount: 0.1,
+            danger_threshold: 0.5,
+            goal_weight: 0.5,
+            flocking,
+            on_tick: None,
+        };
+        // Region queries work before the first tick
+        engine.rebuild_grid();
+        engine
     }
 
     /// The master tick function for 100M agents.
@@ -52,8 +171,10 @@ impl SwarmEngineMaster {
     /// 1. Spatial locality sorting (amortized every 100 ticks)
     /// 2. Rebuild spatial hash grid
     /// 3. Neighbor-driven physics: cohesion, separation, surprise propagation
-    /// 4. Pheromone deposit + diffusion
-    /// 5. Health decay
+    /// 4. Pheromone deposit
+    /// 5. Pheromone diffusion + decay
+    /// 6. Health decay
+    /// 7. `on_tick` hook
     pub fn tick(&mut self) {
         let start_time = Instant::now();
         self.global_tick += 1;
@@ -89,6 +210,276 @@ impl SwarmEngineMaster {
                 elapsed,
             );
         }
+
+        // 7. Observer hook: gets a shared borrow that ends before the next tick
+        if let Some(hook) = self.on_tick.as_mut() {
+            hook(&self.pool, self.global_tick);
+        }
+    }
+
+    /// Checkpoint the engine into `dir` (created if missing): one raw file
+    /// per pool column, `pheromones.bin`, `obstacles.bin` and `engine.json`
+    /// with the tick counter and tuning. Column files use native endianness.
+    /// The `on_tick` hook is not saved.
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        self.pool.save_columns(dir)?;
+
+        let field = &self.pheromones;
+        let bytes: Vec<u8> = field.data.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        File::create(dir.join("pheromones.bin"))?.write_all(&bytes)?;
+        let mask: Vec<u8> = field.obstacles.iter().map(|&o| o as u8).collect();
+        File::create(dir.join("obstacles.bin"))?.write_all(&mask)?;
+
+        let checkpoint = Checkpoint {
+            n_agents: self.pool.n_agents,
+            width: self.width,
+            height: self.height,
+            perception_radius: self.perception_radius,
+            global_tick: self.global_tick,
+            deposit_stride: self.deposit_stride,
+            trail_amount: self.trail_amount,
+            danger_threshold: self.danger_threshold,
+            goal_weight: self.goal_weight,
+            flocking: self.flocking,
+            pheromones: PheromoneCheckpoint {
+                width: field.width,
+                height: field.height,
+                cell_size: field.cell_size,
+                decay_rates: field.decay_rates,
+                diffusion: field.diffusion,
+                sense_radius: field.sense_radius.clone(),
+                goal: field.goal,
+                max_per_cell: field.max_per_cell,
+            },
+        };
+        let json = serde_json::to_vec_pretty(&checkpoint).map_err(|e| invalid(e.to_string()))?;
+        File::create(dir.join("engine.json"))?.write_all(&json)
+    }
+
+    /// Restore an engine written by `save`. Pool columns are mapped
+    /// copy-on-write from their files, so the checkpoint itself is never
+    /// modified. Fails with `InvalidData` if any file disagrees with the
+    /// dimensions recorded in `engine.json`.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut json = Vec::new();
+        File::open(dir.join("engine.json"))?.read_to_end(&mut json)?;
+        let checkpoint: Checkpoint =
+            serde_json::from_slice(&json).map_err(|e| invalid(format!("engine.json: {}", e)))?;
+        let meta = &checkpoint.pheromones;
+
+        let cells = meta.width * meta.height;
+        let mut bytes = Vec::new();
+        File::open(dir.join("pheromones.bin"))?.read_to_end(&mut bytes)?;
+        if bytes.len() != 7 * cells * 4 {
+            return Err(invalid(format!(
+                "pheromones.bin holds {} bytes, expected {} for a {}x{} field",
+                bytes.len(),
+                7 * cells * 4,
+                meta.width,
+                meta.height
+            )));
+        }
+        let mut mask = Vec::new();
+        File::open(dir.join("obstacles.bin"))?.read_to_end(&mut mask)?;
+        if mask.len() != cells {
+            return Err(invalid(format!(
+                "obstacles.bin holds {} cells, expected {}",
+                mask.len(),
+                cells
+            )));
+        }
+        if meta.sense_radius.len() != 7 {
+            return Err(invalid(format!(
+                "expected 7 sense radii, got {}",
+                meta.sense_radius.len()
+            )));
+        }
+        check_perception_radius(checkpoint.perception_radius).map_err(invalid)?;
+        if let Some((gx, gy)) = meta.goal {
+            if gx >= meta.width || gy >= meta.height {
+                return Err(invalid(format!("goal cell ({}, {}) is outside the field", gx, gy)));
+            }
+        }
+
+        let mut pheromones = PheromoneField::with_diffusion(
+            meta.width,
+            meta.height,
+            meta.cell_size,
+            meta.diffusion,
+        );
+        for (value, chunk) in pheromones.data.iter_mut().zip(bytes.chunks_exact(4)) {
+            *value = f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        pheromones.obstacles = mask.iter().map(|&o| o != 0).collect();
+        pheromones.decay_rates = meta.decay_rates;
+        pheromones.sense_radius = meta.sense_radius.clone();
+        pheromones.goal = meta.goal;
+        pheromones.max_per_cell = meta.max_per_cell;
+
+        let n_agents = checkpoint.n_agents;
+        let mut engine = Self {
+            pool: MmapSwarmPool::load_columns(dir, n_agents)?,
+            pheromones,
+            grid: Self::build_grid(n_agents, checkpoint.perception_radius),
+            width: checkpoint.width,
+            height: checkpoint.height,
+            perception_radius: checkpoint.perception_radius,
+            global_tick: checkpoint.global_tick,
+            deposit_stride: checkpoint.deposit_stride,
+            trail_amount: checkpoint.trail_amount,
+            danger_threshold: checkpoint.danger_threshold,
+            goal_weight: checkpoint.goal_weight,
+            flocking: checkpoint.flocking,
+            on_tick: None,
+        };
+        engine.rebuild_grid();
+        Ok(engine)
+    }
+
+    /// Replace the steering weights; takes effect on the next tick.
+    pub fn set_flocking_params(&mut self, flocking: FlockingParams) {
+        self.flocking = flocking;
+    }
+
+    /// Change how far agents perceive neighbors. The spatial grid is rebuilt
+    /// with matching cells so the 3x3 neighbor query stays exact. Fails,
+    /// leaving the engine unchanged, unless `radius` is finite and positive.
+    pub fn set_perception_radius(&mut self, radius: f32) -> Result<(), String> {
+        check_perception_radius(radius)?;
+        self.perception_radius = radius;
+        self.grid = Self::build_grid(self.pool.n_agents, radius);
+        self.rebuild_grid();
+        Ok(())
+    }
+
+    /// Snapshot of a surprise cascade around `origin`: (agents above
+    /// `SURPRISED_THRESHOLD`, their mean distance from `origin`, the peak
+    /// surprise, and the sample std dev of those distances). A growing count
+    /// and mean distance means the wave is spreading; a falling peak means it
+    /// is dying out.
+    pub fn surprise_stats(&self, origin: (f32, f32)) -> (u64, f64, f32, f64) {
+        let x = self.pool.x.as_slice();
+        let y = self.pool.y.as_slice();
+        let surprise = self.pool.surprise.as_slice();
+        // (distance from origin, surprise) of every surprised agent
+        let surprised = || {
+            x.iter()
+                .zip(y)
+                .zip(surprise)
+                .filter(|(_, &s)| s > SURPRISED_THRESHOLD)
+                .map(|((&x, &y), &s)| {
+                    let dx = (x - origin.0) as f64;
+                    let dy = (y - origin.1) as f64;
+                    ((dx * dx + dy * dy).sqrt(), s)
+                })
+        };
+
+        let mut count = 0u64;
+        let mut sum_dist = 0.0f64;
+        let mut peak = 0.0f32;
+        for (dist, s) in surprised() {
+            count += 1;
+            sum_dist += dist;
+            peak = peak.max(s);
+        }
+        let mean_dist = if count > 0 { sum_dist / count as f64 } else { 0.0 };
+
+        // Second pass for the spread around the mean distance
+        let sum_sq: f64 = surprised().map(|(dist, _)| (dist - mean_dist).powi(2)).sum();
+        let std_dev = if count > 1 { (sum_sq / (count - 1) as f64).sqrt() } else { 0.0 };
+
+        (count, mean_dist, peak, std_dev)
+    }
+
+    /// Set the navigation goal. The goal channel is rebuilt once here (and on
+    /// obstacle changes), not every tick.
+    pub fn set_goal(&mut self, x: f32, y: f32) {
+        self.pheromones.set_goal(x, y);
+    }
+
+    /// Mark the rectangle spanned by (x0, y0) and (x1, y1) as a wall.
+    pub fn add_obstacle(&mut self, x0: f32, y0: f32, x1: f32, y1: f32) {
+        self.pheromones.set_obstacle_rect(x0, y0, x1, y1, true);
+    }
+
+    /// Number of agents inside the rectangle spanned by (x0, y0) and (x1, y1).
+    pub fn count_in_region(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> u64 {
+        let mut count = 0u64;
+        self.for_each_in_region(x0, y0, x1, y1, |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+
+    /// Up to `max` `(x, y, surprise)` samples of agents inside the rectangle.
+    pub fn sample_region(
+        &self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        max: usize,
+    ) -> Vec<(f32, f32, f32)> {
+        let x = self.pool.x.as_slice();
+        let y = self.pool.y.as_slice();
+        let surprise = self.pool.surprise.as_slice();
+
+        let mut samples = Vec::new();
+        self.for_each_in_region(x0, y0, x1, y1, |i| {
+            if samples.len() < max {
+                samples.push((x[i], y[i], surprise[i]));
+            }
+            samples.len() < max
+        });
+        samples
+    }
+
+    /// Visits agents inside the rectangle via the spatial grid until `visit`
+    /// returns false. The grid is built before physics moves agents, so the
+    /// query is widened by one cell (more than the per-tick velocity cap)
+    /// and then filtered on current positions. The cell walk is clamped to
+    /// the world, where every agent lives.
+    fn for_each_in_region<F>(&self, x0: f32, y0: f32, x1: f32, y1: f32, mut visit: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let (min_x, max_x) = (x0.min(x1), x0.max(x1));
+        let (min_y, max_y) = (y0.min(y1), y0.max(y1));
+        if max_x < 0.0 || max_y < 0.0 || min_x > self.width || min_y > self.height {
+            return;
+        }
+        let margin = self.grid.cell_size;
+        let x = self.pool.x.as_slice();
+        let y = self.pool.y.as_slice();
+
+        let mut done = false;
+        self.grid.query_rect(
+            min_x.max(0.0) - margin,
+            min_y.max(0.0) - margin,
+            max_x.min(self.width) + margin,
+            max_y.min(self.height) + margin,
+            |idx| {
+                let i = idx as usize;
+                if done || i >= self.pool.n_agents {
+                    return;
+                }
+                if x[i] >= min_x && x[i] <= max_x && y[i] >= min_y && y[i] <= max_y {
+                    done = !visit(i);
+                }
+            },
+        );
+    }
+
+    /// Spatial grid sized for `n_agents`, with cells as wide as the
+    /// perception radius for an optimal 3x3 query
+    fn build_grid(n_agents: usize, perception: f32) -> SpatialHashGrid {
+        SpatialHashGrid::new(
+            if n_agents >= 10_000_000 { 1 << 20 } else { 1 << 18 },
+            perception,
+            [0.0, 0.0],
+        )
     }
 
     /// O(N) two-pass rebuild of the Fibonacci spatial hash grid.
@@ -121,7 +512,7 @@ impl SwarmEngineMaster {
     /// 2. Compute cohesion force (move toward neighbor center-of-mass)
     /// 3. Compute separation force (avoid overlap with nearby agents)
     /// 4. Propagate surprise: if a neighbor has high surprise, absorb some
-    /// 5. Follow pheromone trail gradients
+    /// 5. Follow pheromone trail gradients and the obstacle-aware goal field
     /// 6. Update velocity and position
     fn run_neighbor_physics(&mut self) {
         let n = self.pool.n_agents;
@@ -129,6 +520,8 @@ impl SwarmEngineMaster {
         let height = self.height;
         let r = self.perception_radius;
         let r2 = r * r;
+        let goal_weight = self.goal_weight;
+        let params = self.flocking;
 
         // We need to read positions + surprise and write to velocity + surprise.
         // To avoid aliasing issues, we compute new velocities and surprise into
@@ -188,38 +581,43 @@ impl SwarmEngineMaster {
             });
 
             // Compute steering forces
-            let mut fx = vx[i] * 0.9; // momentum
-            let mut fy = vy[i] * 0.9;
+            let mut fx = vx[i] * params.momentum;
+            let mut fy = vy[i] * params.momentum;
 
             if neighbor_count > 0 {
                 let nc = neighbor_count as f32;
 
                 // Cohesion: steer toward center of neighbors
-                let cohesion_x = (sum_x / nc - px) * 0.01;
-                let cohesion_y = (sum_y / nc - py) * 0.01;
+                let cohesion_x = (sum_x / nc - px) * params.cohesion;
+                let cohesion_y = (sum_y / nc - py) * params.cohesion;
                 fx += cohesion_x;
                 fy += cohesion_y;
 
                 // Separation: push away from overlapping neighbors
-                fx += sep_x * 0.05;
-                fy += sep_y * 0.05;
+                fx += sep_x * params.separation;
+                fy += sep_y * params.separation;
             }
 
             // Pheromone gradient steering
             let (gx_trail, gy_trail) = self.pheromones.gradient(px, py, 2); // Trail
             let (gx_danger, gy_danger) = self.pheromones.gradient(px, py, 1); // Danger
-            fx += 0.3 * gx_trail - 0.5 * gx_danger;
-            fy += 0.3 * gy_trail - 0.5 * gy_danger;
+            fx += params.trail_weight * gx_trail - params.danger_weight * gx_danger;
+            fy += params.trail_weight * gy_trail - params.danger_weight * gy_danger;
+
+            // Goal attraction: walk the BFS wavefront around obstacles
+            let (gx_goal, gy_goal) = self.pheromones.goal_direction(px, py);
+            fx += goal_weight * gx_goal;
+            fy += goal_weight * gy_goal;
 
             // Random exploration
-            fx += (rand::random::<f32>() - 0.5) * 0.1;
-            fy += (rand::random::<f32>() - 0.5) * 0.1;
+            fx += (rand::random::<f32>() - 0.5) * params.exploration;
+            fy += (rand::random::<f32>() - 0.5) * params.exploration;
 
             // Clamp velocity
             let mag = (fx * fx + fy * fy).sqrt().max(0.001);
-            if mag > 2.0 {
-                fx = fx / mag * 2.0;
-                fy = fy / mag * 2.0;
+            if mag > params.max_speed {
+                fx = fx / mag * params.max_speed;
+                fy = fy / mag * params.max_speed;
             }
 
             new_vx[i] = fx;
@@ -237,34 +635,322 @@ impl SwarmEngineMaster {
         let out_x = self.pool.x.as_mut_slice();
         let out_y = self.pool.y.as_mut_slice();
 
+        // Obstacles are solid: a blocked move slides along the free axis,
+        // dropping the velocity component that pointed into the wall
+        let field = &self.pheromones;
         for i in 0..n {
-            out_vx[i] = new_vx[i];
-            out_vy[i] = new_vy[i];
+            let (px, py) = (out_x[i], out_y[i]);
+            let nx = (px + new_vx[i]).clamp(0.0, width);
+            let ny = (py + new_vy[i]).clamp(0.0, height);
+            let (mut vx, mut vy) = (new_vx[i], new_vy[i]);
+            let (x, y) = if !field.is_obstacle(nx, ny) {
+                (nx, ny)
+            } else if !field.is_obstacle(px, ny) {
+                vx = 0.0;
+                (px, ny)
+            } else if !field.is_obstacle(nx, py) {
+                vy = 0.0;
+                (nx, py)
+            } else {
+                vx = 0.0;
+                vy = 0.0;
+                (px, py)
+            };
+            out_vx[i] = vx;
+            out_vy[i] = vy;
             out_surprise[i] = new_surprise[i];
-            out_x[i] = (out_x[i] + new_vx[i]).clamp(0.0, width);
-            out_y[i] = (out_y[i] + new_vy[i]).clamp(0.0, height);
+            out_x[i] = x;
+            out_y[i] = y;
         }
     }
 
     /// Agents deposit pheromones based on their state.
-    /// High-surprise agents emit danger signals.
+    /// High-surprise agents emit danger signals proportional to surprise²,
+    /// so a single anomaly outweighs many mildly surprised agents.
     /// All agents leave trail markers along their path.
     fn deposit_agent_pheromones(&mut self) {
         let x = self.pool.x.as_slice();
         let y = self.pool.y.as_slice();
         let surprise = self.pool.surprise.as_slice();
 
-        // Sample a subset for pheromone deposit (1 in 100 agents)
+        // Sample a subset for pheromone deposit (1 in `deposit_stride` agents)
         // Full 100M deposits would overwhelm the pheromone field
-        let stride = 100.max(1);
+        let stride = self.deposit_stride.max(1);
         for i in (0..self.pool.n_agents).step_by(stride) {
             // Trail marker (channel 2)
-            self.pheromones.deposit(x[i], y[i], 2, 0.1);
+            self.pheromones.deposit(x[i], y[i], 2, self.trail_amount);
 
             // Danger signal if surprised (channel 1)
-            if surprise[i] > 0.5 {
-                self.pheromones.deposit(x[i], y[i], 1, surprise[i]);
+            if surprise[i] > self.danger_threshold {
+                self.pheromones.deposit(x[i], y[i], 1, surprise[i] * surprise[i]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Danger gradient magnitude one cell to the right of `(x, y)`
+    fn danger_gradient(engine: &SwarmEngineMaster, x: f32, y: f32) -> f32 {
+        let (gx, gy) = engine.pheromones.gradient(x + engine.pheromones.cell_size, y, 1);
+        (gx * gx + gy * gy).sqrt()
+    }
+
+    #[test]
+    fn test_region_queries_on_quadrant_cluster() {
+        let mut engine = SwarmEngineMaster::new(1000, 1000.0, 1000.0);
+        for i in 0..1000 {
+            // 200 agents clustered in the lower-left quadrant, the rest upper-right
+            let offset = if i < 200 { 100.0 } else { 600.0 };
+            engine.pool.x.as_mut_slice()[i] = offset + (i % 20) as f32 * 15.0;
+            engine.pool.y.as_mut_slice()[i] = offset + (i / 20 % 20) as f32 * 15.0;
+        }
+        engine.pool.surprise.as_mut_slice().fill(0.25);
+        engine.rebuild_grid();
+
+        assert_eq!(engine.count_in_region(0.0, 0.0, 500.0, 500.0), 200);
+        assert_eq!(engine.count_in_region(500.0, 500.0, 1000.0, 1000.0), 800);
+        assert_eq!(engine.count_in_region(500.0, 0.0, 1000.0, 500.0), 0);
+
+        let samples = engine.sample_region(500.0, 500.0, 0.0, 0.0, 50);
+        assert_eq!(samples.len(), 50);
+        assert!(samples
+            .iter()
+            .all(|&(x, y, s)| x < 500.0 && y < 500.0 && s == 0.25));
+
+        // Agents drift after the grid is built; the widened query still finds them
+        engine.tick();
+        assert_eq!(engine.count_in_region(0.0, 0.0, 500.0, 500.0), 200);
+    }
+
+    #[test]
+    fn test_region_queries_before_first_tick_and_unbounded() {
+        let engine = SwarmEngineMaster::new(500, 1000.0, 1000.0);
+        assert_eq!(engine.count_in_region(0.0, 0.0, 1000.0, 1000.0), 500);
+        assert_eq!(engine.count_in_region(-1e30, -1e30, 1e30, 1e30), 500);
+        assert_eq!(engine.count_in_region(2000.0, 2000.0, 3000.0, 3000.0), 0);
+    }
+
+    #[test]
+    fn test_agents_route_around_wall_toward_gap() {
+        // No random exploration, so the route depends only on the goal field
+        let flocking = FlockingParams {
+            exploration: 0.0,
+            ..FlockingParams::default()
+        };
+        let mut engine = SwarmEngineMaster::with_flocking(20, 1000.0, 1000.0, flocking);
+        // Vertical wall at x = 500..520 with a gap at y = 800..900; goal beyond it
+        engine.add_obstacle(500.0, 0.0, 520.0, 799.0);
+        engine.add_obstacle(500.0, 901.0, 520.0, 1000.0);
+        engine.set_goal(800.0, 500.0);
+
+        for i in 0..20 {
+            engine.pool.x.as_mut_slice()[i] = 480.0;
+            engine.pool.y.as_mut_slice()[i] = 200.0 + i as f32 * 12.0;
+        }
+        engine.pool.vx.as_mut_slice().fill(0.0);
+        engine.pool.vy.as_mut_slice().fill(0.0);
+        engine.pool.surprise.as_mut_slice().fill(0.0);
+        let start_y: f32 = engine.pool.y.as_slice().iter().sum::<f32>() / 20.0;
+
+        // The straight line to the goal points into the wall; the field points north
+        let (dx, dy) = engine.pheromones.goal_direction(499.5, 300.0);
+        assert!(dx <= 0.0 && dy > 0.9, "direction at wall face: ({}, {})", dx, dy);
+
+        for _ in 0..150 {
+            engine.tick();
+        }
+
+        let x = engine.pool.x.as_slice();
+        let y = engine.pool.y.as_slice();
+        assert!(x.iter().all(|&x| x < 520.0), "an agent went through the wall");
+        let end_y: f32 = y.iter().sum::<f32>() / 20.0;
+        assert!(
+            end_y > start_y + 100.0,
+            "agents did not head for the gap: mean y {} -> {}",
+            start_y,
+            end_y
+        );
+    }
+
+    #[test]
+    fn test_on_tick_fires_once_per_tick() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = SwarmEngineMaster::new(1000, 1000.0, 1000.0);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        engine.on_tick = Some(Box::new(move |pool: &MmapSwarmPool, tick: u64| {
+            recorder.lock().unwrap().push((tick, pool.n_agents));
+        }));
+
+        for _ in 0..3 {
+            engine.tick();
+        }
+        assert_eq!(*seen.lock().unwrap(), vec![(1, 1000), (2, 1000), (3, 1000)]);
+    }
+
+    #[test]
+    fn test_high_surprise_dominates_danger_field() {
+        let mut engine = SwarmEngineMaster::new(1000, 1000.0, 1000.0);
+        engine.deposit_stride = 1;
+        engine.pool.surprise.as_mut_slice().fill(0.0);
+        engine.pool.x.as_mut_slice().fill(50.0);
+        engine.pool.y.as_mut_slice().fill(50.0);
+
+        // One anomaly at (300, 500); fifty mildly surprised agents at (700, 500)
+        engine.pool.x.as_mut_slice()[0] = 300.0;
+        engine.pool.y.as_mut_slice()[0] = 500.0;
+        engine.pool.surprise.as_mut_slice()[0] = 10.0;
+        for i in 1..=50 {
+            engine.pool.x.as_mut_slice()[i] = 700.0;
+            engine.pool.y.as_mut_slice()[i] = 500.0;
+            engine.pool.surprise.as_mut_slice()[i] = 0.6;
+        }
+
+        engine.deposit_agent_pheromones();
+
+        let anomaly = danger_gradient(&engine, 300.0, 500.0);
+        let crowd = danger_gradient(&engine, 700.0, 500.0);
+        assert!(
+            anomaly > crowd,
+            "anomaly gradient {} should exceed crowd gradient {}",
+            anomaly,
+            crowd
+        );
+
+        // Below-threshold agents emit no danger at all
+        assert_eq!(engine.pheromones.sample(50.0, 50.0, 1), 0.0);
+        assert!(engine.pheromones.sample(50.0, 50.0, 2) > 0.0);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = std::env::temp_dir().join(format!("swarm-checkpoint-{}", std::process::id()));
+        let mut engine = SwarmEngineMaster::new(100_000, 1000.0, 1000.0);
+        engine.set_goal(900.0, 900.0);
+        for _ in 0..3 {
+            engine.tick();
+        }
+        engine.save(&dir).unwrap();
+        let x = engine.pool.x.as_slice().to_vec();
+        let y = engine.pool.y.as_slice().to_vec();
+        let trail = engine.pheromones.data.to_vec();
+        let goal = engine.pheromones.goal;
+        drop(engine);
+
+        let mut restored = SwarmEngineMaster::load(&dir).unwrap();
+        assert_eq!(restored.global_tick, 3);
+        assert_eq!(restored.pool.n_agents, 100_000);
+        assert_eq!(restored.pool.x.as_slice(), x.as_slice());
+        assert_eq!(restored.pool.y.as_slice(), y.as_slice());
+        assert_eq!(restored.pheromones.data.to_vec(), trail);
+        assert!(goal.is_some());
+        assert_eq!(restored.pheromones.goal, goal);
+
+        restored.tick();
+        assert_eq!(restored.global_tick, 4);
+
+        // Saving a loaded engine over its own checkpoint rewrites the mapped files
+        restored.save(&dir).unwrap();
+        assert_eq!(restored.pool.n_agents, 100_000);
+        let x = restored.pool.x.as_slice().to_vec();
+        let health = restored.pool.health.as_slice().to_vec();
+        drop(restored);
+
+        let reloaded = SwarmEngineMaster::load(&dir).unwrap();
+        assert_eq!(reloaded.global_tick, 4);
+        assert_eq!(reloaded.pool.x.as_slice(), x.as_slice());
+        assert_eq!(reloaded.pool.health.as_slice(), health.as_slice());
+        drop(reloaded);
+
+        // A truncated column is rejected rather than silently resized
+        std::fs::write(dir.join("x.bin"), [0u8; 16]).unwrap();
+        let err = SwarmEngineMaster::load(&dir).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_separation_weight_spreads_the_swarm() {
+        let spread = |separation: f32| {
+            let params = FlockingParams {
+                separation,
+                ..FlockingParams::default()
+            };
+            let mut engine = SwarmEngineMaster::with_flocking(500, 1000.0, 1000.0, params);
+            for i in 0..500 {
+                // 500 agents packed into a 25x20 block around (500, 500)
+                engine.pool.x.as_mut_slice()[i] = 490.0 + (i % 25) as f32 * 0.8;
+                engine.pool.y.as_mut_slice()[i] = 490.0 + (i / 25) as f32 * 0.8;
+            }
+            for _ in 0..10 {
+                engine.tick();
             }
+            let x = engine.pool.x.as_slice();
+            let y = engine.pool.y.as_slice();
+            let (cx, cy) = (x.iter().sum::<f32>() / 500.0, y.iter().sum::<f32>() / 500.0);
+            x.iter()
+                .zip(y)
+                .map(|(x, y)| ((x - cx).powi(2) + (y - cy).powi(2)).sqrt())
+                .sum::<f32>()
+                / 500.0
+        };
+
+        let tight = spread(0.0);
+        let loose = spread(0.5);
+        assert!(loose > tight + 2.0, "spread {} vs {}", loose, tight);
+    }
+
+    #[test]
+    fn test_surprise_stats_track_cascade() {
+        let mut engine = SwarmEngineMaster::new(4, 1000.0, 1000.0);
+        engine.pool.x.as_mut_slice().copy_from_slice(&[100.0, 103.0, 100.0, 900.0]);
+        engine.pool.y.as_mut_slice().copy_from_slice(&[100.0, 104.0, 110.0, 900.0]);
+        engine.pool.surprise.as_mut_slice().copy_from_slice(&[0.0, 0.5, 0.9, 0.05]);
+        let (count, mean_dist, peak, std_dev) = engine.surprise_stats((100.0, 100.0));
+        assert_eq!(count, 2);
+        assert!((mean_dist - 7.5).abs() < 1e-9);
+        assert_eq!(peak, 0.9);
+        assert!((std_dev - 12.5f64.sqrt()).abs() < 1e-9);
+        assert_eq!(engine.surprise_stats((0.0, 0.0)).0, 2);
+
+        // Inject a burst at the center of a dense swarm: the wave spreads
+        // outward while its peak decays
+        let mut engine = SwarmEngineMaster::new(100_000, 1000.0, 1000.0);
+        let origin = (500.0f32, 500.0f32);
+        {
+            let x = engine.pool.x.as_slice();
+            let y = engine.pool.y.as_slice();
+            let surprise = engine.pool.surprise.as_mut_slice();
+            for i in 0..x.len() {
+                let (dx, dy) = (x[i] - origin.0, y[i] - origin.1);
+                if dx * dx + dy * dy < 50.0 * 50.0 {
+                    surprise[i] = 1.0;
+                }
+            }
+        }
+        let (count0, dist0, peak0, _) = engine.surprise_stats(origin);
+        assert!(count0 > 0);
+        assert_eq!(peak0, 1.0);
+
+        engine.tick();
+        let (count1, dist1, peak1, std1) = engine.surprise_stats(origin);
+        assert!(count1 > count0);
+        assert!(dist1 > dist0);
+        assert!(peak1 < peak0 && peak1 > SURPRISED_THRESHOLD);
+        assert!(std1 > 0.0);
+    }
+
+    #[test]
+    fn test_perception_radius_must_be_positive() {
+        let mut engine = SwarmEngineMaster::new(10, 100.0, 100.0);
+        for radius in [0.0, -5.0, f32::NAN, f32::INFINITY] {
+            assert!(engine.set_perception_radius(radius).is_err());
         }
+        assert!(engine.set_perception_radius(25.0).is_ok());
+        assert_eq!(engine.perception_radius, 25.0);
     }
 }
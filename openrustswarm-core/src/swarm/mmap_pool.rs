@@ -5,9 +5,12 @@
 //! on machines with 16GB RAM. The kernel's virtual memory subsystem handles
 //! the pressure — we never allocate 37GB of physical RAM.
 
-use memmap2::MmapMut;
+use memmap2::{MmapMut, MmapOptions};
 use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, Write};
 use std::mem;
+use std::path::Path;
 
 /// A single memory-mapped array of typed elements.
 ///
@@ -71,6 +74,58 @@ impl<T: Copy + Default + Send + Sync> MmapArray<T> {
     pub fn len(&self) -> usize {
         self.len
     }
+
+    /// Write the raw element bytes (native endianness) to `path`.
+    ///
+    /// The bytes go to a temporary file that is then renamed over `path`, so
+    /// an array mapped from `path` by `map_file` keeps reading the old file
+    /// instead of seeing it truncated mid-write.
+    pub fn write_to(&self, path: &Path) -> io::Result<()> {
+        let byte_len = self.len * mem::size_of::<T>();
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = Path::new(&tmp_name);
+        let mut file = File::create(tmp_path)?;
+        file.write_all(&self.mmap[..byte_len])?;
+        file.sync_all()?;
+        std::fs::rename(tmp_path, path)
+    }
+
+    /// Map a file written by `write_to` as a private copy-on-write region:
+    /// pages are read lazily from the file and writes never reach it.
+    /// Saving back to the same path is safe; see `write_to`.
+    /// Fails with `InvalidData` unless the file holds exactly `len` elements.
+    pub fn map_file(path: &Path, len: usize) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let byte_len = len * mem::size_of::<T>();
+        let file_len = file.metadata()?.len();
+        if file_len != byte_len as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} holds {} bytes, expected {} ({} elements)",
+                    path.display(),
+                    file_len,
+                    byte_len,
+                    len
+                ),
+            ));
+        }
+        if len == 0 {
+            return Ok(Self::new(0));
+        }
+
+        // SAFETY: a private mapping is only undefined behaviour if the file is
+        // truncated or rewritten in place while mapped. `write_to` replaces
+        // files by rename, which leaves the mapped inode intact; other writers
+        // must not modify checkpoint files that are loaded.
+        let mmap = unsafe { MmapOptions::new().len(byte_len).map_copy(&file)? };
+        Ok(Self {
+            mmap,
+            len,
+            _marker: std::marker::PhantomData,
+        })
+    }
 }
 
 // Safety: MmapMut is a contiguous byte region. We only expose it through
@@ -193,6 +248,32 @@ impl MmapSwarmPool {
         self.cell_index = new_cell;
     }
 
+    /// Write every column to `dir` as `<column>.bin`.
+    pub fn save_columns(&self, dir: &Path) -> io::Result<()> {
+        self.x.write_to(&dir.join("x.bin"))?;
+        self.y.write_to(&dir.join("y.bin"))?;
+        self.vx.write_to(&dir.join("vx.bin"))?;
+        self.vy.write_to(&dir.join("vy.bin"))?;
+        self.surprise.write_to(&dir.join("surprise.bin"))?;
+        self.health.write_to(&dir.join("health.bin"))?;
+        self.cell_index.write_to(&dir.join("cell_index.bin"))
+    }
+
+    /// Map the columns written by `save_columns`, checking each holds
+    /// exactly `n_agents` elements.
+    pub fn load_columns(dir: &Path, n_agents: usize) -> io::Result<Self> {
+        Ok(Self {
+            n_agents,
+            x: MmapArray::map_file(&dir.join("x.bin"), n_agents)?,
+            y: MmapArray::map_file(&dir.join("y.bin"), n_agents)?,
+            vx: MmapArray::map_file(&dir.join("vx.bin"), n_agents)?,
+            vy: MmapArray::map_file(&dir.join("vy.bin"), n_agents)?,
+            surprise: MmapArray::map_file(&dir.join("surprise.bin"), n_agents)?,
+            health: MmapArray::map_file(&dir.join("health.bin"), n_agents)?,
+            cell_index: MmapArray::map_file(&dir.join("cell_index.bin"), n_agents)?,
+        })
+    }
+
     /// Report approximate physical memory usage in MB.
     pub fn estimated_virtual_mb(&self) -> f64 {
         let bytes_per_agent = 7 * mem::size_of::<f32>(); // 6 f32 + 1 u32
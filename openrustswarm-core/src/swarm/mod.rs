@@ -21,10 +21,13 @@ use pyo3::prelude::*;
 
 pub use promoter::PromotionLogic;
 pub use spatial::GridMap;
-pub use tensor_engine::TensorSwarm;
+pub use tensor_engine::{SwarmMetrics, TensorSwarm};
 pub use lod::{DormantAgent, SimplifiedPool, ProductionTensorSwarm};
 
 /// Configuration for the Swarm Engine
+///
+/// The constructor takes the sizing fields; the behaviour fields start at their
+/// defaults and are set as attributes (or with struct-update syntax in Rust).
 #[derive(Clone, Debug)]
 #[pyclass]
 pub struct SwarmConfig {
@@ -36,6 +39,22 @@ pub struct SwarmConfig {
     pub world_height: usize,
     #[pyo3(get, set)]
     pub max_threads: usize,
+    /// Agents whose health drops below this value die (0 = immortal, the default)
+    #[pyo3(get, set)]
+    pub death_threshold: f32,
+    /// Respawn dead agents at a random position with full health
+    #[pyo3(get, set)]
+    pub respawn: bool,
+    /// Half-width of the square around a village or city in which agents harvest or trade
+    #[pyo3(get, set)]
+    pub location_radius: f32,
+    /// Per-tick pull of workers toward their nearest village (0 = unbiased random walk)
+    #[pyo3(get, set)]
+    pub foraging_drive: f32,
+    /// Most heavy (LLM) agents active at once; the least recently promoted
+    /// is retired to make room (0 = unlimited)
+    #[pyo3(get, set)]
+    pub max_heavy_agents: usize,
 }
 
 #[pymethods]
@@ -53,6 +72,11 @@ impl SwarmConfig {
             world_width,
             world_height,
             max_threads,
+            death_threshold: 0.0,
+            respawn: false,
+            location_radius: 5.0,
+            foraging_drive: 0.0,
+            max_heavy_agents: 64,
         }
     }
 }
@@ -1,4 +1,17 @@
+use std::collections::VecDeque;
 use std::f32;
+use tracing::warn;
+
+/// Channel holding the obstacle-aware goal attraction field
+pub const GOAL_CHANNEL: usize = 6;
+
+/// Largest diffusion coefficient the explicit 5-point stencil in `tick` can
+/// take without blowing up (CFL condition `d <= 1/4`). `tick` works in grid
+/// units, so the bound holds for every `cell_size`.
+pub const MAX_STABLE_DIFFUSION: f32 = 0.25;
+
+/// Smallest grid side: bilinear sampling reads a 2x2 cell neighborhood
+const MIN_GRID_SIDE: usize = 2;
 
 /// Multi-channel pheromone field.
 /// Each channel represents a different "chemical" for emergent stigmergic coordination.
@@ -8,19 +21,66 @@ use std::f32;
 /// CH_3: Hoarding Suppressor
 /// CH_4: Novelty Beacon
 /// CH_5: Alliance Signal
+/// CH_6: Goal Attraction (BFS wavefront, static between goal/obstacle changes)
 pub struct PheromoneField {
     pub data: Box<[f32]>,
     pub channels: usize,
     pub width: usize,
     pub height: usize,
     pub cell_size: f32,
-    pub decay_rates: [f32; 6],
-    pub diffusion: [f32; 6],
+    pub decay_rates: [f32; 7],
+    pub diffusion: [f32; 7],
+    /// Per-channel gradient sampling distance, as a multiple of `cell_size`
+    pub sense_radius: Vec<f32>,
+    /// Impassable cells for the goal wavefront
+    pub obstacles: Vec<bool>,
+    /// Goal cell, if one is set
+    pub goal: Option<(usize, usize)>,
+    /// Per-channel saturation cap enforced after `deposit` and `tick`
+    /// (None leaves values unbounded)
+    pub max_per_cell: Option<[f32; 7]>,
+}
+
+/// Clamp a diffusion coefficient into the stable range, warning when it changes
+fn stable_diffusion(d: f32) -> f32 {
+    let clamped = if d.is_nan() { 0.0 } else { d.clamp(0.0, MAX_STABLE_DIFFUSION) };
+    if clamped != d {
+        warn!(
+            "[Pheromone] Diffusion {} violates the stability bound, clamped to {}",
+            d, clamped
+        );
+    }
+    clamped
 }
 
 impl PheromoneField {
     pub fn new(width: usize, height: usize, cell_size: f32) -> Self {
-        let channels = 6;
+        // The goal channel neither decays nor diffuses; it is recomputed on change.
+        // Danger spreads fastest, at the stability limit.
+        Self::with_diffusion(width, height, cell_size, [0.1, 0.25, 0.05, 0.2, 0.25, 0.1, 0.0])
+    }
+
+    /// Like `new`, but caps each channel's per-cell value at `max_per_cell`
+    /// so heavy deposits cannot swamp the gradients of other signals.
+    pub fn with_caps(width: usize, height: usize, cell_size: f32, max_per_cell: [f32; 7]) -> Self {
+        let mut field = Self::new(width, height, cell_size);
+        field.max_per_cell = Some(max_per_cell);
+        field
+    }
+
+    /// Like `new`, with custom per-channel diffusion coefficients. Values that
+    /// would make `tick` unstable are clamped to `[0, MAX_STABLE_DIFFUSION]`
+    /// with a warning, as are grid sides below two cells.
+    pub fn with_diffusion(width: usize, height: usize, cell_size: f32, diffusion: [f32; 7]) -> Self {
+        if width < MIN_GRID_SIDE || height < MIN_GRID_SIDE {
+            warn!(
+                "[Pheromone] Grid {}x{} is too small for bilinear sampling, raised to at least {}x{}",
+                width, height, MIN_GRID_SIDE, MIN_GRID_SIDE
+            );
+        }
+        let width = width.max(MIN_GRID_SIDE);
+        let height = height.max(MIN_GRID_SIDE);
+        let channels = 7;
         let total_cells = channels * width * height;
         Self {
             data: vec![0.0; total_cells].into_boxed_slice(),
@@ -28,8 +88,12 @@ impl PheromoneField {
             width,
             height,
             cell_size,
-            decay_rates: [0.005, 0.02, 0.003, 0.01, 0.015, 0.008],
-            diffusion: [0.1, 0.3, 0.05, 0.2, 0.25, 0.1],
+            decay_rates: [0.005, 0.02, 0.003, 0.01, 0.015, 0.008, 0.0],
+            diffusion: diffusion.map(stable_diffusion),
+            sense_radius: vec![1.0; channels],
+            obstacles: vec![false; width * height],
+            goal: None,
+            max_per_cell: None,
         }
     }
 
@@ -51,10 +115,21 @@ impl PheromoneField {
         let ch_off = channel * w * self.height;
 
         // Bilinear splatting across 4 cells
-        self.data[ch_off + cy * w + cx] += amount * (1.0 - fx) * (1.0 - fy);
-        self.data[ch_off + cy * w + (cx + 1)] += amount * fx * (1.0 - fy);
-        self.data[ch_off + (cy + 1) * w + cx] += amount * (1.0 - fx) * fy;
-        self.data[ch_off + (cy + 1) * w + (cx + 1)] += amount * fx * fy;
+        let cap = self.cap(channel);
+        let splats = [
+            (ch_off + cy * w + cx, (1.0 - fx) * (1.0 - fy)),
+            (ch_off + cy * w + (cx + 1), fx * (1.0 - fy)),
+            (ch_off + (cy + 1) * w + cx, (1.0 - fx) * fy),
+            (ch_off + (cy + 1) * w + (cx + 1), fx * fy),
+        ];
+        for (idx, weight) in splats {
+            self.data[idx] = (self.data[idx] + amount * weight).min(cap);
+        }
+    }
+
+    /// Saturation cap for `channel` (infinite when uncapped)
+    fn cap(&self, channel: usize) -> f32 {
+        self.max_per_cell.map_or(f32::INFINITY, |caps| caps[channel])
     }
 
     pub fn sample(&self, x: f32, y: f32, channel: usize) -> f32 {
@@ -65,9 +140,12 @@ impl PheromoneField {
         self.data[ch_off + cy * self.width + cx]
     }
 
-    /// Retrieve the gradient (dx, dy) to steer agents based on pheromone density
+    /// Retrieve the gradient (dx, dy) to steer agents based on pheromone density.
+    /// Samples `sense_radius[channel]` cells away, so long-range chemicals are
+    /// perceived over a wider, smoother neighborhood.
     pub fn gradient(&self, x: f32, y: f32, channel: usize) -> (f32, f32) {
-        let eps = self.cell_size;
+        let radius = self.sense_radius.get(channel).copied().unwrap_or(1.0).max(1.0);
+        let eps = self.cell_size * radius;
         let cx = self.sample(x + eps, y, channel) - self.sample(x - eps, y, channel);
         let cy = self.sample(x, y + eps, channel) - self.sample(x, y - eps, channel);
         (cx / (2.0 * eps), cy / (2.0 * eps))
@@ -83,8 +161,10 @@ impl PheromoneField {
 
         for ch in 0..self.channels {
             let rate = self.decay_rates[ch];
-            let d = self.diffusion[ch];
+            // Fields are public, so guard against unstable values set after construction
+            let d = self.diffusion[ch].clamp(0.0, MAX_STABLE_DIFFUSION);
             let off = ch * w * h;
+            let cap = self.cap(ch);
 
             for i in 1..h-1 {
                 for j in 1..w-1 {
@@ -97,12 +177,224 @@ impl PheromoneField {
                                   + self.data[off + i * w + (j + 1)]
                                   - 4.0 * self.data[idx];
                                   
-                    // Diffusion + Decay
-                    next_data[idx] = (self.data[idx] + d * laplacian) * (1.0 - rate);
+                    // Diffusion + Decay, then saturation
+                    next_data[idx] = ((self.data[idx] + d * laplacian) * (1.0 - rate)).min(cap);
                 }
             }
         }
         
         self.data = next_data;
     }
+
+    /// True if any cell holds NaN or ±inf, i.e. the field has been corrupted
+    pub fn has_nan(&self) -> bool {
+        self.data.iter().any(|v| !v.is_finite())
+    }
+
+    /// Nearest grid cell for a world position, clamped to the field.
+    fn cell_of(&self, x: f32, y: f32) -> (usize, usize) {
+        let cx = (x / self.cell_size).clamp(0.0, (self.width - 1) as f32) as usize;
+        let cy = (y / self.cell_size).clamp(0.0, (self.height - 1) as f32) as usize;
+        (cx, cy)
+    }
+
+    /// Set the navigation goal and rebuild the goal channel.
+    pub fn set_goal(&mut self, x: f32, y: f32) {
+        self.goal = Some(self.cell_of(x, y));
+        self.recompute_goal_field();
+    }
+
+    /// Remove the goal; the goal channel is cleared.
+    pub fn clear_goal(&mut self) {
+        self.goal = None;
+        self.recompute_goal_field();
+    }
+
+    /// Mark (or unmark) the rectangle spanned by (x0, y0) and (x1, y1) as
+    /// impassable, then rebuild the goal channel.
+    pub fn set_obstacle_rect(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, blocked: bool) {
+        let (cx0, cy0) = self.cell_of(x0.min(x1), y0.min(y1));
+        let (cx1, cy1) = self.cell_of(x0.max(x1), y0.max(y1));
+        for cy in cy0..=cy1 {
+            for cx in cx0..=cx1 {
+                self.obstacles[cy * self.width + cx] = blocked;
+            }
+        }
+        self.recompute_goal_field();
+    }
+
+    /// True if the cell containing (x, y) is an obstacle.
+    pub fn is_obstacle(&self, x: f32, y: f32) -> bool {
+        let (cx, cy) = self.cell_of(x, y);
+        self.obstacles[cy * self.width + cx]
+    }
+
+    /// 4-connected BFS from the goal over free cells. Each reachable cell stores
+    /// `max_distance + 1 - distance`, so values rise toward the goal; obstacles
+    /// and unreachable cells stay 0.
+    fn recompute_goal_field(&mut self) {
+        let w = self.width;
+        let h = self.height;
+        let off = GOAL_CHANNEL * w * h;
+        let goal_channel = &mut self.data[off..off + w * h];
+        goal_channel.iter_mut().for_each(|v| *v = 0.0);
+
+        let (gx, gy) = match self.goal {
+            Some(g) if !self.obstacles[g.1 * w + g.0] => g,
+            _ => return,
+        };
+
+        let mut dist = vec![u32::MAX; w * h];
+        let mut queue = VecDeque::new();
+        dist[gy * w + gx] = 0;
+        queue.push_back((gx, gy));
+        let mut max_dist = 0;
+
+        while let Some((cx, cy)) = queue.pop_front() {
+            let d = dist[cy * w + cx];
+            max_dist = max_dist.max(d);
+            let neighbors = [
+                (cx.wrapping_sub(1), cy),
+                (cx + 1, cy),
+                (cx, cy.wrapping_sub(1)),
+                (cx, cy + 1),
+            ];
+            for (nx, ny) in neighbors {
+                if nx >= w || ny >= h {
+                    continue;
+                }
+                let idx = ny * w + nx;
+                if !self.obstacles[idx] && dist[idx] == u32::MAX {
+                    dist[idx] = d + 1;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        for (value, d) in goal_channel.iter_mut().zip(dist) {
+            if d != u32::MAX {
+                *value = (max_dist + 1 - d) as f32;
+            }
+        }
+    }
+
+    /// Unit steering direction up the goal channel at (x, y).
+    ///
+    /// Only free neighbor cells closer to the goal contribute, so agents slide
+    /// along walls toward openings instead of pushing into them. An agent that
+    /// has drifted inside an obstacle is steered back to the nearest free cell.
+    pub fn goal_direction(&self, x: f32, y: f32) -> (f32, f32) {
+        if self.goal.is_none() {
+            return (0.0, 0.0);
+        }
+        let w = self.width;
+        let off = GOAL_CHANNEL * w * self.height;
+        let (cx, cy) = self.cell_of(x, y);
+        let here = self.data[off + cy * w + cx];
+
+        let mut dx = 0.0f32;
+        let mut dy = 0.0f32;
+        let neighbors = [
+            (cx.wrapping_sub(1), cy, -1.0, 0.0),
+            (cx + 1, cy, 1.0, 0.0),
+            (cx, cy.wrapping_sub(1), 0.0, -1.0),
+            (cx, cy + 1, 0.0, 1.0),
+        ];
+        for (nx, ny, ux, uy) in neighbors {
+            if nx >= w || ny >= self.height {
+                continue;
+            }
+            let idx = ny * w + nx;
+            if !self.obstacles[idx] && self.data[off + idx] > here {
+                dx += ux;
+                dy += uy;
+            }
+        }
+
+        let mag = (dx * dx + dy * dy).sqrt();
+        if mag == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (dx / mag, dy / mag)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degenerate_grid_is_raised_to_minimum() {
+        let mut field = PheromoneField::new(0, 0, 10.0);
+        assert_eq!((field.width, field.height), (2, 2));
+        field.deposit(5.0, 5.0, 0, 1.0);
+        field.set_obstacle_rect(0.0, 0.0, 5.0, 5.0, true);
+        field.set_goal(15.0, 15.0);
+        field.tick();
+        assert!(field.sample(5.0, 5.0, 0).is_finite());
+        assert!(!field.has_nan());
+    }
+
+    #[test]
+    fn test_unstable_diffusion_is_clamped_and_stays_finite() {
+        let mut field = PheromoneField::with_diffusion(32, 32, 10.0, [1.0; 7]);
+        assert!(field.diffusion.iter().all(|d| *d == MAX_STABLE_DIFFUSION));
+
+        field.deposit(160.0, 160.0, 0, 1000.0);
+        for _ in 0..100 {
+            field.tick();
+        }
+        assert!(!field.has_nan());
+
+        // Coefficients raised after construction are still clamped in tick
+        field.diffusion[1] = 5.0;
+        field.deposit(160.0, 160.0, 1, 1000.0);
+        for _ in 0..100 {
+            field.tick();
+        }
+        assert!(!field.has_nan());
+
+        field.data[0] = f32::NAN;
+        assert!(field.has_nan());
+    }
+
+    #[test]
+    fn test_sense_radius_extends_gradient_range() {
+        let mut field = PheromoneField::new(100, 100, 1.0);
+        field.deposit(50.0, 50.0, 5, 10.0);
+
+        // The default one-cell stencil is sharp next to the deposit but blind five cells away
+        assert_eq!(field.gradient(51.0, 50.0, 5), (-5.0, 0.0));
+        assert_eq!(field.gradient(55.0, 50.0, 5), (0.0, 0.0));
+
+        // A five-cell stencil still senses it there, with a gentler slope
+        field.sense_radius[5] = 5.0;
+        assert_eq!(field.gradient(55.0, 50.0, 5), (-1.0, 0.0));
+
+        // Other channels keep the default range
+        field.deposit(50.0, 50.0, 0, 10.0);
+        assert_eq!(field.gradient(55.0, 50.0, 0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_repeated_deposits_saturate_at_cap() {
+        let mut caps = [f32::INFINITY; 7];
+        caps[0] = 5.0;
+        let mut field = PheromoneField::with_caps(32, 32, 1.0, caps);
+
+        for _ in 0..100 {
+            field.deposit(10.0, 10.0, 0, 1.0);
+            field.deposit(10.0, 10.0, 2, 1.0);
+        }
+        assert_eq!(field.sample(10.0, 10.0, 0), 5.0);
+        // Uncapped channels keep accumulating
+        assert_eq!(field.sample(10.0, 10.0, 2), 100.0);
+
+        for _ in 0..10 {
+            field.deposit(10.0, 10.0, 0, 50.0);
+            field.tick();
+            assert!(field.sample(10.0, 10.0, 0) <= 5.0);
+        }
+    }
 }
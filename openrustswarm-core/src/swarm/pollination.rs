@@ -39,13 +39,18 @@ pub struct PollinatorState {
     /// TD Future Discount factor (Gamma)
     #[pyo3(get, set)]
     pub gamma: f32,
+
+    /// Lower bound on the effective share probability, so an agent whose
+    /// eagerness collapsed still broadcasts now and then and can earn it back
+    #[pyo3(get, set)]
+    pub min_share_floor: f32,
 }
 
 #[pymethods]
 impl PollinatorState {
     #[new]
-    #[pyo3(signature = (recency_window=10, surprise_broadcast_weight=0.5, sigmoid_temperature=1.0, alpha=0.1, gamma=0.9))]
-    pub fn new(recency_window: u64, surprise_broadcast_weight: f32, sigmoid_temperature: f32, alpha: f32, gamma: f32) -> Self {
+    #[pyo3(signature = (recency_window=10, surprise_broadcast_weight=0.5, sigmoid_temperature=1.0, alpha=0.1, gamma=0.9, min_share_floor=0.01))]
+    pub fn new(recency_window: u64, surprise_broadcast_weight: f32, sigmoid_temperature: f32, alpha: f32, gamma: f32, min_share_floor: f32) -> Self {
         let mut state = PollinatorState {
             raw_eagerness: 0.0, // Neutral start
             share_probability: 0.5,
@@ -55,6 +60,7 @@ impl PollinatorState {
             sigmoid_temperature,
             alpha,
             gamma,
+            min_share_floor: min_share_floor.clamp(0.0, 1.0),
         };
         state.update_probability(0.0);
         state
@@ -63,11 +69,14 @@ impl PollinatorState {
     /// Evaluates if this agent wants to share context based on its bounded probability.
     /// Incorporates the current state's `surprise_score` to dynamically dilate or suppress 
     /// the agent's baseline eagerness based on how locally volatile the environment is.
+    /// The result never drops below `min_share_floor`.
     pub fn should_pollinate(&self, random_val: f32, current_surprise: f32) -> bool {
         // High surprise (anomaly) temporarily boosts the willingness to broadcast context
         // This prevents the RL loop from suppressing emergency information flow
         // even if the agent normally leans "selfish" (low raw_eagerness)
-        let effective_probability = (self.share_probability + (current_surprise * self.surprise_broadcast_weight)).min(1.0);
+        let effective_probability = (self.share_probability + (current_surprise * self.surprise_broadcast_weight))
+            .max(self.min_share_floor)
+            .min(1.0);
         random_val < effective_probability
     }
 
@@ -79,23 +88,8 @@ impl PollinatorState {
     /// Temporal Difference (TD) RL Reward Signal Hook
     /// Computes strictly isolated expected value updates (V) mitigating spam signals.
     pub fn apply_feedback(&mut self, target_agent_id: u32, reward_delta: f32, current_step: u64) {
-        if let Some(share_time) = self.active_shares.get(&target_agent_id) {
-            // Only claim credit if the share was recently enough
-            if current_step.saturating_sub(*share_time) <= self.recency_window {
-                
-                let current_v = self.raw_eagerness;
-                // Single-state loop approximation: next state expected value mirrors the updated baseline
-                let next_v = current_v; 
-
-                // V(s) <- V(s) + alpha * [R + gamma*V(s') - V(s)]
-                let td_error = reward_delta + (self.gamma * next_v) - current_v;
-                self.raw_eagerness += self.alpha * td_error;
-
-                self.update_probability(0.0);
-            }
-            
-            // Clear tracking after feedback resolved
-            self.active_shares.remove(&target_agent_id);
+        if self.td_update(target_agent_id, reward_delta, current_step) {
+            self.update_probability(0.0);
         }
     }
 
@@ -110,3 +104,82 @@ impl PollinatorState {
         self.share_probability = 1.0 / (1.0 + (-self.raw_eagerness / self.sigmoid_temperature).exp());
     }
 }
+
+impl PollinatorState {
+    /// Apply every `(target_agent_id, reward_delta)` pair like `apply_feedback`,
+    /// but recompute `share_probability` once at the end instead of per update
+    pub fn apply_feedback_batch(&mut self, feedbacks: &[(u32, f32)], current_step: u64) {
+        let mut updated = false;
+        for &(target_agent_id, reward_delta) in feedbacks {
+            updated |= self.td_update(target_agent_id, reward_delta, current_step);
+        }
+        if updated {
+            self.update_probability(0.0);
+        }
+    }
+
+    /// TD(0) update of `raw_eagerness` for one share, without touching
+    /// `share_probability`. Returns true if the eagerness changed.
+    fn td_update(&mut self, target_agent_id: u32, reward_delta: f32, current_step: u64) -> bool {
+        let Some(share_time) = self.active_shares.remove(&target_agent_id) else {
+            return false;
+        };
+        // Only claim credit if the share was recently enough
+        if current_step.saturating_sub(share_time) > self.recency_window {
+            return false;
+        }
+
+        let current_v = self.raw_eagerness;
+        // Single-state loop approximation: next state expected value mirrors the updated baseline
+        let next_v = current_v;
+
+        // V(s) <- V(s) + alpha * [R + gamma*V(s') - V(s)]
+        let td_error = reward_delta + (self.gamma * next_v) - current_v;
+        self.raw_eagerness += self.alpha * td_error;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silenced_agent_still_explores() {
+        let mut state = PollinatorState::new(10, 0.5, 1.0, 0.1, 0.9, 0.05);
+        state.raw_eagerness = -50.0;
+        state.update_probability(0.0);
+        assert!(state.share_probability < 1e-6);
+
+        let broadcasts = (0..1000)
+            .filter(|i| state.should_pollinate(*i as f32 / 1000.0, 0.0))
+            .count();
+        assert_eq!(broadcasts, 50);
+
+        // sigmoid(-50) is tiny but positive, so only a roll of exactly 0 still passes
+        state.min_share_floor = 0.0;
+        assert!(!(1..1000).any(|i| state.should_pollinate(i as f32 / 1000.0, 0.0)));
+    }
+
+    #[test]
+    fn batch_feedback_matches_sequential() {
+        let mut sequential = PollinatorState::new(10, 0.5, 1.0, 0.1, 0.9, 0.01);
+        for id in 0..6 {
+            sequential.register_share(id, 100);
+        }
+        // Broker 5 shared too long ago to earn credit; broker 9 never shared
+        sequential.register_share(5, 80);
+        let mut batched = sequential.clone();
+
+        let feedbacks = [(0, 1.0), (1, -0.5), (2, 0.25), (3, 2.0), (4, -1.5), (5, 3.0), (9, 4.0)];
+        for &(id, reward) in &feedbacks {
+            sequential.apply_feedback(id, reward, 105);
+        }
+        batched.apply_feedback_batch(&feedbacks, 105);
+
+        assert!((sequential.raw_eagerness - batched.raw_eagerness).abs() < 1e-6);
+        assert!((sequential.share_probability - batched.share_probability).abs() < 1e-6);
+        assert!(batched.active_shares_keys().is_empty());
+        assert!(batched.share_probability != 0.5);
+    }
+}
@@ -1,4 +1,5 @@
-use super::master_pipeline::SwarmEngineMaster;
+use super::master_pipeline::{SwarmEngineMaster, TickHook};
+use super::mmap_pool::MmapSwarmPool;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
@@ -24,11 +25,63 @@ impl PySwarmEngine {
 
     /// Inject pheromones into the stigmergic field.
     /// Channel 0: Resources, Channel 1: Danger, Channel 2: Trail,
-    /// Channel 3: Hoarding Suppressor, Channel 4: Novelty, Channel 5: Alliance
+    /// Channel 3: Hoarding Suppressor, Channel 4: Novelty, Channel 5: Alliance,
+    /// Channel 6: Goal (managed by `set_goal`)
     pub fn deposit_pheromone(&mut self, x: f32, y: f32, channel: usize, amount: f32) {
         self.engine.pheromones.deposit(x, y, channel, amount);
     }
 
+    /// Set the navigation goal agents steer toward, routing around obstacles.
+    pub fn set_goal(&mut self, x: f32, y: f32) {
+        self.engine.set_goal(x, y);
+    }
+
+    /// Add an impassable wall covering the rectangle spanned by (x0, y0) and (x1, y1).
+    pub fn add_obstacle(&mut self, x0: f32, y0: f32, x1: f32, y1: f32) {
+        self.engine.add_obstacle(x0, y0, x1, y1);
+    }
+
+    /// Count agents inside the rectangle spanned by (x0, y0) and (x1, y1).
+    pub fn count_in_region(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> u64 {
+        self.engine.count_in_region(x0, y0, x1, y1)
+    }
+
+    /// Up to `max` `(x, y, surprise)` tuples for agents inside the rectangle.
+    #[pyo3(signature = (x0, y0, x1, y1, max=10_000))]
+    pub fn sample_region(
+        &self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        max: usize,
+    ) -> Vec<(f32, f32, f32)> {
+        self.engine.sample_region(x0, y0, x1, y1, max)
+    }
+
+    /// Register `callback(positions, tick)` to run at the end of every tick, or
+    /// pass `None` to remove it. `positions` is a list of `(x, y)` tuples for
+    /// every `sample_every`-th agent, so large swarms are never copied in full.
+    #[pyo3(signature = (callback, sample_every=1000))]
+    pub fn set_on_tick(&mut self, callback: Option<PyObject>, sample_every: usize) {
+        let stride = sample_every.max(1);
+        self.engine.on_tick = callback.map(|callback| {
+            Box::new(move |pool: &MmapSwarmPool, tick: u64| {
+                let x = pool.x.as_slice();
+                let y = pool.y.as_slice();
+                let positions: Vec<(f32, f32)> = (0..pool.n_agents)
+                    .step_by(stride)
+                    .map(|i| (x[i], y[i]))
+                    .collect();
+                Python::with_gil(|py| {
+                    if let Err(e) = callback.call1(py, (positions, tick)) {
+                        e.print(py);
+                    }
+                });
+            }) as TickHook
+        });
+    }
+
     /// Extract macro-state metrics for analysis.
     pub fn get_macro_state(&self) -> PyObject {
         Python::with_gil(|py| {
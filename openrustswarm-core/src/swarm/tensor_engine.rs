@@ -7,9 +7,101 @@ use super::SwarmConfig;
 use crate::swarm::pollination::PollinatorState;
 use crate::worldmodel::{LatentState, WorldModelConfig};
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyBytes, PyDict};
 use rayon::prelude::*;
-use tracing::info;
+use std::collections::VecDeque;
+use tracing::{info, warn};
+
+/// Role code for harvesting/trading agents
+pub const ROLE_WORKER: u8 = 0;
+/// Role code for exploring agents
+pub const ROLE_SCOUT: u8 = 1;
+/// Sentinel role for dead agents (skipped in physics)
+pub const ROLE_DEAD: u8 = u8::MAX;
+
+/// Registered locations as (villages, towns, cities, ambush_zones)
+pub type Locations = (
+    Vec<(f32, f32)>,
+    Vec<(f32, f32)>,
+    Vec<(f32, f32)>,
+    Vec<(f32, f32)>,
+);
+
+/// Brownian step scale per role (scouts roam three times as far)
+fn role_step_scale(role: u8) -> f32 {
+    match role {
+        ROLE_SCOUT => 6.0,
+        _ => 2.0,
+    }
+}
+
+/// Unit vector from (x, y) toward the closest of `targets`, or zero if there
+/// are none or the agent is already on top of one
+fn toward_nearest(x: f32, y: f32, targets: &[(f32, f32)]) -> (f32, f32) {
+    let nearest = targets
+        .iter()
+        .map(|&(tx, ty)| (tx - x, ty - y))
+        .min_by(|a, b| (a.0 * a.0 + a.1 * a.1).total_cmp(&(b.0 * b.0 + b.1 * b.1)));
+    match nearest {
+        Some((dx, dy)) => {
+            let dist = (dx * dx + dy * dy).sqrt();
+            if dist > 0.0 {
+                (dx / dist, dy / dist)
+            } else {
+                (0.0, 0.0)
+            }
+        }
+        None => (0.0, 0.0),
+    }
+}
+
+/// Surprise broadcast weight per role (scouts are eager to report anomalies)
+fn role_broadcast_weight(role: u8) -> f32 {
+    match role {
+        ROLE_SCOUT => 0.9,
+        _ => 0.6,
+    }
+}
+
+/// Why an agent was queued for promotion to a heavy (LLM) agent
+#[pyclass]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PromotionReason {
+    /// A city sale escalated into a negotiation; magnitude is the resources
+    /// the agent held going into the sale
+    ComplexTrade,
+}
+
+#[pymethods]
+impl PromotionReason {
+    pub fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Typed population snapshot returned by `TensorSwarm::metrics`
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwarmMetrics {
+    /// Mean surprise score across living agents
+    #[pyo3(get)]
+    pub mean_surprise: f32,
+    /// Heavy agents currently active (at most `max_heavy_agents`)
+    #[pyo3(get)]
+    pub active_heavy_agents: usize,
+    /// Total heavy agents spawned by promotion
+    #[pyo3(get)]
+    pub total_heavy_spawned: usize,
+    /// Agents not marked `ROLE_DEAD`
+    #[pyo3(get)]
+    pub alive_count: usize,
+    /// Mean health across living agents
+    #[pyo3(get)]
+    pub mean_health: f32,
+    /// Agents that broadcast surprise on the last tick
+    #[pyo3(get)]
+    pub broadcaster_count: usize,
+}
 
 /// Massive Swarm using SoA (Tensor) layout
 #[pyclass]
@@ -45,11 +137,28 @@ pub struct TensorSwarm {
     ambush_zones: Vec<(f32, f32)>,
 
     // Analytics
-    active_heavy_agents: usize,
-    pub awaiting_promotions: Vec<u32>,
+    /// Active heavy agent IDs, least recently promoted first
+    heavy_lru: VecDeque<u32>,
+    /// Heavy agents evicted by the cap, not yet drained by `pop_retired`
+    retired_heavy: Vec<u32>,
+    total_heavy_spawned: usize,
+    last_broadcaster_count: usize,
+    /// Queued promotions as (agent id, reason, magnitude)
+    pub awaiting_promotions: Vec<(u32, PromotionReason, f32)>,
 
     // Time Tracking
     pub global_tick: u64,
+
+    // Mortality
+    pub deaths: u64,
+
+    // Economy
+    /// City trades completed during the most recent tick
+    #[pyo3(get)]
+    pub trades_this_tick: u64,
+    /// City trades completed since the swarm was created
+    #[pyo3(get)]
+    pub total_trades: u64,
 }
 
 #[pymethods]
@@ -73,7 +182,7 @@ impl TensorSwarm {
         );
 
         let default_latent = LatentState::new(vec![0.0; w_cfg.latent_dim], "".to_string(), 0);
-        let default_pollinator = PollinatorState::new(15, 0.6, 1.0, 0.1, 0.9);
+        let default_pollinator = PollinatorState::new(15, role_broadcast_weight(ROLE_WORKER), 1.0, 0.1, 0.9, 0.01);
 
         let mut x_vec = vec![0.0; size];
         let mut y_vec = vec![0.0; size];
@@ -100,9 +209,15 @@ impl TensorSwarm {
             towns: Vec::new(),
             cities: Vec::new(),
             ambush_zones: Vec::new(),
-            active_heavy_agents: 0,
+            heavy_lru: VecDeque::new(),
+            retired_heavy: Vec::new(),
+            total_heavy_spawned: 0,
+            last_broadcaster_count: 0,
             awaiting_promotions: Vec::new(),
             global_tick: 0,
+            deaths: 0,
+            trades_this_tick: 0,
+            total_trades: 0,
         }
     }
 
@@ -120,6 +235,64 @@ impl TensorSwarm {
             .for_each(|y| *y = rand::random::<f32>() * height);
     }
 
+    /// Current swarm configuration
+    pub fn config(&self) -> SwarmConfig {
+        self.config.clone()
+    }
+
+    /// Resize the world mid-run and clamp every agent into the new bounds.
+    /// Shrinking below the current agent spread warns, since agents beyond
+    /// the new edge are piled onto it.
+    pub fn set_world_bounds(&mut self, width: usize, height: usize) {
+        let spread_x = self.x.par_iter().cloned().reduce(|| 0.0, f32::max);
+        let spread_y = self.y.par_iter().cloned().reduce(|| 0.0, f32::max);
+        if spread_x > width as f32 || spread_y > height as f32 {
+            warn!(
+                "[Swarm] Shrinking world to {}x{} below agent spread {:.1}x{:.1}; clamping agents to the edge",
+                width, height, spread_x, spread_y
+            );
+        }
+
+        self.config.world_width = width;
+        self.config.world_height = height;
+        let (w, h) = (width as f32, height as f32);
+        self.x.par_iter_mut().for_each(|x| *x = x.clamp(0.0, w));
+        self.y.par_iter_mut().for_each(|y| *y = y.clamp(0.0, h));
+    }
+
+    /// Assign roles to all agents (0=Worker, 1=Scout) and retune their broadcast weights.
+    /// Dead agents stay dead whatever their slot holds; only mortality can kill.
+    pub fn set_roles(&mut self, roles: Vec<u8>) -> PyResult<()> {
+        if roles.len() != self.ids.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Expected {} roles, got {}",
+                self.ids.len(),
+                roles.len()
+            )));
+        }
+        if let Some(i) = self
+            .role
+            .iter()
+            .zip(&roles)
+            .position(|(&current, &new)| current != ROLE_DEAD && new == ROLE_DEAD)
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cannot assign ROLE_DEAD to living agent at index {}",
+                i
+            )));
+        }
+        self.pollinator_states
+            .par_iter_mut()
+            .zip(self.role.par_iter_mut())
+            .zip(roles.par_iter())
+            .filter(|((_, current), _)| **current != ROLE_DEAD)
+            .for_each(|((pollinator, current), role)| {
+                pollinator.surprise_broadcast_weight = role_broadcast_weight(*role);
+                *current = *role;
+            });
+        Ok(())
+    }
+
     #[pyo3(name="step")]
     pub fn step(&mut self) {
         self.tick();
@@ -133,11 +306,17 @@ impl TensorSwarm {
         let width = self.config.world_width as f32;
         let height = self.config.world_height as f32;
         let size = self.ids.len();
+        let death_threshold = self.config.death_threshold;
+        let respawn = self.config.respawn;
+        let radius = self.config.location_radius;
+        let foraging_drive = self.config.foraging_drive;
 
         // Pass 1 Output Buffers
         let mut trade_rewards = vec![0.0; size];
         let mut broadcasting = vec![false; size];
-        let mut needs_promotion = vec![false; size];
+        let mut needs_promotion: Vec<Option<(PromotionReason, f32)>> = vec![None; size];
+        let mut died = vec![false; size];
+        let mut completed_trade = vec![false; size];
 
         // Pass 1: Physical Updates, Harvesting, and Intent
         self.x
@@ -150,42 +329,77 @@ impl TensorSwarm {
             .zip(trade_rewards.par_iter_mut())
             .zip(broadcasting.par_iter_mut())
             .zip(needs_promotion.par_iter_mut())
-            .for_each(|((((((((x, y), health), resources), surprise), pollinator), reward), is_broadcasting), promote)| {
-                // Rule: Brownian Motion
-                *x = (*x + (rand::random::<f32>() - 0.5) * 2.0).clamp(0.0, width);
-                *y = (*y + (rand::random::<f32>() - 0.5) * 2.0).clamp(0.0, height);
+            .zip(self.role.par_iter_mut())
+            .zip(died.par_iter_mut())
+            .zip(completed_trade.par_iter_mut())
+            .for_each(|(((((((((((x, y), health), resources), surprise), pollinator), reward), is_broadcasting), promote), role), has_died), has_traded)| {
+                if *role == ROLE_DEAD {
+                    return;
+                }
+
+                let is_worker = *role != ROLE_SCOUT;
+
+                // Rule: Brownian Motion (scouts take larger steps), with workers
+                // drifting toward the nearest village by `foraging_drive`
+                let step = role_step_scale(*role);
+                let (drift_x, drift_y) = if is_worker && foraging_drive > 0.0 {
+                    let (ux, uy) = toward_nearest(*x, *y, &self.villages);
+                    (ux * foraging_drive, uy * foraging_drive)
+                } else {
+                    (0.0, 0.0)
+                };
+                *x = (*x + (rand::random::<f32>() - 0.5) * step + drift_x).clamp(0.0, width);
+                *y = (*y + (rand::random::<f32>() - 0.5) * step + drift_y).clamp(0.0, height);
                 *health *= 0.999; // Natural decay
 
+                // Rule: Mortality (respawn keeps the agent's role)
+                if *health < death_threshold {
+                    *has_died = true;
+                    if respawn {
+                        *x = rand::random::<f32>() * width;
+                        *y = rand::random::<f32>() * height;
+                        *health = 1.0;
+                        *resources = 0.0;
+                        *surprise = 0.0;
+                    } else {
+                        *role = ROLE_DEAD;
+                    }
+                    return;
+                }
+
                 // Rule: Ebbinghaus decay on surprise_score
                 let retention = (-0.1 * (1.0 - *surprise).max(0.1)).exp();
                 *surprise = *surprise * retention;
 
                 let mut traded = false;
 
-                // Harvest resources at villages
-                for village in self.villages.iter() {
-                    if (*x - village.0).abs() < 5.0 && (*y - village.1).abs() < 5.0 {
+                // Harvest resources at villages (workers only)
+                for village in self.villages.iter().filter(|_| is_worker) {
+                    if (*x - village.0).abs() < radius && (*y - village.1).abs() < radius {
                         *resources += 1.0;
                         break;
                     }
                 }
 
-                // Sell resources at cities
-                for city in self.cities.iter() {
-                    if (*x - city.0).abs() < 5.0 && (*y - city.1).abs() < 5.0 {
+                // Sell resources at cities (workers only)
+                for city in self.cities.iter().filter(|_| is_worker) {
+                    if (*x - city.0).abs() < radius && (*y - city.1).abs() < radius {
                         if *resources > 0.0 {
+                            let held = *resources;
                             *health = (*health + 0.5).min(1.0); // Heal from successful trade
                             *resources -= 1.0;
                             traded = true;
                             // Signal that a complex trade occurred, triggering LLM negotiation 10% of the time
                             if rand::random::<f32>() < 0.10 {
-                                *promote = true;
+                                *promote = Some((PromotionReason::ComplexTrade, held));
                             }
                         }
                         break;
                     }
                 }
 
+                *has_traded = traded;
+
                 // RL Signal: A successful trade validates any past info we acted on.
                 // We waste a tiny bit of energy if we didn't trade (baseline survival cost).
                 *reward = if traded || *surprise > 0.8 { 1.0 } else { -0.1 };
@@ -194,15 +408,20 @@ impl TensorSwarm {
                 *is_broadcasting = pollinator.should_pollinate(rand::random(), *surprise);
             });
 
+        self.deaths += died.iter().filter(|d| **d).count() as u64;
+        self.trades_this_tick = completed_trade.iter().filter(|t| **t).count() as u64;
+        self.total_trades += self.trades_this_tick;
+
         // Optimization: Collect the spatial coordinates of ONLY the agents who decided to broadcast
         // This avoids N^2 distance checks. 
         let broadcasters: Vec<(u32, f32, f32)> = self.ids.iter().zip(self.x.iter()).zip(self.y.iter()).zip(broadcasting.iter())
             .filter_map(|(((id, x), y), b)| if *b { Some((*id, *x, *y)) } else { None })
             .collect();
+        self.last_broadcaster_count = broadcasters.len();
             
         // Collect promotions
-        let new_promotions: Vec<u32> = self.ids.iter().zip(needs_promotion.iter())
-            .filter_map(|(id, p)| if *p { Some(*id) } else { None })
+        let new_promotions: Vec<(u32, PromotionReason, f32)> = self.ids.iter().zip(needs_promotion.iter())
+            .filter_map(|(id, p)| p.map(|(reason, magnitude)| (*id, reason, magnitude)))
             .collect();
         self.awaiting_promotions.extend(new_promotions);
 
@@ -219,11 +438,13 @@ impl TensorSwarm {
                 
                 // 1. Send the trade reward feedback back to whoever shared context with us recently
                 // The pollinator state holds a hashmap of (Agent_ID -> Tick_of_Share)
-                // We use `.clone()` on the keys to avoid concurrent borrow mutations while sending feedback
-                let active_keys: Vec<u32> = pollinator.active_shares_keys(); 
-                for broker_id in active_keys {
-                     pollinator.apply_feedback(broker_id, *reward, global_tick);
-                }
+                // The keys are copied out first; the sigmoid is recomputed once for the whole batch
+                let feedbacks: Vec<(u32, f32)> = pollinator
+                    .active_shares_keys()
+                    .into_iter()
+                    .map(|broker_id| (broker_id, *reward))
+                    .collect();
+                pollinator.apply_feedback_batch(&feedbacks, global_tick);
 
                 // 2. Receive new signals from nearby broadcasters (Simulating P2P Info Exchange)
                 // If an agent is broadcasting within D=5.0, we "hear" them and credit them later if we trade
@@ -248,12 +469,70 @@ impl TensorSwarm {
 
     /// Retrieve the queue of agents that reached a promotion trigger, clearing it
     pub fn pop_promotions(&mut self) -> Vec<u32> {
-        let promoted = self.awaiting_promotions.clone();
-        self.active_heavy_agents += promoted.len(); // Track total spawned
-        self.awaiting_promotions.clear();
+        self.pop_promotions_detailed()
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect()
+    }
+
+    /// Like `pop_promotions`, but each entry is (agent id, reason, magnitude)
+    /// so the heavy agent knows what it was promoted to handle.
+    ///
+    /// Returns at most `max_heavy_agents` entries; the rest stay queued for
+    /// the next call. Each returned agent becomes the most recently used heavy
+    /// agent, retiring the least recently used one when the tier is full;
+    /// retired IDs are collected for `pop_retired`.
+    pub fn pop_promotions_detailed(&mut self) -> Vec<(u32, PromotionReason, f32)> {
+        let cap = self.config.max_heavy_agents;
+        let take = if cap == 0 {
+            self.awaiting_promotions.len()
+        } else {
+            self.awaiting_promotions.len().min(cap)
+        };
+        let promoted: Vec<_> = self.awaiting_promotions.drain(..take).collect();
+
+        for (id, _, _) in &promoted {
+            if let Some(pos) = self.heavy_lru.iter().position(|active| active == id) {
+                // Already heavy: just refresh its recency
+                self.heavy_lru.remove(pos);
+            } else {
+                self.total_heavy_spawned += 1;
+                if cap > 0 && self.heavy_lru.len() >= cap {
+                    if let Some(oldest) = self.heavy_lru.pop_front() {
+                        info!("🪫 [Swarm] Retiring heavy agent {} to make room", oldest);
+                        self.retired_heavy.push(oldest);
+                    }
+                }
+            }
+            self.heavy_lru.push_back(*id);
+        }
         promoted
     }
 
+    /// Heavy agents the cap retired since the last call, oldest first, so
+    /// their LLM work can be cancelled. Agents retired through
+    /// `retire_heavy` are not included.
+    pub fn pop_retired(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.retired_heavy)
+    }
+
+    /// Return a heavy agent to the light tier, freeing its slot.
+    /// Returns false if it was not active.
+    pub fn retire_heavy(&mut self, id: u32) -> bool {
+        match self.heavy_lru.iter().position(|active| *active == id) {
+            Some(pos) => {
+                self.heavy_lru.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Active heavy agent IDs, least recently promoted first
+    pub fn active_heavy_ids(&self) -> Vec<u32> {
+        self.heavy_lru.iter().copied().collect()
+    }
+
     /// Register tracking locations for the spatial simulation
     pub fn register_locations(
         &mut self,
@@ -268,6 +547,24 @@ impl TensorSwarm {
         self.ambush_zones = ambush_zones;
     }
 
+    /// Registered locations as (villages, towns, cities, ambush_zones)
+    pub fn get_locations(&self) -> Locations {
+        (
+            self.villages.clone(),
+            self.towns.clone(),
+            self.cities.clone(),
+            self.ambush_zones.clone(),
+        )
+    }
+
+    /// Closest registered city to (x, y) as (city_x, city_y, distance)
+    pub fn nearest_city(&self, x: f32, y: f32) -> Option<(f32, f32, f32)> {
+        self.cities
+            .iter()
+            .map(|&(cx, cy)| (cx, cy, ((cx - x).powi(2) + (cy - y).powi(2)).sqrt()))
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
     /// Force high surprise score on agents within a blast radius
     pub fn apply_environmental_shock(&mut self, location: (f32, f32), radius: f32, intensity: f32) {
         let r2 = radius * radius;
@@ -285,11 +582,79 @@ impl TensorSwarm {
             });
     }
 
+    /// Sum of resources held by all agents
+    pub fn total_resources(&self) -> f32 {
+        self.resources.iter().sum()
+    }
+
+    /// Every agent's resources, sorted ascending (e.g. for Gini / Lorenz curves)
+    pub fn wealth_distribution(&self) -> Vec<f32> {
+        let mut wealth = self.resources.clone();
+        wealth.sort_by(|a, b| a.total_cmp(b));
+        wealth
+    }
+
+    /// Typed metrics snapshot, computed in one pass over the SoA columns.
+    /// Means skip `ROLE_DEAD` agents and are 0 when none are alive.
+    pub fn metrics(&self) -> SwarmMetrics {
+        let mut surprise_sum = 0.0f32;
+        let mut health_sum = 0.0f32;
+        let mut alive_count = 0usize;
+        for ((surprise, health), role) in self
+            .surprise_scores
+            .iter()
+            .zip(self.health.iter())
+            .zip(self.role.iter())
+        {
+            if *role != ROLE_DEAD {
+                surprise_sum += surprise;
+                health_sum += health;
+                alive_count += 1;
+            }
+        }
+
+        let mean = |sum: f32| {
+            if alive_count == 0 {
+                0.0
+            } else {
+                sum / alive_count as f32
+            }
+        };
+        SwarmMetrics {
+            mean_surprise: mean(surprise_sum),
+            active_heavy_agents: self.heavy_lru.len(),
+            total_heavy_spawned: self.total_heavy_spawned,
+            alive_count,
+            mean_health: mean(health_sum),
+            broadcaster_count: self.last_broadcaster_count,
+        }
+    }
+
+    /// Snapshot every SoA column as a dict of NumPy arrays.
+    ///
+    /// Each column is copied once into a bytes object that `numpy.frombuffer`
+    /// wraps without a second copy, so the arrays are read-only. Requires numpy.
+    pub fn to_numpy_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let numpy = py.import_bound("numpy")?;
+        let frombuffer = numpy.getattr("frombuffer")?;
+        let dict = PyDict::new_bound(py);
+        for (name, dtype, bytes) in self.column_bytes() {
+            let buffer = PyBytes::new_bound(py, bytes);
+            let kwargs = PyDict::new_bound(py);
+            kwargs.set_item("dtype", dtype)?;
+            let array = frombuffer.call((buffer,), Some(&kwargs))?;
+            dict.set_item(name, array)?;
+        }
+        Ok(dict.into())
+    }
+
     /// Provide standard simulation metrics snapshot
     pub fn sample_population_metrics(&self) -> PyObject {
         Python::with_gil(|py| {
             let dict = PyDict::new_bound(py);
-            dict.set_item("active_heavy_agents", self.active_heavy_agents).unwrap();
+            dict.set_item("active_heavy_agents", self.heavy_lru.len()).unwrap();
+            dict.set_item("total_heavy_spawned", self.total_heavy_spawned).unwrap();
+            dict.set_item("deaths", self.deaths).unwrap();
             
             // For histogram metrics
             dict.set_item("share_probability_distribution", self.share_probabilities.clone()).unwrap();
@@ -303,3 +668,352 @@ impl TensorSwarm {
         })
     }
 }
+
+impl TensorSwarm {
+    /// SoA columns as (name, numpy dtype, native-endian bytes), borrowed
+    /// straight from the vectors
+    fn column_bytes(&self) -> Vec<(&'static str, &'static str, &[u8])> {
+        vec![
+            ("ids", "uint32", ne_bytes(&self.ids)),
+            ("x", "float32", ne_bytes(&self.x)),
+            ("y", "float32", ne_bytes(&self.y)),
+            ("health", "float32", ne_bytes(&self.health)),
+            ("resources", "float32", ne_bytes(&self.resources)),
+            ("role", "uint8", &self.role),
+            ("surprise_scores", "float32", ne_bytes(&self.surprise_scores)),
+            ("share_probabilities", "float32", ne_bytes(&self.share_probabilities)),
+        ]
+    }
+}
+
+/// Native-endian byte view of a numeric column
+trait Plain: Copy {}
+impl Plain for u32 {}
+impl Plain for f32 {}
+
+fn ne_bytes<T: Plain>(column: &[T]) -> &[u8] {
+    // SAFETY: `Plain` types have no padding and every bit pattern is a valid
+    // u8, so the column's memory can be read as `size_of_val` bytes
+    unsafe {
+        std::slice::from_raw_parts(column.as_ptr() as *const u8, std::mem::size_of_val(column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_bytes_match_vectors() {
+        let mut swarm = TensorSwarm::new(3, None, None);
+        swarm.health = vec![1.0, 0.5, 0.25];
+        swarm.role = vec![ROLE_WORKER, ROLE_SCOUT, ROLE_DEAD];
+
+        let columns = swarm.column_bytes();
+        let column = |name: &str| {
+            columns
+                .iter()
+                .find(|(n, _, _)| *n == name)
+                .map(|(_, dtype, bytes)| (*dtype, bytes.to_vec()))
+                .unwrap()
+        };
+        let floats = |bytes: Vec<u8>| -> Vec<f32> {
+            bytes
+                .chunks_exact(4)
+                .map(|c| f32::from_ne_bytes(c.try_into().unwrap()))
+                .collect()
+        };
+
+        let (dtype, x) = column("x");
+        assert_eq!(dtype, "float32");
+        assert_eq!(floats(x), swarm.x);
+        assert_eq!(floats(column("health").1), swarm.health);
+        assert_eq!(column("role"), ("uint8", swarm.role.clone()));
+        let ids: Vec<u32> = column("ids")
+            .1
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(ids, swarm.ids);
+        assert_eq!(columns.len(), 8);
+    }
+
+    #[test]
+    fn test_set_world_bounds_grows_and_clamps() {
+        let mut swarm = TensorSwarm::new(500, None, None);
+        let (x, y) = (swarm.x.clone(), swarm.y.clone());
+
+        swarm.set_world_bounds(400, 300);
+        assert_eq!(swarm.config().world_width, 400);
+        assert_eq!(swarm.config().world_height, 300);
+        assert_eq!(swarm.x, x);
+        assert_eq!(swarm.y, y);
+        swarm.tick();
+        assert!(swarm.x.iter().all(|x| (0.0..=400.0).contains(x)));
+        assert!(swarm.y.iter().all(|y| (0.0..=300.0).contains(y)));
+
+        swarm.set_world_bounds(10, 20);
+        assert!(swarm.x.iter().all(|x| (0.0..=10.0).contains(x)));
+        assert!(swarm.y.iter().all(|y| (0.0..=20.0).contains(y)));
+        assert!(swarm.x.contains(&10.0));
+        assert!(swarm.y.contains(&20.0));
+    }
+
+    #[test]
+    fn test_set_roles_rejects_length_mismatch() {
+        let mut swarm = TensorSwarm::new(10, None, None);
+        assert!(swarm.set_roles(vec![ROLE_SCOUT; 3]).is_err());
+        assert!(swarm.set_roles(vec![ROLE_SCOUT; 10]).is_ok());
+        assert!(swarm.role.iter().all(|r| *r == ROLE_SCOUT));
+    }
+
+    #[test]
+    fn test_set_roles_keeps_dead_agents_dead() {
+        let mut swarm = TensorSwarm::new(4, None, None);
+        swarm.role[1] = ROLE_DEAD;
+        assert!(swarm.set_roles(vec![ROLE_SCOUT, ROLE_DEAD, ROLE_DEAD, ROLE_SCOUT]).is_err());
+        assert_eq!(swarm.role, vec![ROLE_WORKER, ROLE_DEAD, ROLE_WORKER, ROLE_WORKER]);
+
+        swarm.set_roles(vec![ROLE_SCOUT; 4]).unwrap();
+        assert_eq!(swarm.role, vec![ROLE_SCOUT, ROLE_DEAD, ROLE_SCOUT, ROLE_SCOUT]);
+    }
+
+    #[test]
+    fn test_default_config_is_immortal() {
+        let mut swarm = TensorSwarm::new(100, None, None);
+        for _ in 0..5000 {
+            swarm.tick();
+        }
+        assert_eq!(swarm.deaths, 0);
+        assert!(swarm.role.iter().all(|r| *r != ROLE_DEAD));
+    }
+
+    #[test]
+    fn test_metrics_means() {
+        let mut swarm = TensorSwarm::new(4, None, None);
+        swarm.surprise_scores = vec![0.2, 0.4, 0.6, 0.8];
+        swarm.health = vec![1.0, 0.5, 0.5, 0.0];
+        swarm.role[3] = ROLE_DEAD;
+
+        // The dead agent is left out of both means
+        let m = swarm.metrics();
+        assert!((m.mean_surprise - 0.4).abs() < 1e-6);
+        assert!((m.mean_health - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(m.alive_count, 3);
+        assert_eq!(m.active_heavy_agents, 0);
+        assert_eq!(m.total_heavy_spawned, 0);
+        assert_eq!(m.broadcaster_count, 0);
+
+        assert_eq!(TensorSwarm::new(0, None, None).metrics().mean_surprise, 0.0);
+        swarm.role.fill(ROLE_DEAD);
+        assert_eq!(swarm.metrics().mean_health, 0.0);
+    }
+
+    #[test]
+    fn test_city_trades_are_counted() {
+        let mut swarm = TensorSwarm::new(2, None, None);
+        swarm.register_locations(vec![], vec![], vec![(50.0, 50.0)], vec![]);
+        swarm.x = vec![50.0, 50.0];
+        swarm.y = vec![50.0, 50.0];
+        swarm.resources = vec![3.0, 0.0];
+
+        swarm.tick();
+        assert_eq!(swarm.trades_this_tick, 1);
+        assert_eq!(swarm.total_trades, 1);
+        assert_eq!(swarm.total_resources(), 2.0);
+
+        swarm.tick();
+        assert_eq!(swarm.trades_this_tick, 1);
+        assert_eq!(swarm.total_trades, 2);
+        assert_eq!(swarm.wealth_distribution(), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_trade_promotion_reports_reason() {
+        let mut swarm = TensorSwarm::new(200, None, None);
+        swarm.register_locations(vec![], vec![], vec![(50.0, 50.0)], vec![]);
+        swarm.x = vec![50.0; 200];
+        swarm.y = vec![50.0; 200];
+        swarm.resources = vec![4.0; 200];
+
+        swarm.tick();
+        let promoted = swarm.pop_promotions_detailed();
+        assert!(!promoted.is_empty());
+        for (id, reason, magnitude) in &promoted {
+            assert!(swarm.ids.contains(id));
+            assert_eq!(*reason, PromotionReason::ComplexTrade);
+            assert_eq!(*magnitude, 4.0);
+        }
+        assert_eq!(swarm.metrics().active_heavy_agents, promoted.len());
+        assert!(swarm.pop_promotions().is_empty());
+    }
+
+    #[test]
+    fn test_heavy_tier_respects_cap() {
+        let cfg = SwarmConfig {
+            max_heavy_agents: 3,
+            ..SwarmConfig::default()
+        };
+        let mut swarm = TensorSwarm::new(20, None, Some(cfg));
+        let queue = |swarm: &mut TensorSwarm, ids: &[u32]| {
+            for id in ids {
+                swarm
+                    .awaiting_promotions
+                    .push((*id, PromotionReason::ComplexTrade, 1.0));
+            }
+        };
+
+        // Oversized batches are deferred, and the oldest agent is retired
+        queue(&mut swarm, &[0, 1, 2, 3, 4]);
+        assert_eq!(swarm.pop_promotions(), vec![0, 1, 2]);
+        assert_eq!(swarm.awaiting_promotions.len(), 2);
+        assert!(swarm.pop_retired().is_empty());
+        assert_eq!(swarm.pop_promotions(), vec![3, 4]);
+        assert_eq!(swarm.active_heavy_ids(), vec![2, 3, 4]);
+        assert_eq!(swarm.pop_retired(), vec![0, 1]);
+        assert!(swarm.pop_retired().is_empty());
+
+        // Re-promoting an active agent refreshes it instead of spawning
+        queue(&mut swarm, &[2, 5]);
+        swarm.pop_promotions();
+        assert_eq!(swarm.active_heavy_ids(), vec![4, 2, 5]);
+        assert_eq!(swarm.pop_retired(), vec![3]);
+
+        assert!(swarm.retire_heavy(4));
+        assert!(!swarm.retire_heavy(4));
+        assert_eq!(swarm.metrics().active_heavy_agents, 2);
+        assert!(swarm.pop_retired().is_empty());
+
+        queue(&mut swarm, &(6..16).collect::<Vec<u32>>());
+        while !swarm.awaiting_promotions.is_empty() {
+            swarm.pop_promotions();
+            assert!(swarm.metrics().active_heavy_agents <= 3);
+        }
+        assert_eq!(swarm.metrics().total_heavy_spawned, 16);
+    }
+
+    #[test]
+    fn test_single_city_geography() {
+        let cfg = SwarmConfig {
+            location_radius: 2.0,
+            ..SwarmConfig::default()
+        };
+        let mut swarm = TensorSwarm::new(2, None, Some(cfg));
+        assert_eq!(swarm.nearest_city(0.0, 0.0), None);
+
+        swarm.register_locations(vec![(10.0, 10.0)], vec![], vec![(50.0, 50.0)], vec![]);
+        let (villages, towns, cities, ambush) = swarm.get_locations();
+        assert_eq!(villages, vec![(10.0, 10.0)]);
+        assert!(towns.is_empty() && ambush.is_empty());
+        assert_eq!(cities, vec![(50.0, 50.0)]);
+        assert_eq!(swarm.nearest_city(53.0, 54.0), Some((50.0, 50.0, 5.0)));
+
+        // One agent inside the radius trades, one just outside does not
+        swarm.x = vec![51.0, 54.0];
+        swarm.y = vec![50.0, 50.0];
+        swarm.resources = vec![1.0, 1.0];
+        swarm.role = vec![ROLE_WORKER, ROLE_WORKER];
+        swarm.tick();
+        assert_eq!(swarm.trades_this_tick, 1);
+    }
+
+    #[test]
+    fn test_foraging_drive_pulls_workers_to_villages() {
+        let mean_distance = |swarm: &TensorSwarm| {
+            let total: f32 = swarm
+                .x
+                .iter()
+                .zip(&swarm.y)
+                .map(|(x, y)| ((x - 50.0).powi(2) + (y - 50.0).powi(2)).sqrt())
+                .sum();
+            total / swarm.x.len() as f32
+        };
+        let make = |foraging_drive: f32| {
+            let cfg = SwarmConfig {
+                foraging_drive,
+                ..SwarmConfig::default()
+            };
+            let mut swarm = TensorSwarm::new(1000, None, Some(cfg));
+            swarm.register_locations(vec![(50.0, 50.0)], vec![], vec![], vec![]);
+            swarm
+        };
+
+        let mut baseline = make(0.0);
+        let mut foraging = make(0.5);
+        foraging.x = baseline.x.clone();
+        foraging.y = baseline.y.clone();
+        let start = mean_distance(&baseline);
+
+        for _ in 0..20 {
+            baseline.tick();
+            foraging.tick();
+        }
+        assert!(mean_distance(&foraging) < start - 5.0);
+        assert!(mean_distance(&foraging) < mean_distance(&baseline) - 5.0);
+    }
+
+    #[test]
+    fn test_low_health_agent_dies() {
+        let cfg = SwarmConfig {
+            death_threshold: 0.05,
+            ..SwarmConfig::default()
+        };
+        let mut swarm = TensorSwarm::new(4, None, Some(cfg));
+        swarm.health[0] = 0.0501;
+        for _ in 0..5 {
+            swarm.tick();
+        }
+        assert_eq!(swarm.role[0], ROLE_DEAD);
+        assert_eq!(swarm.deaths, 1);
+
+        // Dead agents are frozen in place
+        let (x, y) = (swarm.x[0], swarm.y[0]);
+        swarm.tick();
+        assert_eq!((swarm.x[0], swarm.y[0]), (x, y));
+        assert_eq!(swarm.deaths, 1);
+    }
+
+    #[test]
+    fn test_respawn_restores_health() {
+        let cfg = SwarmConfig {
+            death_threshold: 0.05,
+            respawn: true,
+            ..SwarmConfig::default()
+        };
+        let mut swarm = TensorSwarm::new(4, None, Some(cfg));
+        swarm.health[0] = 0.0501;
+        for _ in 0..5 {
+            swarm.tick();
+        }
+        assert_eq!(swarm.deaths, 1);
+        assert_eq!(swarm.role[0], ROLE_WORKER);
+        assert!(swarm.health[0] > 0.99);
+    }
+
+    #[test]
+    fn test_scouts_cover_more_distance_than_workers() {
+        let n = 2000;
+        let mut swarm = TensorSwarm::new(n, None, None);
+        let roles: Vec<u8> = (0..n).map(|i| if i % 2 == 0 { ROLE_WORKER } else { ROLE_SCOUT }).collect();
+        swarm.set_roles(roles.clone()).unwrap();
+
+        let mut worker_dist = 0.0;
+        let mut scout_dist = 0.0;
+        for _ in 0..5 {
+            // Re-center so world bounds never clamp a step
+            swarm.x.fill(50.0);
+            swarm.y.fill(50.0);
+            swarm.tick();
+            for ((x, y), role) in swarm.x.iter().zip(swarm.y.iter()).zip(roles.iter()) {
+                let d = ((x - 50.0).powi(2) + (y - 50.0).powi(2)).sqrt();
+                if *role == ROLE_SCOUT {
+                    scout_dist += d;
+                } else {
+                    worker_dist += d;
+                }
+            }
+        }
+
+        assert!(scout_dist > worker_dist * 2.0);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::intel::safety::PredictiveSafetyShield;
+use crate::swarm::master_pipeline::SwarmEngineMaster;
 use crate::TrajectoryPoint;
 use pyo3::prelude::*;
 use serde::Deserialize;
+use std::io::Write;
+use std::time::Instant;
 use tracing::info;
 
 /// Evaluation task for benchmarking (internal use only)
@@ -13,19 +16,129 @@ struct EvalTask {
     dangerous: bool,
 }
 
-/// Benchmark - Evaluates agent safety
+/// Benchmark - Evaluates agent safety and records timing samples
 #[pyclass]
-pub struct AgentBenchmark {}
+pub struct AgentBenchmark {
+    /// Recorded durations in milliseconds
+    samples: Vec<f64>,
+}
 
 #[pymethods]
 impl AgentBenchmark {
     #[new]
     pub fn new() -> Self {
-        AgentBenchmark {}
+        AgentBenchmark {
+            samples: Vec::new(),
+        }
+    }
+
+    /// Record a timing sample in milliseconds
+    pub fn record(&mut self, duration_ms: f64) {
+        self.samples.push(duration_ms);
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Discard all recorded samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Percentile `p` (0-100) of recorded samples, linearly interpolated.
+    /// Returns 0.0 when no samples have been recorded.
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let frac = rank - lower as f64;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+    }
+
+    /// Mean of recorded samples (0.0 when empty)
+    pub fn mean(&self) -> f64 {
+        summarize(&self.samples).0
+    }
+
+    /// 95% confidence interval (low, high) of the mean of recorded samples
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let (_, low, high) = summarize(&self.samples);
+        (low, high)
     }
 
-    /// Run evaluation on a dataset (JSONL format)
-    pub fn run_eval(&self, dataset_json: String, limit: usize) -> (usize, usize) {
+    /// Time a Python callable: run `warmup_iters` untimed calls to absorb
+    /// cold-start effects, then record `measured_iters` timed calls.
+    /// Returns (mean_ms, ci_low_ms, ci_high_ms) over the measured calls.
+    pub fn run_with_warmup(
+        &mut self,
+        py: Python,
+        warmup_iters: usize,
+        measured_iters: usize,
+        callback: PyObject,
+    ) -> PyResult<(f64, f64, f64)> {
+        self.run_timed(warmup_iters, measured_iters, || {
+            callback.call0(py).map(|_| ())
+        })
+    }
+
+    /// Dump raw samples as CSV (`index,duration_ms`); header-only when empty
+    pub fn export_csv(&self, path: String) -> PyResult<()> {
+        let mut file = std::fs::File::create(&path).map_err(|e| {
+            pyo3::exceptions::PyIOError::new_err(format!("Cannot create {}: {}", path, e))
+        })?;
+        let mut csv = String::from("index,duration_ms\n");
+        for (i, sample) in self.samples.iter().enumerate() {
+            csv.push_str(&format!("{},{}\n", i, sample));
+        }
+        file.write_all(csv.as_bytes()).map_err(|e| {
+            pyo3::exceptions::PyIOError::new_err(format!("Cannot write {}: {}", path, e))
+        })
+    }
+
+    /// Run `SwarmEngineMaster` with `n_agents` for `ticks` ticks on a
+    /// 1000x1000 world and report per-tick timings as JSON:
+    /// `{n_agents, ticks, mean_ms, p50_ms, p95_ms, p99_ms, max_ms, peak_rss_kb}`.
+    /// Tick timings are also recorded as samples. `peak_rss_kb` is the
+    /// process high-water mark (0 where /proc is unavailable).
+    pub fn bench_swarm_pipeline(&mut self, n_agents: usize, ticks: usize) -> String {
+        let mut engine = SwarmEngineMaster::new(n_agents, 1000.0, 1000.0);
+        let mut run = AgentBenchmark::new();
+        for _ in 0..ticks {
+            let started = Instant::now();
+            engine.tick();
+            run.record(started.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        let report = serde_json::json!({
+            "n_agents": n_agents,
+            "ticks": ticks,
+            "mean_ms": run.mean(),
+            "p50_ms": run.percentile(50.0),
+            "p95_ms": run.percentile(95.0),
+            "p99_ms": run.percentile(99.0),
+            "max_ms": run.percentile(100.0),
+            "peak_rss_kb": peak_rss_kb(),
+        });
+        info!(
+            "Swarm pipeline benchmark: {} agents x {} ticks, p50 {:.3}ms, p99 {:.3}ms",
+            n_agents,
+            ticks,
+            run.percentile(50.0),
+            run.percentile(99.0)
+        );
+        self.samples.extend(run.samples);
+        report.to_string()
+    }
+
+    /// Run evaluation on a dataset (JSONL format), recording per-task timings
+    pub fn run_eval(&mut self, dataset_json: String, limit: usize) -> (usize, usize) {
         let tasks: Vec<EvalTask> = dataset_json
             .lines()
             .filter_map(|line| serde_json::from_str(line).ok())
@@ -38,7 +151,9 @@ impl AgentBenchmark {
         let total = tasks.len();
 
         for task in &tasks {
+            let started = Instant::now();
             let result = evaluate_single_task(task);
+            self.record(started.elapsed().as_secs_f64() * 1000.0);
             if result.0 {
                 info!("[{}] PASSED", task.task_id);
                 passed += 1;
@@ -62,6 +177,67 @@ impl AgentBenchmark {
     }
 }
 
+impl AgentBenchmark {
+    /// Warmup-then-measure loop shared by `run_with_warmup`
+    pub fn run_timed<F>(
+        &mut self,
+        warmup_iters: usize,
+        measured_iters: usize,
+        mut f: F,
+    ) -> PyResult<(f64, f64, f64)>
+    where
+        F: FnMut() -> PyResult<()>,
+    {
+        for _ in 0..warmup_iters {
+            f()?;
+        }
+
+        let mut measured = Vec::with_capacity(measured_iters);
+        for _ in 0..measured_iters {
+            let started = Instant::now();
+            f()?;
+            measured.push(started.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        let stats = summarize(&measured);
+        info!(
+            "Benchmark: {} warmup + {} measured, mean {:.3}ms (95% CI {:.3}..{:.3})",
+            warmup_iters, measured_iters, stats.0, stats.1, stats.2
+        );
+        self.samples.extend(measured);
+        Ok(stats)
+    }
+}
+
+/// Mean and 95% confidence interval (mean ± 1.96·stddev/√n)
+fn summarize(samples: &[f64]) -> (f64, f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, mean, mean);
+    }
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let half_width = 1.96 * variance.sqrt() / n.sqrt();
+    (mean, mean - half_width, mean + half_width)
+}
+
+/// Peak resident set size (VmHWM) of this process in KiB, 0 if unknown
+fn peak_rss_kb() -> u64 {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find(|line| line.starts_with("VmHWM:"))
+                .and_then(|line| line.split_whitespace().nth(1))
+                .and_then(|kb| kb.parse().ok())
+        })
+        .unwrap_or(0)
+}
+
 /// Internal helper function (not exposed to Python)
 fn evaluate_single_task(task: &EvalTask) -> (bool, String) {
     let shield = PredictiveSafetyShield::new(0.5);
@@ -115,3 +291,83 @@ fn evaluate_single_task(task: &EvalTask) -> (bool, String) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_on_known_distribution() {
+        let mut bench = AgentBenchmark::new();
+        assert_eq!(bench.percentile(50.0), 0.0);
+
+        for v in 1..=101 {
+            bench.record(v as f64);
+        }
+        assert!((bench.percentile(50.0) - 51.0).abs() < 1e-9);
+        assert!((bench.percentile(95.0) - 96.0).abs() < 1e-9);
+        assert!((bench.percentile(100.0) - 101.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn export_csv_writes_header_and_rows() {
+        let path = std::env::temp_dir().join("agent_benchmark_export_test.csv");
+        let path_str = path.to_string_lossy().to_string();
+
+        let mut bench = AgentBenchmark::new();
+        bench.export_csv(path_str.clone()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "index,duration_ms\n"
+        );
+
+        bench.record(1.5);
+        bench.record(2.0);
+        bench.export_csv(path_str).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "index,duration_ms\n0,1.5\n1,2\n"
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn warmup_samples_are_excluded() {
+        let mut bench = AgentBenchmark::new();
+        let mut calls = 0;
+        let (mean, low, high) = bench
+            .run_timed(3, 5, || {
+                calls += 1;
+                if calls <= 3 {
+                    // Simulated cold start
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(calls, 8);
+        assert_eq!(bench.sample_count(), 5);
+        assert!(bench.percentile(100.0) < 50.0);
+        assert!(mean < 50.0);
+        assert!(low <= mean && mean <= high);
+    }
+
+    #[test]
+    fn swarm_pipeline_smoke_at_10k() {
+        let mut bench = AgentBenchmark::new();
+        let report: serde_json::Value =
+            serde_json::from_str(&bench.bench_swarm_pipeline(10_000, 5)).unwrap();
+
+        assert_eq!(report["n_agents"], 10_000);
+        assert_eq!(report["ticks"], 5);
+        assert_eq!(bench.sample_count(), 5);
+        let p50 = report["p50_ms"].as_f64().unwrap();
+        let p99 = report["p99_ms"].as_f64().unwrap();
+        assert!(p50 > 0.0 && p50 <= p99);
+        assert!(p99 <= report["max_ms"].as_f64().unwrap());
+        if cfg!(target_os = "linux") {
+            assert!(report["peak_rss_kb"].as_u64().unwrap() > 0);
+        }
+    }
+}
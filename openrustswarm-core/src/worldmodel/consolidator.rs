@@ -2,11 +2,25 @@
 //!
 //! Compresses old trajectories into summary latent states for long-term memory.
 
-use super::{LatentEncoder, LatentState, WorldModelConfig};
+use super::geometric::sphere_mean;
+use super::{check_dim, normalize_vector, LatentEncoder, LatentState, WorldModelConfig};
 use parking_lot::RwLock;
 use pyo3::prelude::*;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Retention weight of a memory `hours_elapsed` old under the given
+/// forgetting curve. `decay` is the (surprise-modulated) decay rate.
+pub fn retention_weight(model: &str, hours_elapsed: f32, decay: f32) -> f32 {
+    match model {
+        // Wixted & Ebbesen power law: R = (1 + t)^-d
+        "power_law" => (1.0 + hours_elapsed).powf(-decay),
+        "linear" => (1.0 - hours_elapsed * decay).max(0.0),
+        // Ebbinghaus: R = e^(-t * d)
+        _ => (-hours_elapsed * decay).exp(),
+    }
+}
 
 /// Memory consolidation result
 #[derive(Clone)]
@@ -36,21 +50,35 @@ pub struct MemoryConsolidator {
     config: WorldModelConfig,
     encoder: LatentEncoder,
     consolidated: RwLock<HashMap<String, Vec<ConsolidatedMemory>>>,
+    /// How `merge_all` combines summaries: "euclidean" (weighted average) or
+    /// "geometric" (weighted mean on the unit hypersphere)
+    #[pyo3(get)]
+    pub merge_mode: String,
 }
 
 #[pymethods]
 impl MemoryConsolidator {
     #[new]
-    #[pyo3(signature = (config = None))]
-    pub fn new(config: Option<WorldModelConfig>) -> Self {
+    #[pyo3(signature = (config = None, merge_mode = "euclidean".to_string()))]
+    pub fn new(config: Option<WorldModelConfig>, merge_mode: String) -> PyResult<Self> {
+        check_merge_mode(&merge_mode)?;
         let cfg = config.clone().unwrap_or_default();
+        let encoder = LatentEncoder::new(config)?;
         info!("💾 [Consolidator] Initialized for long-term memory");
 
-        MemoryConsolidator {
-            config: cfg.clone(),
-            encoder: LatentEncoder::new(config).unwrap(),
+        Ok(MemoryConsolidator {
+            config: cfg,
+            encoder,
             consolidated: RwLock::new(HashMap::new()),
-        }
+            merge_mode,
+        })
+    }
+
+    #[setter]
+    pub fn set_merge_mode(&mut self, merge_mode: String) -> PyResult<()> {
+        check_merge_mode(&merge_mode)?;
+        self.merge_mode = merge_mode;
+        Ok(())
     }
 
     /// Consolidate multiple trajectories into a single summary
@@ -73,40 +101,7 @@ impl MemoryConsolidator {
             .map(|tj| self.encoder.encode(tj.clone(), agent_id.clone()))
             .collect();
 
-        // Average the latent vectors using Ebbinghaus exponential decay
-        let mut summary_vector = vec![0.0f32; self.config.latent_dim];
-        let mut total_weight = 0.0f32;
-
-        // Base the "Now" timestamp off the most recent trajectory in the batch
-        let current_time = encoded.iter().map(|s| s.timestamp).max().unwrap_or(0);
-
-        for state in &encoded {
-            // Ebbinghaus Formula: Retention = e^(-time_elapsed * decay_rate)
-            // But highly surprising events are retained longer!
-            // We modulate the decay rate so high surprise = lower decay.
-            let hours_elapsed = (current_time.saturating_sub(state.timestamp)) as f32 / 3600.0;
-            let effective_decay = self.config.ebbinghaus_decay_rate * (1.0 - state.surprise_score).max(0.1);
-            let ebbinghaus_weight = (-hours_elapsed * effective_decay).exp();
-
-            for (i, v) in state.vector.iter().enumerate() {
-                summary_vector[i] += v * ebbinghaus_weight;
-            }
-            total_weight += ebbinghaus_weight;
-        }
-
-        if total_weight > 0.0 {
-            for v in &mut summary_vector {
-                *v /= total_weight;
-            }
-        }
-
-        // Normalize
-        let norm: f32 = summary_vector.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for v in &mut summary_vector {
-                *v /= norm;
-            }
-        }
+        let summary_vector = weighted_summary(&encoded, &self.config);
 
         // Calculate time span
         let timestamps: Vec<u64> = encoded.iter().map(|s| s.timestamp).collect();
@@ -118,11 +113,89 @@ impl MemoryConsolidator {
             0.0
         };
 
+        self.store(
+            agent_id,
+            summary_vector,
+            trajectory_jsons.len(),
+            time_span_hours,
+        )
+    }
+
+    /// Consolidate like `consolidate`, but encode `batch_size` trajectories at
+    /// a time through `encode_batch` so only one batch of latent states is held
+    /// in memory at once
+    #[pyo3(signature = (agent_id, trajectory_jsons, batch_size = 256))]
+    pub fn consolidate_streaming(
+        &self,
+        agent_id: String,
+        trajectory_jsons: Vec<String>,
+        batch_size: usize,
+    ) -> ConsolidatedMemory {
+        if trajectory_jsons.is_empty() {
+            return self.consolidate(agent_id, trajectory_jsons);
+        }
+
+        let mut summary = StreamingSummary::new(self.config.latent_dim);
+        for batch in trajectory_jsons.chunks(batch_size.max(1)) {
+            let encoded = self.encoder.encode_batch(batch.to_vec(), agent_id.clone());
+            summary.extend(&encoded);
+        }
+
+        let time_span_hours = summary.time_span_hours();
+        let summary_vector = summary.finish(&self.config);
+        self.store(
+            agent_id,
+            summary_vector,
+            trajectory_jsons.len(),
+            time_span_hours,
+        )
+    }
+
+    /// Get all consolidated memories for an agent
+    pub fn get_memories(&self, agent_id: String) -> Vec<ConsolidatedMemory> {
+        let store = self.consolidated.read();
+        store.get(&agent_id).cloned().unwrap_or_default()
+    }
+
+    /// Merge consolidated memories into a single long-term memory
+    pub fn merge_all(&self, agent_id: String) -> LatentState {
+        let store = self.consolidated.read();
+        let memories = store.get(&agent_id);
+
+        match memories {
+            Some(mems) if !mems.is_empty() => LatentState::new(
+                merge_summaries(mems, self.config.latent_dim, &self.merge_mode),
+                agent_id,
+                0,
+            ),
+            _ => LatentState::new(vec![0.0; self.config.latent_dim], agent_id, 0),
+        }
+    }
+
+    /// Get total trajectory count across all memories
+    pub fn total_trajectories(&self, agent_id: String) -> usize {
+        let store = self.consolidated.read();
+        store
+            .get(&agent_id)
+            .map(|mems| mems.iter().map(|m| m.num_trajectories).sum())
+            .unwrap_or(0)
+    }
+}
+
+impl MemoryConsolidator {
+    /// Record a consolidated summary for `agent_id`
+    fn store(
+        &self,
+        agent_id: String,
+        summary_vector: Vec<f32>,
+        num_trajectories: usize,
+        time_span_hours: f32,
+    ) -> ConsolidatedMemory {
         let summary = LatentState::new(summary_vector, agent_id.clone(), 0);
 
         let consolidated = ConsolidatedMemory {
-            summary: summary.clone(),
-            num_trajectories: trajectory_jsons.len(),
+            summary,
+            num_trajectories,
             time_span_hours,
         };
 
@@ -135,57 +208,392 @@ impl MemoryConsolidator {
 
         info!(
             "💾 [Consolidator] Consolidated {} trajectories for {} ({:.1}h span)",
-            trajectory_jsons.len(),
-            agent_id,
-            time_span_hours
+            num_trajectories, agent_id, time_span_hours
         );
 
         consolidated
     }
+}
 
-    /// Get all consolidated memories for an agent
-    pub fn get_memories(&self, agent_id: String) -> Vec<ConsolidatedMemory> {
-        let store = self.consolidated.read();
-        store.get(&agent_id).cloned().unwrap_or_default()
+/// Forgetting-curve weight of `state` when "now" is `current_time`.
+/// Highly surprising events are retained longer: high surprise = lower decay.
+fn memory_weight(
+    state_time: u64,
+    surprise: f32,
+    current_time: u64,
+    config: &WorldModelConfig,
+) -> f32 {
+    let hours_elapsed = (current_time.saturating_sub(state_time)) as f32 / 3600.0;
+    let effective_decay = config.ebbinghaus_decay_rate * (1.0 - surprise).max(0.1);
+    retention_weight(&config.retention_model, hours_elapsed, effective_decay)
+}
+
+fn normalize(vector: &mut [f32]) {
+    normalize_vector("Consolidator", vector);
+}
+
+/// Combine consolidated summaries, weighting each by its trajectory count.
+/// "geometric" takes the manifold mean on the unit hypersphere; any other mode
+/// averages in Euclidean space and renormalizes.
+/// Err unless `mode` is "euclidean" or "geometric"
+fn check_merge_mode(mode: &str) -> PyResult<()> {
+    if matches!(mode, "euclidean" | "geometric") {
+        Ok(())
+    } else {
+        Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "merge_mode must be euclidean or geometric, got '{}'",
+            mode
+        )))
+    }
+}
+
+fn merge_summaries(mems: &[ConsolidatedMemory], dim: usize, mode: &str) -> Vec<f32> {
+    let weights: Vec<f32> = mems.iter().map(|m| m.num_trajectories as f32).collect();
+
+    if mode == "geometric" {
+        let vectors: Vec<&[f32]> = mems.iter().map(|m| m.summary.vector.as_slice()).collect();
+        return sphere_mean(&vectors, &weights);
     }
 
-    /// Merge consolidated memories into a single long-term memory
-    pub fn merge_all(&self, agent_id: String) -> LatentState {
-        let store = self.consolidated.read();
-        let memories = store.get(&agent_id);
+    let mut merged = vec![0.0f32; dim];
+    let total_weight: f32 = weights.iter().sum();
 
-        match memories {
-            Some(mems) if !mems.is_empty() => {
-                let mut merged = vec![0.0f32; self.config.latent_dim];
-                let total_weight: f32 = mems.iter().map(|m| m.num_trajectories as f32).sum();
-
-                for mem in mems {
-                    let weight = mem.num_trajectories as f32 / total_weight;
-                    for (i, v) in mem.summary.vector.iter().enumerate() {
-                        merged[i] += v * weight;
-                    }
-                }
+    for (mem, weight) in mems.iter().zip(&weights) {
+        let weight = weight / total_weight;
+        for (i, v) in mem.summary.vector.iter().enumerate() {
+            merged[i] += v * weight;
+        }
+    }
 
-                // Normalize
-                let norm: f32 = merged.iter().map(|x| x * x).sum::<f32>().sqrt();
-                if norm > 0.0 {
-                    for v in &mut merged {
-                        *v /= norm;
+    // Normalize
+    normalize(&mut merged);
+    merged
+}
+
+/// True when `state` fits `dim`; otherwise logs a warning so the caller can skip it
+fn fits_dim(state: &LatentState, dim: usize) -> bool {
+    match check_dim("consolidate", dim, state.vector.len()) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(
+                "💾 [Consolidator] Skipping state from {}: {}",
+                state.agent_id, e
+            );
+            false
+        }
+    }
+}
+
+/// Average the latent vectors using Ebbinghaus exponential decay (or the
+/// configured power-law / linear curve), then L2-normalize. States whose
+/// dimension differs from `latent_dim` are skipped with a warning.
+fn weighted_summary(encoded: &[LatentState], config: &WorldModelConfig) -> Vec<f32> {
+    let mut summary_vector = vec![0.0f32; config.latent_dim];
+    let mut total_weight = 0.0f32;
+
+    // Base the "Now" timestamp off the most recent trajectory in the batch
+    let current_time = encoded.iter().map(|s| s.timestamp).max().unwrap_or(0);
+
+    for state in encoded.iter().filter(|s| fits_dim(s, config.latent_dim)) {
+        let weight = memory_weight(state.timestamp, state.surprise_score, current_time, config);
+        for (i, v) in state.vector.iter().enumerate() {
+            summary_vector[i] += v * weight;
+        }
+        total_weight += weight;
+    }
+
+    if total_weight > 0.0 {
+        for v in &mut summary_vector {
+            *v /= total_weight;
+        }
+    }
+
+    normalize(&mut summary_vector);
+    summary_vector
+}
+
+/// Most buckets a `StreamingSummary` holds before it coarsens them
+const MAX_STREAMING_BUCKETS: usize = 1024;
+/// Surprise scores are bucketed to this many steps between 0 and 1
+const SURPRISE_LEVELS: f32 = 256.0;
+
+/// Pre-summed vectors of the states that fell into one streaming bucket
+struct Bucket {
+    sum: Vec<f32>,
+    count: usize,
+    timestamp_sum: u128,
+    surprise_sum: f32,
+}
+
+/// Running form of `weighted_summary` that can be fed batch by batch.
+///
+/// Weights are relative to the newest timestamp, which is only known once the
+/// stream ends, so vectors are pre-summed into (timestamp, surprise) buckets
+/// and each bucket is weighted at its mean timestamp and surprise in `finish`.
+/// Buckets start one second wide; whenever there are more than
+/// `MAX_STREAMING_BUCKETS` their width doubles and neighbours merge, so memory
+/// stays bounded however long the stream is. Until then the result matches
+/// `weighted_summary`.
+struct StreamingSummary {
+    dim: usize,
+    buckets: HashMap<(u64, u32), Bucket>,
+    resolution: u64,
+    min_t: u64,
+    max_t: u64,
+}
+
+impl StreamingSummary {
+    fn new(dim: usize) -> Self {
+        StreamingSummary {
+            dim,
+            buckets: HashMap::new(),
+            resolution: 1,
+            min_t: u64::MAX,
+            max_t: 0,
+        }
+    }
+
+    fn extend(&mut self, states: &[LatentState]) {
+        let dim = self.dim;
+        for state in states.iter().filter(|s| fits_dim(s, dim)) {
+            self.min_t = self.min_t.min(state.timestamp);
+            self.max_t = self.max_t.max(state.timestamp);
+            let level = (state.surprise_score.clamp(0.0, 1.0) * SURPRISE_LEVELS).round() as u32;
+            let bucket = self
+                .buckets
+                .entry((state.timestamp / self.resolution, level))
+                .or_insert_with(|| Bucket {
+                    sum: vec![0.0; dim],
+                    count: 0,
+                    timestamp_sum: 0,
+                    surprise_sum: 0.0,
+                });
+            for (i, v) in state.vector.iter().enumerate() {
+                bucket.sum[i] += v;
+            }
+            bucket.count += 1;
+            bucket.timestamp_sum += state.timestamp as u128;
+            bucket.surprise_sum += state.surprise_score;
+
+            while self.buckets.len() > MAX_STREAMING_BUCKETS {
+                self.coarsen();
+            }
+        }
+    }
+
+    /// Double the bucket width, merging buckets that now share a key
+    fn coarsen(&mut self) {
+        self.resolution *= 2;
+        let old = std::mem::take(&mut self.buckets);
+        for ((slot, level), bucket) in old {
+            match self.buckets.entry((slot / 2, level)) {
+                Entry::Occupied(mut entry) => {
+                    let merged = entry.get_mut();
+                    for (m, v) in merged.sum.iter_mut().zip(&bucket.sum) {
+                        *m += v;
                     }
+                    merged.count += bucket.count;
+                    merged.timestamp_sum += bucket.timestamp_sum;
+                    merged.surprise_sum += bucket.surprise_sum;
                 }
+                Entry::Vacant(entry) => {
+                    entry.insert(bucket);
+                }
+            }
+        }
+    }
+
+    fn time_span_hours(&self) -> f32 {
+        self.max_t.saturating_sub(self.min_t) as f32 / 3600.0
+    }
 
-                LatentState::new(merged, agent_id, 0)
+    fn finish(self, config: &WorldModelConfig) -> Vec<f32> {
+        let mut summary_vector = vec![0.0f32; self.dim];
+        let mut total_weight = 0.0f32;
+
+        for bucket in self.buckets.values() {
+            let count = bucket.count as f32;
+            let timestamp = (bucket.timestamp_sum / bucket.count as u128) as u64;
+            let weight = memory_weight(timestamp, bucket.surprise_sum / count, self.max_t, config);
+            for (i, v) in bucket.sum.iter().enumerate() {
+                summary_vector[i] += v * weight;
+            }
+            total_weight += weight * count;
+        }
+
+        if total_weight > 0.0 {
+            for v in &mut summary_vector {
+                *v /= total_weight;
             }
-            _ => LatentState::new(vec![0.0; self.config.latent_dim], agent_id, 0),
         }
+
+        normalize(&mut summary_vector);
+        summary_vector
     }
+}
 
-    /// Get total trajectory count across all memories
-    pub fn total_trajectories(&self, agent_id: String) -> usize {
-        let store = self.consolidated.read();
-        store
-            .get(&agent_id)
-            .map(|mems| mems.iter().map(|m| m.num_trajectories).sum())
-            .unwrap_or(0)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_models_weight_old_memories_differently() {
+        // A day-old memory at the default decay rate
+        let exponential = retention_weight("exponential", 24.0, 0.1);
+        let power_law = retention_weight("power_law", 24.0, 0.1);
+        let linear = retention_weight("linear", 24.0, 0.1);
+
+        assert!((exponential - (-2.4f32).exp()).abs() < 1e-6);
+        assert!((power_law - 25.0f32.powf(-0.1)).abs() < 1e-6);
+        assert_eq!(linear, 0.0);
+        assert!(power_law > exponential && exponential > linear);
+
+        // Fresh memories are fully retained under every model
+        for model in ["exponential", "power_law", "linear"] {
+            assert_eq!(retention_weight(model, 0.0, 0.1), 1.0);
+        }
+    }
+
+    #[test]
+    fn surprise_slows_forgetting_under_each_model() {
+        for model in ["exponential", "power_law", "linear"] {
+            let boring = retention_weight(model, 5.0, 0.1 * (1.0 - 0.0f32).max(0.1));
+            let surprising = retention_weight(model, 5.0, 0.1 * (1.0 - 0.9f32).max(0.1));
+            assert!(surprising > boring, "{}", model);
+        }
+    }
+
+    #[test]
+    fn streaming_summary_matches_batch_summary() {
+        let config = WorldModelConfig::default();
+        let dim = config.latent_dim;
+        let states: Vec<LatentState> = (0..50)
+            .map(|i| {
+                let vector = (0..dim).map(|j| ((i * 7 + j) % 13) as f32 - 6.0).collect();
+                let mut state = LatentState::new_normalized(vector, "agent".to_string(), 0);
+                state.timestamp = 1_000_000 + (i as u64 / 5) * 1800;
+                state.surprise_score = if i % 4 == 0 { 0.8 } else { 0.0 };
+                state
+            })
+            .collect();
+
+        let expected = weighted_summary(&states, &config);
+
+        let mut streaming = StreamingSummary::new(dim);
+        for batch in states.chunks(7) {
+            streaming.extend(batch);
+        }
+        assert!((streaming.time_span_hours() - 4.5).abs() < 1e-6);
+        let actual = streaming.finish(&config);
+
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-5, "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    fn streaming_summary_stays_bounded_on_long_streams() {
+        let config = WorldModelConfig::default();
+        let dim = config.latent_dim;
+        // A slowly drifting direction, one state every 7 seconds for ~39 hours
+        let states: Vec<LatentState> = (0..20_000)
+            .map(|i| {
+                let angle = i as f32 / 20_000.0 * std::f32::consts::FRAC_PI_2;
+                let mut vector = vec![0.0; dim];
+                vector[0] = angle.cos();
+                vector[1] = angle.sin();
+                let mut state = LatentState::new_normalized(vector, "agent".to_string(), 0);
+                state.timestamp = 1_000_000 + i as u64 * 7;
+                state.surprise_score = (i % 5) as f32 * 0.2;
+                state
+            })
+            .collect();
+
+        let expected = weighted_summary(&states, &config);
+
+        let mut streaming = StreamingSummary::new(dim);
+        for batch in states.chunks(256) {
+            streaming.extend(batch);
+            assert!(streaming.buckets.len() <= MAX_STREAMING_BUCKETS);
+        }
+        let actual = streaming.finish(&config);
+
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-3, "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    #[ignore = "downloads the fastembed model"]
+    fn consolidate_streaming_matches_consolidate() {
+        let consolidator = MemoryConsolidator::new(None, "euclidean".to_string()).unwrap();
+        let trajectories: Vec<String> = (0..20)
+            .map(|i| {
+                serde_json::to_string(&vec![crate::TrajectoryPoint::new(
+                    1,
+                    "Thought".to_string(),
+                    format!("Step {} of the plan: check inventory bin {}", i, i % 3),
+                )])
+                .unwrap()
+            })
+            .collect();
+
+        let batch = consolidator.consolidate("agent".to_string(), trajectories.clone());
+        let streamed = consolidator.consolidate_streaming("agent".to_string(), trajectories, 3);
+
+        assert_eq!(streamed.num_trajectories, batch.num_trajectories);
+        for (a, e) in streamed.summary.vector.iter().zip(&batch.summary.vector) {
+            assert!((a - e).abs() < 1e-4, "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    fn invalid_config_is_an_error_not_a_panic() {
+        let config = WorldModelConfig {
+            latent_dim: 0,
+            ..WorldModelConfig::default()
+        };
+        assert!(MemoryConsolidator::new(Some(config.clone()), "euclidean".to_string()).is_err());
+        assert!(crate::worldmodel::GeometricEncoder::new(Some(config)).is_err());
+        assert!(MemoryConsolidator::new(None, "hyperbolic".to_string()).is_err());
+        assert!(check_merge_mode("geometric").is_ok());
+    }
+
+    #[test]
+    fn mismatched_states_are_skipped_not_mixed_in() {
+        let config = WorldModelConfig {
+            latent_dim: 4,
+            ..WorldModelConfig::default()
+        };
+        let good = LatentState::new_normalized(vec![1.0, 0.0, 0.0, 0.0], "a".to_string(), 0);
+        let bad = LatentState::new_normalized(vec![0.0, 1.0, 0.0, 0.0, 1.0], "a".to_string(), 0);
+
+        let summary = weighted_summary(&[good.clone(), bad.clone()], &config);
+        assert_eq!(summary, vec![1.0, 0.0, 0.0, 0.0]);
+
+        let mut streaming = StreamingSummary::new(config.latent_dim);
+        streaming.extend(&[bad, good]);
+        assert_eq!(streaming.finish(&config), vec![1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn geometric_merge_follows_the_sphere() {
+        let memory = |vector: Vec<f32>, n: usize| ConsolidatedMemory {
+            summary: LatentState::new_normalized(vector, "a".to_string(), 0),
+            num_trajectories: n,
+            time_span_hours: 0.0,
+        };
+        let mems = vec![memory(vec![1.0, 0.0], 3), memory(vec![0.0, 1.0], 1)];
+
+        let euclidean = merge_summaries(&mems, 2, "euclidean");
+        let geometric = merge_summaries(&mems, 2, "geometric");
+        let angle = |v: &[f32]| v[1].atan2(v[0]);
+
+        // Chord averaging pulls toward the heavier memory more than the arc does
+        assert!((angle(&euclidean) - (1.0f32 / 3.0).atan()).abs() < 1e-5);
+        assert!((angle(&geometric) - std::f32::consts::FRAC_PI_8).abs() < 1e-4);
+        for v in [&euclidean, &geometric] {
+            assert!((v[0].hypot(v[1]) - 1.0).abs() < 1e-5);
+        }
     }
 }
@@ -4,10 +4,17 @@
 
 use super::{LatentState, Prediction, WorldModelConfig};
 use pyo3::prelude::*;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use rand_distr::{Distribution, Normal};
 use tracing::info;
 
+/// Std-dev of the per-step noise injected into stochastic rollouts
+const ROLLOUT_NOISE_STD: f32 = 0.05;
+/// How strongly mean per-step variance discounts rollout confidence
+const VARIANCE_PENALTY: f32 = 100.0;
+/// Confidence of a rollout with zero variance across samples
+const BASE_CONFIDENCE: f32 = 0.85;
+
 /// Diffusion-based predictor
 #[pyclass]
 pub struct DiffusionPredictor {
@@ -103,4 +110,141 @@ impl DiffusionPredictor {
 
         Prediction::new(vec![best_guess], 0.85, vec!["action".to_string()])
     }
+
+    /// Noise-free multi-step rollout (autocorrelation decay, renormalized each step)
+    pub fn deterministic_rollout(&self, initial: &LatentState, steps: usize) -> Prediction {
+        let states = self.rollout_path(initial, steps, None::<&mut rand::rngs::ThreadRng>);
+        Prediction::new(states, BASE_CONFIDENCE, vec!["rollout".to_string(); steps])
+    }
+
+    /// Draw `n_samples` rollouts of `steps` steps and the per-step variance across them.
+    ///
+    /// The first sample is the deterministic rollout; the rest add Gaussian noise
+    /// at every step. Each prediction's confidence shrinks as the mean variance grows.
+    pub fn sample_rollouts(
+        &self,
+        initial: &LatentState,
+        steps: usize,
+        n_samples: usize,
+    ) -> (Vec<Prediction>, Vec<f32>) {
+        let mut rng = thread_rng();
+        let paths: Vec<Vec<LatentState>> = (0..n_samples)
+            .map(|i| {
+                if i == 0 {
+                    self.rollout_path(initial, steps, None::<&mut rand::rngs::ThreadRng>)
+                } else {
+                    self.rollout_path(initial, steps, Some(&mut rng))
+                }
+            })
+            .collect();
+
+        let variance = step_variance(&paths, steps);
+        let mean_variance = if variance.is_empty() {
+            0.0
+        } else {
+            variance.iter().sum::<f32>() / variance.len() as f32
+        };
+        let confidence = BASE_CONFIDENCE / (1.0 + VARIANCE_PENALTY * mean_variance);
+
+        let predictions = paths
+            .into_iter()
+            .map(|states| Prediction::new(states, confidence, vec!["rollout".to_string(); steps]))
+            .collect();
+        (predictions, variance)
+    }
+}
+
+impl DiffusionPredictor {
+    fn rollout_path<R: Rng>(
+        &self,
+        initial: &LatentState,
+        steps: usize,
+        mut noise_rng: Option<&mut R>,
+    ) -> Vec<LatentState> {
+        let normal = Normal::new(0.0, ROLLOUT_NOISE_STD).ok();
+        let mut current = initial.vector.clone();
+        let mut states = Vec::with_capacity(steps);
+
+        for step in 0..steps {
+            for v in current.iter_mut() {
+                *v *= 0.95;
+                if let (Some(rng), Some(normal)) = (noise_rng.as_deref_mut(), normal.as_ref()) {
+                    *v += normal.sample(rng);
+                }
+            }
+            let norm: f32 = current.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for v in current.iter_mut() {
+                    *v /= norm;
+                }
+            }
+            states.push(LatentState::new(
+                current.clone(),
+                initial.agent_id.clone(),
+                initial.step + step as u32 + 1,
+            ));
+        }
+        states
+    }
+}
+
+/// Mean (over dimensions) variance across sample paths, for each step
+fn step_variance(paths: &[Vec<LatentState>], steps: usize) -> Vec<f32> {
+    let n = paths.len();
+    (0..steps)
+        .map(|t| {
+            if n < 2 {
+                return 0.0;
+            }
+            let dim = paths[0][t].vector.len();
+            if dim == 0 {
+                return 0.0;
+            }
+            let total: f32 = (0..dim)
+                .map(|d| {
+                    let mean = paths.iter().map(|p| p[t].vector[d]).sum::<f32>() / n as f32;
+                    paths
+                        .iter()
+                        .map(|p| (p[t].vector[d] - mean).powi(2))
+                        .sum::<f32>()
+                        / n as f32
+                })
+                .sum();
+            total / dim as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn initial() -> LatentState {
+        LatentState::new(vec![0.6, 0.8, 0.0, 0.0], "a".to_string(), 0)
+    }
+
+    #[test]
+    fn test_single_sample_matches_deterministic_rollout() {
+        let predictor = DiffusionPredictor::new(None);
+        let expected = predictor.deterministic_rollout(&initial(), 5);
+        let (samples, variance) = predictor.sample_rollouts(&initial(), 5, 1);
+
+        assert_eq!(samples.len(), 1);
+        let got: Vec<&Vec<f32>> = samples[0].future_states.iter().map(|s| &s.vector).collect();
+        let want: Vec<&Vec<f32>> = expected.future_states.iter().map(|s| &s.vector).collect();
+        assert_eq!(got, want);
+        assert_eq!(variance, vec![0.0; 5]);
+        assert_eq!(samples[0].confidence, expected.confidence);
+    }
+
+    #[test]
+    fn test_multiple_samples_have_variance_and_lower_confidence() {
+        let predictor = DiffusionPredictor::new(None);
+        let (samples, variance) = predictor.sample_rollouts(&initial(), 5, 16);
+
+        assert_eq!(samples.len(), 16);
+        assert_eq!(variance.len(), 5);
+        assert!(variance.iter().all(|v| *v > 0.0));
+        assert!(samples[0].confidence < BASE_CONFIDENCE);
+    }
 }
@@ -3,9 +3,9 @@
 //! Predicts next latent state given current state and action.
 //! Upgraded to leverage local ONNX/safetensor-capable ML graphs.
 
-use super::{LatentState, Prediction, WorldModelConfig};
+use super::{check_dim, normalize_vector, LatentResult, LatentState, Prediction, WorldModelConfig};
 use pyo3::prelude::*;
-use tracing::info;
+use tracing::{info, warn};
 use candle_core::{Device, Tensor};
 use candle_nn::{Linear, Module};
 
@@ -49,8 +49,12 @@ impl AutoregressivePredictor {
         })
     }
 
-    /// Predict next latent state given current state and action
+    /// Predict next latent state given current state and action.
+    /// Inputs of the wrong dimension are zero-padded or truncated with a warning.
     pub fn predict_next(&self, current: &LatentState, action_encoding: Vec<f32>) -> LatentState {
+        if let Err(e) = self.check_inputs(current, &action_encoding) {
+            warn!("[Dynamics] {}", e);
+        }
         let device = Device::Cpu;
         let fallback_vec = current.vector.clone();
 
@@ -77,16 +81,20 @@ impl AutoregressivePredictor {
         };
 
         // Normalize state output
-        let norm: f32 = next_vector.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for v in &mut next_vector {
-                *v /= norm;
-            }
-        }
+        normalize_vector("Dynamics", &mut next_vector);
 
         LatentState::new(next_vector, current.agent_id.clone(), current.step + 1)
     }
 
+    /// Like `predict_next`, but raises ValueError on mismatched dimensions
+    pub fn try_predict_next(
+        &self,
+        current: &LatentState,
+        action_encoding: Vec<f32>,
+    ) -> PyResult<LatentState> {
+        Ok(self.checked_predict_next(current, action_encoding)?)
+    }
+
     /// Predict multiple steps into the future
     pub fn predict_sequence(
         &self,
@@ -103,9 +111,86 @@ impl AutoregressivePredictor {
             action_sequence.push(format!("action_{}", i));
         }
 
-        let confidence = 1.0 / (1.0 + 0.1 * future_states.len() as f32);
+        let mut prediction = Prediction::new(future_states, 0.0, action_sequence);
+        prediction.recompute_confidence();
+        prediction
+    }
+
+    /// Roll many start states forward under the same action, `steps` times.
+    ///
+    /// Each step runs one batched forward pass over all states, so the cost of
+    /// the projection is shared across agents. Returns one `Prediction` per
+    /// input state, matching what `rollout` would produce for it.
+    pub fn predict_batch(
+        &self,
+        states: Vec<LatentState>,
+        action_encoding: Vec<f32>,
+        steps: usize,
+    ) -> Vec<Prediction> {
+        let dim = self.config.latent_dim;
+        let n = states.len();
+        let mut current = states;
+        let mut futures: Vec<Vec<LatentState>> = vec![Vec::with_capacity(steps); n];
+        if n == 0 {
+            return Vec::new();
+        }
+
+        for state in &current {
+            if let Err(e) = self.check_inputs(state, &action_encoding) {
+                warn!("[Dynamics] {}", e);
+            }
+        }
+        let mut safe_action = action_encoding;
+        safe_action.resize(dim, 0.0);
+        let actions: Vec<f32> = safe_action.iter().copied().cycle().take(n * dim).collect();
+
+        for _ in 0..steps {
+            let process = || -> candle_core::Result<Vec<Vec<f32>>> {
+                let device = Device::Cpu;
+                let mut flat = Vec::with_capacity(n * dim);
+                for state in &current {
+                    let mut v = state.vector.clone();
+                    v.resize(dim, 0.0);
+                    flat.extend(v);
+                }
+                let state_tensor = Tensor::from_vec(flat, (n, dim), &device)?;
+                let action_tensor = Tensor::from_vec(actions.clone(), (n, dim), &device)?;
+                let input_tensor = Tensor::cat(&[&state_tensor, &action_tensor], 1)?;
+                self.weights.forward(&input_tensor)?.to_vec2::<f32>()
+            };
+
+            let outputs = match process() {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::error!("Candle tensor error predicting batch: {}", e);
+                    current.iter().map(|s| s.vector.clone()).collect()
+                }
+            };
+
+            current = current
+                .iter()
+                .zip(outputs)
+                .map(|(state, mut next_vector)| {
+                    // Normalize state output
+                    normalize_vector("Dynamics", &mut next_vector);
+                    LatentState::new(next_vector, state.agent_id.clone(), state.step + 1)
+                })
+                .collect();
+            for (future, state) in futures.iter_mut().zip(&current) {
+                future.push(state.clone());
+            }
+        }
 
-        Prediction::new(future_states, confidence, action_sequence)
+        let action_sequence: Vec<String> = (0..steps).map(|i| format!("action_{}", i)).collect();
+        futures
+            .into_iter()
+            .map(|future_states| {
+                let mut prediction =
+                    Prediction::new(future_states, 0.0, action_sequence.clone());
+                prediction.recompute_confidence();
+                prediction
+            })
+            .collect()
     }
 
     /// Rollout a single action for N steps
@@ -119,3 +204,57 @@ impl AutoregressivePredictor {
         self.predict_sequence(initial, action_encodings)
     }
 }
+
+impl AutoregressivePredictor {
+    /// Err unless both the state and action match `config.latent_dim`
+    fn check_inputs(&self, current: &LatentState, action_encoding: &[f32]) -> LatentResult<()> {
+        check_dim("predict_next state", self.config.latent_dim, current.vector.len())?;
+        check_dim("predict_next action", self.config.latent_dim, action_encoding.len())
+    }
+
+    /// `predict_next` that refuses mismatched inputs instead of resizing them
+    pub fn checked_predict_next(
+        &self,
+        current: &LatentState,
+        action_encoding: Vec<f32>,
+    ) -> LatentResult<LatentState> {
+        self.check_inputs(current, &action_encoding)?;
+        Ok(self.predict_next(current, action_encoding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worldmodel::LatentError;
+
+    #[test]
+    fn test_checked_predict_next_rejects_mismatched_dims() {
+        let config = WorldModelConfig {
+            latent_dim: 8,
+            ..WorldModelConfig::default()
+        };
+        let predictor = AutoregressivePredictor::new(Some(config)).unwrap();
+        let state = LatentState::new_normalized(vec![1.0; 8], "a".to_string(), 0);
+
+        let next = predictor.checked_predict_next(&state, vec![0.5; 8]).unwrap();
+        assert_eq!(next.vector.len(), 8);
+        assert_eq!(next.step, 1);
+
+        let short_state = LatentState::new(vec![1.0; 5], "a".to_string(), 0);
+        assert_eq!(
+            predictor
+                .checked_predict_next(&short_state, vec![0.5; 8])
+                .unwrap_err(),
+            LatentError::DimensionMismatch {
+                context: "predict_next state".to_string(),
+                expected: 8,
+                actual: 5,
+            }
+        );
+        assert!(matches!(
+            predictor.checked_predict_next(&state, vec![0.5; 12]),
+            Err(LatentError::DimensionMismatch { actual: 12, .. })
+        ));
+    }
+}
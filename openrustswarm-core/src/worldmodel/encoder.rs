@@ -3,10 +3,10 @@
 //! Converts trajectory context AND goals into compact latent representations.
 //! Updated for context-aware language conditioning with native ONNX semantic embeddings.
 
-use super::{LatentState, WorldModelConfig};
+use super::{normalize_vector, LatentState, WorldModelConfig};
 use crate::TrajectoryPoint;
 use pyo3::prelude::*;
-use tracing::info;
+use tracing::{info, warn};
 use fastembed::{TextEmbedding, InitOptions, EmbeddingModel};
 
 use parking_lot::RwLock;
@@ -24,6 +24,7 @@ impl LatentEncoder {
     #[pyo3(signature = (config = None))]
     pub fn new(config: Option<WorldModelConfig>) -> pyo3::PyResult<Self> {
         let cfg = config.unwrap_or_default();
+        cfg.validate()?;
         info!("[Encoder] Initializing fastembed ONNX runtime natively...");
 
         let model = TextEmbedding::try_new(InitOptions::new(EmbeddingModel::BGEBaseENV15))
@@ -45,29 +46,7 @@ impl LatentEncoder {
             serde_json::from_str(&trajectory_json).unwrap_or_default();
 
         let mut vector = vec![0.0f32; self.config.latent_dim];
-        let mut texts = Vec::new();
-        let mut weights = Vec::new();
-
-        // 1. Encode Trajectory Context (Past)
-        let window_size = self.config.context_window.min(trajectory.len());
-        let recent: Vec<&TrajectoryPoint> = trajectory.iter().rev().take(window_size).collect();
-
-        for (i, point) in recent.iter().enumerate() {
-            let weight = 0.6 / (i as f32 + 1.0); // 60% weight to history
-            texts.push(format!("Action: {} Thought: {}", point.action, point.thought));
-            weights.push(weight);
-        }
-
-        // 2. Encode Goal Context (Future Intent)
-        if !goal.is_empty() {
-            texts.push(format!("Goal: {}", goal));
-            weights.push(0.4); // 40% weight to goal
-        }
-
-        if texts.is_empty() {
-             texts.push("empty".to_string());
-             weights.push(1.0);
-        }
+        let (texts, weights) = self.context_texts(&trajectory, &goal);
 
         let mut model_lock = self.model.write();
         if let Ok(embeddings) = model_lock.embed(texts, None) {
@@ -84,6 +63,49 @@ impl LatentEncoder {
         LatentState::new(vector, agent_id, trajectory.len() as u32)
     }
 
+    /// Encode many trajectories with a single embedding call
+    pub fn encode_batch(&self, trajectory_jsons: Vec<String>, agent_id: String) -> Vec<LatentState> {
+        let trajectories: Vec<Vec<TrajectoryPoint>> = trajectory_jsons
+            .iter()
+            .map(|tj| serde_json::from_str(tj).unwrap_or_default())
+            .collect();
+
+        // Flatten every trajectory's texts into one embedding batch
+        let mut all_texts = Vec::new();
+        let mut spans = Vec::with_capacity(trajectories.len());
+        for trajectory in &trajectories {
+            let (texts, weights) = self.context_texts(trajectory, "");
+            spans.push((all_texts.len(), weights));
+            all_texts.extend(texts);
+        }
+
+        let embeddings = match self.model.write().embed(all_texts, None) {
+            Ok(embeddings) => embeddings,
+            Err(e) => {
+                warn!(
+                    "[Encoder] encode_batch embedding failed, returning zero vectors: {}",
+                    e
+                );
+                Vec::new()
+            }
+        };
+
+        trajectories
+            .iter()
+            .zip(spans)
+            .map(|(trajectory, (start, weights))| {
+                let mut vector = vec![0.0f32; self.config.latent_dim];
+                for (emb, weight) in embeddings.iter().skip(start).zip(weights.iter()) {
+                    for j in 0..self.config.latent_dim.min(emb.len()) {
+                        vector[j] += emb[j] * weight;
+                    }
+                }
+                self.normalize(&mut vector);
+                LatentState::new(vector, agent_id.clone(), trajectory.len() as u32)
+            })
+            .collect()
+    }
+
     /// Encode just the trajectory (backward handling)
     pub fn encode(&self, trajectory_json: String, agent_id: String) -> LatentState {
         self.encode_context(trajectory_json, String::new(), agent_id)
@@ -107,6 +129,35 @@ impl LatentEncoder {
         vector
     }
 
+    /// Cosine similarity between two texts, embedded together in one call.
+    /// Returns 0.0 if embedding fails.
+    pub fn text_similarity(&self, a: String, b: String) -> f32 {
+        let texts: Vec<String> = [a, b]
+            .into_iter()
+            .map(|t| if t.is_empty() { "empty".to_string() } else { t })
+            .collect();
+
+        let embeddings = match self.model.write().embed(texts, None) {
+            Ok(embeddings) if embeddings.len() == 2 => embeddings,
+            Ok(_) => return 0.0,
+            Err(e) => {
+                warn!("[Encoder] text_similarity embedding failed: {}", e);
+                return 0.0;
+            }
+        };
+
+        let mut vectors: Vec<Vec<f32>> = embeddings
+            .into_iter()
+            .map(|mut emb| {
+                emb.truncate(self.config.latent_dim);
+                self.normalize(&mut emb);
+                emb
+            })
+            .collect();
+        let (va, vb) = (vectors.remove(0), vectors.remove(0));
+        va.iter().zip(&vb).map(|(x, y)| x * y).sum()
+    }
+
     /// Decode latent state back to summary
     pub fn decode(&self, state: &LatentState) -> String {
         let magnitude: f32 =
@@ -122,12 +173,54 @@ impl LatentEncoder {
 }
 
 impl LatentEncoder {
-    fn normalize(&self, vector: &mut Vec<f32>) {
-        let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for v in vector.iter_mut() {
-                *v /= norm;
-            }
+    /// Texts to embed for a trajectory + goal, with their blend weights
+    fn context_texts(&self, trajectory: &[TrajectoryPoint], goal: &str) -> (Vec<String>, Vec<f32>) {
+        let mut texts = Vec::new();
+        let mut weights = Vec::new();
+
+        // 1. Encode Trajectory Context (Past)
+        let window_size = self.config.context_window.min(trajectory.len());
+        let recent: Vec<&TrajectoryPoint> = trajectory.iter().rev().take(window_size).collect();
+
+        for (i, point) in recent.iter().enumerate() {
+            let weight = 0.6 / (i as f32 + 1.0); // 60% weight to history
+            texts.push(format!("Action: {} Thought: {}", point.action, point.thought));
+            weights.push(weight);
         }
+
+        // 2. Encode Goal Context (Future Intent)
+        if !goal.is_empty() {
+            texts.push(format!("Goal: {}", goal));
+            weights.push(0.4); // 40% weight to goal
+        }
+
+        if texts.is_empty() {
+             texts.push("empty".to_string());
+             weights.push(1.0);
+        }
+
+        (texts, weights)
+    }
+
+    fn normalize(&self, vector: &mut Vec<f32>) {
+        normalize_vector("LatentEncoder", vector);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "downloads the fastembed model"]
+    fn text_similarity_ranks_identical_above_unrelated() {
+        let encoder = LatentEncoder::new(None).unwrap();
+        let text = "Search for the current NVIDIA stock price".to_string();
+
+        let same = encoder.text_similarity(text.clone(), text.clone());
+        let unrelated =
+            encoder.text_similarity(text, "A recipe for slow-cooked lentil soup".to_string());
+        assert!((same - 1.0).abs() < 1e-3, "{}", same);
+        assert!(unrelated < same - 0.2, "{} vs {}", unrelated, same);
     }
 }
@@ -3,7 +3,7 @@
 //! Implements topological constraints for latent consistency.
 //! Enforces temporal consistency: similar states must be close in latent space.
 
-use super::{LatentEncoder, LatentState, WorldModelConfig};
+use super::{check_dim, LatentEncoder, LatentState, WorldModelConfig};
 use pyo3::prelude::*;
 use tracing::info;
 
@@ -18,13 +18,13 @@ pub struct GeometricEncoder {
 impl GeometricEncoder {
     #[new]
     #[pyo3(signature = (config = None))]
-    pub fn new(config: Option<WorldModelConfig>) -> Self {
-        let cfg = config.unwrap_or_default();
+    pub fn new(config: Option<WorldModelConfig>) -> PyResult<Self> {
+        let base_encoder = LatentEncoder::new(config)?;
         info!("[GeometricEncoder] Initialized");
-        GeometricEncoder {
-            base_encoder: LatentEncoder::new(Some(cfg)).unwrap(),
+        Ok(GeometricEncoder {
+            base_encoder,
             regularization_strength: 0.1,
-        }
+        })
     }
 
     /// Encode with geometric regularization
@@ -50,6 +50,179 @@ impl GeometricEncoder {
         self.regularize(&mut vec);
         vec
     }
+
+    /// Great-circle (angular) distance between two states on the unit hypersphere
+    pub fn geodesic_distance(&self, a: &LatentState, b: &LatentState) -> PyResult<f32> {
+        check_dim("geodesic_distance", a.vector.len(), b.vector.len())?;
+        Ok(sphere_distance(&a.vector, &b.vector))
+    }
+
+    /// Spherical interpolation between two states; `t` is clamped to [0, 1].
+    /// The result keeps `a`'s agent and step.
+    pub fn interpolate(&self, a: &LatentState, b: &LatentState, t: f32) -> PyResult<LatentState> {
+        check_dim("interpolate", a.vector.len(), b.vector.len())?;
+        Ok(LatentState::new(
+            slerp(&a.vector, &b.vector, t),
+            a.agent_id.clone(),
+            a.step,
+        ))
+    }
+
+    /// Weighted Fréchet mean of `states` on the unit hypersphere. `weights`
+    /// defaults to uniform. The result keeps the first state's agent and step.
+    #[pyo3(signature = (states, weights = None))]
+    pub fn manifold_mean(
+        &self,
+        states: Vec<LatentState>,
+        weights: Option<Vec<f32>>,
+    ) -> PyResult<LatentState> {
+        let Some(first) = states.first() else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "manifold_mean needs at least one state",
+            ));
+        };
+        for state in &states[1..] {
+            check_dim("manifold_mean", first.vector.len(), state.vector.len())?;
+        }
+        let weights = weights.unwrap_or_else(|| vec![1.0; states.len()]);
+        if weights.len() != states.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Expected {} weights, got {}",
+                states.len(),
+                weights.len()
+            )));
+        }
+
+        let vectors: Vec<&[f32]> = states.iter().map(|s| s.vector.as_slice()).collect();
+        Ok(LatentState::new(
+            sphere_mean(&vectors, &weights),
+            first.agent_id.clone(),
+            first.step,
+        ))
+    }
+}
+
+fn normalized(v: &[f32]) -> Option<Vec<f32>> {
+    let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    (norm > 0.0).then(|| v.iter().map(|x| x / norm).collect())
+}
+
+/// Angle between `a` and `b` after projection onto the unit hypersphere.
+/// Uses `2·atan2(|a−b|, |a+b|)`, which stays exact near 0 and π.
+/// Falls back to Euclidean distance when either vector is zero.
+pub fn sphere_distance(a: &[f32], b: &[f32]) -> f32 {
+    let (Some(a), Some(b)) = (normalized(a), normalized(b)) else {
+        return a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f32>()
+            .sqrt();
+    };
+    let diff: f32 = a
+        .iter()
+        .zip(&b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f32>()
+        .sqrt();
+    let sum: f32 = a
+        .iter()
+        .zip(&b)
+        .map(|(x, y)| (x + y).powi(2))
+        .sum::<f32>()
+        .sqrt();
+    2.0 * diff.atan2(sum)
+}
+
+/// Spherical linear interpolation between the unit projections of `a` and `b`.
+pub fn slerp(a: &[f32], b: &[f32], t: f32) -> Vec<f32> {
+    let (Some(a), Some(b)) = (normalized(a), normalized(b)) else {
+        return a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| x + (y - x) * t.clamp(0.0, 1.0))
+            .collect();
+    };
+    if t <= 0.0 {
+        return a;
+    }
+    if t >= 1.0 {
+        return b;
+    }
+
+    let omega = sphere_distance(&a, &b);
+    let sin_omega = omega.sin();
+    let mixed: Vec<f32> = if sin_omega.abs() < 1e-6 {
+        // Nearly parallel (or antipodal): fall back to a normalized lerp
+        a.iter().zip(&b).map(|(x, y)| x + (y - x) * t).collect()
+    } else {
+        let wa = ((1.0 - t) * omega).sin() / sin_omega;
+        let wb = (t * omega).sin() / sin_omega;
+        a.iter().zip(&b).map(|(x, y)| wa * x + wb * y).collect()
+    };
+    normalized(&mixed).unwrap_or(mixed)
+}
+
+/// Weighted Fréchet (Karcher) mean of the unit projections of `vectors`.
+///
+/// Starts from the normalized Euclidean mean and refines it by averaging in
+/// the tangent space (log map) and stepping back onto the sphere (exp map).
+/// Zero vectors and non-positive weights are ignored; with nothing left the
+/// result is all zeros.
+pub fn sphere_mean(vectors: &[&[f32]], weights: &[f32]) -> Vec<f32> {
+    let dim = vectors.first().map_or(0, |v| v.len());
+    let points: Vec<(Vec<f32>, f32)> = vectors
+        .iter()
+        .zip(weights)
+        .filter(|(_, w)| **w > 0.0)
+        .filter_map(|(v, w)| normalized(v).map(|v| (v, *w)))
+        .collect();
+    let total: f32 = points.iter().map(|(_, w)| w).sum();
+    if points.is_empty() {
+        return vec![0.0; dim];
+    }
+
+    let mut euclid = vec![0.0f32; dim];
+    for (p, w) in &points {
+        for (m, x) in euclid.iter_mut().zip(p) {
+            *m += x * w;
+        }
+    }
+    let mut mean = normalized(&euclid).unwrap_or_else(|| points[0].0.clone());
+
+    for _ in 0..64 {
+        // Weighted average of log maps at the current estimate
+        let mut tangent = vec![0.0f32; dim];
+        for (p, w) in &points {
+            let cos = mean
+                .iter()
+                .zip(p)
+                .map(|(a, b)| a * b)
+                .sum::<f32>()
+                .clamp(-1.0, 1.0);
+            let theta = sphere_distance(&mean, p);
+            if theta < 1e-7 {
+                continue;
+            }
+            let scale = theta / theta.sin() * w / total;
+            for ((t, x), m) in tangent.iter_mut().zip(p).zip(&mean) {
+                *t += scale * (x - cos * m);
+            }
+        }
+
+        let step = tangent.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if step < 1e-7 {
+            break;
+        }
+        // Exp map back onto the sphere
+        let moved: Vec<f32> = mean
+            .iter()
+            .zip(&tangent)
+            .map(|(m, t)| step.cos() * m + step.sin() * t / step)
+            .collect();
+        mean = normalized(&moved).unwrap_or(moved);
+    }
+    mean
 }
 
 impl GeometricEncoder {
@@ -77,3 +250,44 @@ impl GeometricEncoder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_to_self_is_zero() {
+        let v = vec![0.3, -0.5, 0.8, 0.1];
+        assert_eq!(sphere_distance(&v, &v), 0.0);
+
+        let orthogonal = sphere_distance(&[1.0, 0.0], &[0.0, 1.0]);
+        assert!((orthogonal - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interpolate_endpoints_and_midpoint() {
+        let a = vec![1.0, 0.0, 0.0];
+        let b = vec![0.0, 1.0, 0.0];
+        assert_eq!(slerp(&a, &b, 0.0), a);
+        assert_eq!(slerp(&a, &b, 1.0), b);
+
+        let mid = slerp(&a, &b, 0.5);
+        let h = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((mid[0] - h).abs() < 1e-6 && (mid[1] - h).abs() < 1e-6);
+        assert!((sphere_distance(&a, &mid) - sphere_distance(&mid, &b)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sphere_mean_is_geodesic_weighted() {
+        // 3:1 weights between two orthogonal directions: the manifold mean sits
+        // a quarter of the arc away from the heavier point
+        let a: &[f32] = &[1.0, 0.0];
+        let b: &[f32] = &[0.0, 1.0];
+        let mean = sphere_mean(&[a, b], &[3.0, 1.0]);
+        let angle = mean[1].atan2(mean[0]);
+        assert!((angle - std::f32::consts::FRAC_PI_8).abs() < 1e-4);
+
+        assert_eq!(sphere_mean(&[a], &[1.0]), vec![1.0, 0.0]);
+        assert_eq!(sphere_mean(&[&[0.0, 0.0]], &[1.0]), vec![0.0, 0.0]);
+    }
+}
@@ -27,6 +27,88 @@ pub use encoder::LatentEncoder;
 pub use geometric::GeometricEncoder;
 pub use planner::PlanningEngine;
 
+/// Result type for latent-space operations
+pub type LatentResult<T> = Result<T, LatentError>;
+
+/// Latent-space error types
+#[derive(Debug, Clone, PartialEq)]
+pub enum LatentError {
+    /// A vector's length differs from the dimension an operation requires
+    DimensionMismatch {
+        context: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl std::fmt::Display for LatentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LatentError::DimensionMismatch {
+                context,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Dimension mismatch in {}: expected {}, got {}",
+                context, expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LatentError {}
+
+impl From<LatentError> for PyErr {
+    fn from(err: LatentError) -> PyErr {
+        pyo3::exceptions::PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Err unless `actual == expected`
+pub fn check_dim(context: &str, expected: usize, actual: usize) -> LatentResult<()> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(LatentError::DimensionMismatch {
+            context: context.to_string(),
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Replace NaN/inf components with 0.0 in place, logging a warning naming
+/// `context` when any were found. Returns the number of replaced components.
+pub fn sanitize_vector(context: &str, vector: &mut [f32]) -> usize {
+    let mut replaced = 0;
+    for v in vector.iter_mut().filter(|v| !v.is_finite()) {
+        *v = 0.0;
+        replaced += 1;
+    }
+    if replaced > 0 {
+        tracing::warn!(
+            "[{}] Replaced {} non-finite value(s) in a {}-dim vector",
+            context,
+            replaced,
+            vector.len()
+        );
+    }
+    replaced
+}
+
+/// Sanitize, then scale to unit L2 norm in place. Empty and all-zero vectors
+/// are left unchanged, as are vectors whose norm overflows.
+pub fn normalize_vector(context: &str, vector: &mut [f32]) {
+    sanitize_vector(context, vector);
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 && norm.is_finite() {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
 /// Latent state representation (compressed context)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[pyclass]
@@ -46,8 +128,10 @@ pub struct LatentState {
 
 #[pymethods]
 impl LatentState {
+    /// NaN/inf components are replaced with 0.0 (with a warning)
     #[new]
-    pub fn new(vector: Vec<f32>, agent_id: String, step: u32) -> Self {
+    pub fn new(mut vector: Vec<f32>, agent_id: String, step: u32) -> Self {
+        sanitize_vector("LatentState", &mut vector);
         LatentState {
             vector,
             timestamp: std::time::SystemTime::now()
@@ -60,25 +144,40 @@ impl LatentState {
         }
     }
 
-    pub fn similarity(&self, other: &LatentState) -> f32 {
-        if self.vector.len() != other.vector.len() {
-            return 0.0;
-        }
+    /// Construct a state whose vector is L2-normalized, ready for `compute_surprise`
+    #[staticmethod]
+    pub fn new_normalized(vector: Vec<f32>, agent_id: String, step: u32) -> Self {
+        let mut state = LatentState::new(vector, agent_id, step);
+        state.normalize();
+        state
+    }
 
-        let dot: f32 = self
-            .vector
-            .iter()
-            .zip(&other.vector)
-            .map(|(a, b)| a * b)
-            .sum();
-        let norm_a: f32 = self.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_b: f32 = other.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    /// Scale the vector to unit L2 norm in place (empty and zero vectors are
+    /// left unchanged; NaN/inf components are zeroed first)
+    pub fn normalize(&mut self) {
+        normalize_vector("LatentState", &mut self.vector);
+    }
 
-        if norm_a == 0.0 || norm_b == 0.0 {
-            return 0.0;
+    /// True when the vector's L2 norm is within `tol` of 1.0, or the vector is all zeros
+    pub fn is_normalized(&self, tol: f32) -> bool {
+        let norm: f32 = self.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        (norm - 1.0).abs() <= tol || norm == 0.0
+    }
+
+    /// Cosine similarity; mismatched dimensions log a warning and score 0.0
+    pub fn similarity(&self, other: &LatentState) -> f32 {
+        match self.checked_similarity(other) {
+            Ok(sim) => sim,
+            Err(e) => {
+                tracing::warn!("[LatentState] {}", e);
+                0.0
+            }
         }
+    }
 
-        dot / (norm_a * norm_b)
+    /// Cosine similarity that raises ValueError on mismatched dimensions
+    pub fn try_similarity(&self, other: &LatentState) -> PyResult<f32> {
+        Ok(self.checked_similarity(other)?)
     }
 
     pub fn __repr__(&self) -> String {
@@ -93,17 +192,18 @@ impl LatentState {
 
     /// Calculate mathematically how "surprised" this state is compared to what was predicted.
     /// Uses Cosine Similarity. 0.0 = Totally boring/predictable. 1.0 = Complete anomaly.
+    ///
+    /// Both states must be L2-normalized (see `normalize` / `new_normalized`);
+    /// debug builds assert this.
     pub fn compute_surprise(&mut self, predicted_prior: &LatentState) {
         // Assertion: ensure both vectors are L2-normalized before computing cosine similarity
-        let norm_self: f32 = self.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_pred: f32 = predicted_prior.vector.iter().map(|x| x * x).sum::<f32>().sqrt();
         debug_assert!(
-            (norm_self - 1.0).abs() < 1e-3 || norm_self == 0.0,
-            "Real state not properly L2-normalized! Norm: {}", norm_self
+            self.is_normalized(1e-3),
+            "Real state not properly L2-normalized! Call normalize() first"
         );
         debug_assert!(
-            (norm_pred - 1.0).abs() < 1e-3 || norm_pred == 0.0,
-            "Predicted state not properly L2-normalized! Norm: {}", norm_pred
+            predicted_prior.is_normalized(1e-3),
+            "Predicted state not properly L2-normalized! Call normalize() first"
         );
 
         let sim = self.similarity(predicted_prior);
@@ -118,6 +218,31 @@ impl LatentState {
     }
 }
 
+impl LatentState {
+    /// Cosine similarity, or `DimensionMismatch` if the vectors differ in length.
+    /// Empty or all-zero vectors score 0.0; non-finite components count as 0.0.
+    pub fn checked_similarity(&self, other: &LatentState) -> LatentResult<f32> {
+        check_dim("similarity", self.vector.len(), other.vector.len())?;
+
+        let finite = |x: &f32| if x.is_finite() { *x } else { 0.0 };
+        let dot: f32 = self
+            .vector
+            .iter()
+            .zip(&other.vector)
+            .map(|(a, b)| finite(a) * finite(b))
+            .sum();
+        let norm = |v: &[f32]| v.iter().map(|x| finite(x).powi(2)).sum::<f32>().sqrt();
+        let (norm_a, norm_b) = (norm(&self.vector), norm(&other.vector));
+
+        if norm_a == 0.0 || norm_b == 0.0 {
+            return Ok(0.0);
+        }
+
+        let sim = dot / (norm_a * norm_b);
+        Ok(if sim.is_finite() { sim.clamp(-1.0, 1.0) } else { 0.0 })
+    }
+}
+
 /// World model configuration
 #[derive(Clone, Debug)]
 #[pyclass]
@@ -135,8 +260,18 @@ pub struct WorldModelConfig {
     #[pyo3(get, set)]
     pub ebbinghaus_decay_rate: f32,
 
+    /// Shape of the forgetting curve: "exponential" | "power_law" | "linear"
+    /// (set after construction; defaults to "exponential")
+    #[pyo3(get, set)]
+    pub retention_model: String,
+
     #[pyo3(get, set)]
     pub grid_size: (usize, usize),
+
+    /// Weight of the novelty bonus in `PlanningEngine::evaluate_actions`
+    /// (0.0 = pure exploitation, the default; set after construction)
+    #[pyo3(get, set)]
+    pub curiosity_weight: f32,
 }
 
 #[pymethods]
@@ -157,8 +292,52 @@ impl WorldModelConfig {
             prediction_steps,
             learning_rate,
             ebbinghaus_decay_rate,
+            retention_model: "exponential".to_string(),
             grid_size,
+            curiosity_weight: 0.0,
+        }
+    }
+
+    /// Reject configurations that would silently produce garbage downstream
+    pub fn validate(&self) -> PyResult<()> {
+        let invalid = |msg: String| Err(pyo3::exceptions::PyValueError::new_err(msg));
+        if self.latent_dim == 0 {
+            return invalid("WorldModelConfig.latent_dim must be > 0".to_string());
+        }
+        if self.context_window == 0 {
+            return invalid("WorldModelConfig.context_window must be > 0".to_string());
+        }
+        if self.prediction_steps == 0 {
+            return invalid("WorldModelConfig.prediction_steps must be > 0".to_string());
+        }
+        if self.learning_rate.is_nan() || self.learning_rate <= 0.0 {
+            return invalid(format!(
+                "WorldModelConfig.learning_rate must be > 0.0, got {}",
+                self.learning_rate
+            ));
         }
+        if self.ebbinghaus_decay_rate.is_nan() || self.ebbinghaus_decay_rate < 0.0 {
+            return invalid(format!(
+                "WorldModelConfig.ebbinghaus_decay_rate must be >= 0.0, got {}",
+                self.ebbinghaus_decay_rate
+            ));
+        }
+        if !matches!(
+            self.retention_model.as_str(),
+            "exponential" | "power_law" | "linear"
+        ) {
+            return invalid(format!(
+                "WorldModelConfig.retention_model must be exponential, power_law or linear, got '{}'",
+                self.retention_model
+            ));
+        }
+        if self.curiosity_weight.is_nan() || self.curiosity_weight < 0.0 {
+            return invalid(format!(
+                "WorldModelConfig.curiosity_weight must be >= 0.0, got {}",
+                self.curiosity_weight
+            ));
+        }
+        Ok(())
     }
 
     pub fn __repr__(&self) -> String {
@@ -202,6 +381,25 @@ impl Prediction {
         }
     }
 
+    /// Recalibrate `confidence` from the rollout itself: the mean cosine
+    /// similarity between consecutive future states, clamped to [0, 1].
+    /// Smooth, self-consistent rollouts score near 1; erratic or collapsing
+    /// ones (e.g. states decaying to zero) score low. Rollouts with fewer than
+    /// two states fall back to the horizon discount `1 / (1 + 0.1 * steps)`.
+    pub fn recompute_confidence(&mut self) {
+        if self.future_states.len() < 2 {
+            self.confidence = 1.0 / (1.0 + 0.1 * self.future_states.len() as f32);
+            return;
+        }
+
+        let total: f32 = self
+            .future_states
+            .windows(2)
+            .map(|pair| pair[0].similarity(&pair[1]).clamp(0.0, 1.0))
+            .sum();
+        self.confidence = total / (self.future_states.len() - 1) as f32;
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "Prediction(steps={}, confidence={:.2}, actions={:?})",
@@ -277,3 +475,156 @@ impl PromoterConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similarity_reports_dimension_mismatch() {
+        let a = LatentState::new(vec![1.0, 0.0, 0.0], "a".to_string(), 0);
+        let b = LatentState::new(vec![1.0, 0.0], "b".to_string(), 0);
+
+        assert_eq!(
+            a.checked_similarity(&b),
+            Err(LatentError::DimensionMismatch {
+                context: "similarity".to_string(),
+                expected: 3,
+                actual: 2,
+            })
+        );
+        // The lenient form still degrades to 0.0
+        assert_eq!(a.similarity(&b), 0.0);
+        assert_eq!(a.checked_similarity(&a), Ok(1.0));
+    }
+
+    #[test]
+    fn normalize_scales_to_unit_length() {
+        let mut state = LatentState::new(vec![3.0, 4.0], "a".to_string(), 0);
+        assert!(!state.is_normalized(1e-3));
+        state.normalize();
+        assert!(state.is_normalized(1e-6));
+        assert!((state.vector[0] - 0.6).abs() < 1e-6);
+        assert!((state.vector[1] - 0.8).abs() < 1e-6);
+
+        let mut zero = LatentState::new(vec![0.0, 0.0], "z".to_string(), 0);
+        zero.normalize();
+        assert_eq!(zero.vector, vec![0.0, 0.0]);
+        assert!(zero.is_normalized(1e-6));
+    }
+
+    #[test]
+    fn nan_vectors_are_sanitized() {
+        let mut raw = vec![f32::NAN, 3.0, f32::INFINITY, 4.0, f32::NEG_INFINITY];
+        assert_eq!(sanitize_vector("test", &mut raw), 3);
+        assert_eq!(raw, vec![0.0, 3.0, 0.0, 4.0, 0.0]);
+
+        let mut state =
+            LatentState::new(vec![f32::NAN, 3.0, f32::INFINITY, 4.0], "a".to_string(), 0);
+        assert!(state.vector.iter().all(|v| v.is_finite()));
+        state.normalize();
+        assert!(state.is_normalized(1e-6));
+
+        // A state mutated after construction still never yields a NaN score
+        let mut poisoned = LatentState::new(vec![0.0; 4], "b".to_string(), 0);
+        poisoned.vector = vec![f32::NAN; 4];
+        assert_eq!(state.similarity(&poisoned), 0.0);
+        assert!((state.similarity(&state) - 1.0).abs() < 1e-6);
+
+        let mut empty = LatentState::new(vec![], "e".to_string(), 0);
+        empty.normalize();
+        assert!(empty.vector.is_empty());
+        assert_eq!(empty.similarity(&empty), 0.0);
+
+        let mut huge = LatentState::new(vec![f32::MAX, f32::MAX], "h".to_string(), 0);
+        huge.normalize();
+        assert!(huge.vector.iter().all(|v| v.is_finite()));
+        assert!(huge.similarity(&huge).is_finite());
+    }
+
+    #[test]
+    fn new_normalized_feeds_compute_surprise() {
+        let mut real = LatentState::new_normalized(vec![2.0, 0.0], "a".to_string(), 1);
+        let predicted = LatentState::new_normalized(vec![0.0, -5.0], "a".to_string(), 1);
+        assert!(real.is_normalized(1e-6) && predicted.is_normalized(1e-6));
+
+        // Orthogonal vectors: cosine 0 maps to surprise 0.5
+        real.compute_surprise(&predicted);
+        assert!((real.surprise_score - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(WorldModelConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn collapsing_rollout_is_less_confident() {
+        let state = |v: Vec<f32>, step: u32| LatentState::new(v, "a".to_string(), step);
+        let mut stable = Prediction::new(
+            (0..4u32)
+                .map(|i| state(vec![1.0, 0.1 * i as f32, 0.0], i))
+                .collect(),
+            0.0,
+            vec![],
+        );
+        let mut collapsing = Prediction::new(
+            vec![
+                state(vec![1.0, 0.0, 0.0], 0),
+                state(vec![0.0, 1.0, 0.0], 1),
+                state(vec![0.0, 0.0, 0.0], 2),
+                state(vec![0.0, 0.0, 0.0], 3),
+            ],
+            0.0,
+            vec![],
+        );
+
+        stable.recompute_confidence();
+        collapsing.recompute_confidence();
+        assert!(stable.confidence > 0.95);
+        assert!(collapsing.confidence < 0.1);
+        assert!(collapsing.confidence < stable.confidence);
+    }
+
+    #[test]
+    fn invalid_fields_are_rejected() {
+        let defaults = WorldModelConfig::default;
+        let invalid = [
+            WorldModelConfig {
+                latent_dim: 0,
+                ..defaults()
+            },
+            WorldModelConfig {
+                context_window: 0,
+                ..defaults()
+            },
+            WorldModelConfig {
+                prediction_steps: 0,
+                ..defaults()
+            },
+            WorldModelConfig {
+                learning_rate: 0.0,
+                ..defaults()
+            },
+            WorldModelConfig {
+                learning_rate: f32::NAN,
+                ..defaults()
+            },
+            WorldModelConfig {
+                ebbinghaus_decay_rate: -0.1,
+                ..defaults()
+            },
+            WorldModelConfig {
+                retention_model: "hyperbolic".to_string(),
+                ..defaults()
+            },
+            WorldModelConfig {
+                curiosity_weight: -0.5,
+                ..defaults()
+            },
+        ];
+        for cfg in &invalid {
+            assert!(cfg.validate().is_err());
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use super::{ActionScore, AutoregressivePredictor, LatentEncoder, LatentState, WorldModelConfig};
+use super::{
+    ActionScore, AutoregressivePredictor, LatentEncoder, LatentState, Prediction, WorldModelConfig,
+};
 use pyo3::prelude::*;
 use tracing::info;
 
@@ -14,13 +16,14 @@ pub struct PlanningEngine {
 impl PlanningEngine {
     #[new]
     #[pyo3(signature = (config = None))]
-    pub fn new(config: Option<WorldModelConfig>) -> Self {
+    pub fn new(config: Option<WorldModelConfig>) -> PyResult<Self> {
         let cfg = config.clone().unwrap_or_default();
-        PlanningEngine {
+        cfg.validate()?;
+        Ok(PlanningEngine {
             config: cfg.clone(),
-            encoder: LatentEncoder::new(config.clone()).unwrap(),
-            predictor: AutoregressivePredictor::new(config).unwrap(),
-        }
+            encoder: LatentEncoder::new(config.clone())?,
+            predictor: AutoregressivePredictor::new(config)?,
+        })
     }
 
     /// Plan the best action given current state and goal
@@ -30,96 +33,397 @@ impl PlanningEngine {
         candidate_actions: Vec<String>,
         goal: String,
     ) -> ActionScore {
-        // Encode semantic goal target
-        let goal_encoding = self.encoder.encode_action(goal.clone());
-        let goal_state = LatentState::new(goal_encoding, "goal".to_string(), 0);
+        info!("[Planner] Planning for goal: '{}'", goal);
 
-        let mut best_action = String::new();
-        let mut best_score = f32::NEG_INFINITY;
-        let mut best_outcome = String::new();
+        // Encode semantic goal target and natural language actions
+        let goal_state = self.encode_goal(goal);
+        let actions = self.encode_actions(candidate_actions);
 
-        info!("[Planner] Planning for goal: '{}'", goal);
+        let best = best_action(
+            &self.predictor,
+            self.config.prediction_steps,
+            current_state,
+            &[(goal_state, 1.0)],
+            &actions,
+        );
+        info!("Best action: {} (score: {:.3})", best.action, best.score);
+        best
+    }
 
-        for action in &candidate_actions {
-            // Encode natural language action
-            let action_encoding = self.encoder.encode_action(action.clone());
-
-            // Rollout future (State-of-the-art prediction)
-            let prediction = self.predictor.rollout(
-                current_state,
-                action_encoding,
-                self.config.prediction_steps,
-            );
-
-            // Score: Semantic similarity of final state to goal
-            // Both state and goal are language-conditioned
-            let final_state = prediction.future_states.last();
-            let score = match final_state {
-                Some(state) => state.similarity(&goal_state) * prediction.confidence,
-                None => 0.0,
-            };
+    /// Plan against several weighted goals at once. An action scores the
+    /// weighted sum of its rollout endpoint's similarity to each goal, so
+    /// competing objectives can be traded off (negative weights penalize).
+    pub fn plan_multi(
+        &self,
+        current_state: &LatentState,
+        candidate_actions: Vec<String>,
+        goals: Vec<(String, f32)>,
+    ) -> ActionScore {
+        info!("[Planner] Planning for {} weighted goals", goals.len());
 
-            if score > best_score {
-                best_score = score;
-                best_action = action.clone();
-                best_outcome = format!(
-                    "Predicted {} steps, goal align: {:.3}",
-                    prediction.future_states.len(),
-                    score
-                );
-            }
-        }
+        let goal_states: Vec<(LatentState, f32)> = goals
+            .into_iter()
+            .map(|(goal, weight)| (self.encode_goal(goal), weight))
+            .collect();
+        let actions = self.encode_actions(candidate_actions);
 
-        info!("Best action: {} (score: {:.3})", best_action, best_score);
+        let best = best_action(
+            &self.predictor,
+            self.config.prediction_steps,
+            current_state,
+            &goal_states,
+            &actions,
+        );
+        info!("Best action: {} (score: {:.3})", best.action, best.score);
+        best
+    }
 
-        ActionScore {
-            action: best_action,
-            score: best_score,
-            predicted_outcome: best_outcome,
-        }
+    /// Plan the best action for every state in `states` at once.
+    ///
+    /// The goal and each action are encoded a single time, and every action is
+    /// rolled out for all states in one batched `predict_batch` call. Returns
+    /// one `ActionScore` per input state, in order.
+    pub fn plan_batch(
+        &self,
+        states: Vec<LatentState>,
+        candidate_actions: Vec<String>,
+        goal: String,
+    ) -> Vec<ActionScore> {
+        info!(
+            "[Planner] Batch planning {} states for goal: '{}'",
+            states.len(),
+            goal
+        );
+
+        let goal_state = self.encode_goal(goal);
+        let actions = self.encode_actions(candidate_actions);
+
+        best_actions_batch(
+            &self.predictor,
+            self.config.prediction_steps,
+            states,
+            &[(goal_state, 1.0)],
+            &actions,
+        )
     }
 
-    /// Evaluate all actions and return ranked scores
+    /// Evaluate all actions and return ranked scores.
+    ///
+    /// With a positive `config.curiosity_weight`, each score also earns a
+    /// bonus proportional to the predicted surprise of the rollout endpoint,
+    /// so informative actions win when the goal signal is weak.
     pub fn evaluate_actions(
         &self,
         current_state: &LatentState,
         candidate_actions: Vec<String>,
         goal: String,
     ) -> Vec<ActionScore> {
+        let goal_state = self.encode_goal(goal);
+        let actions = self.encode_actions(candidate_actions);
+
+        rank_actions(
+            &self.predictor,
+            self.config.prediction_steps,
+            current_state,
+            &[(goal_state, 1.0)],
+            &actions,
+            self.config.curiosity_weight,
+        )
+    }
+}
+
+impl PlanningEngine {
+    fn encode_goal(&self, goal: String) -> LatentState {
         let goal_encoding = self.encoder.encode_action(goal);
-        let goal_state = LatentState::new(goal_encoding, "goal".to_string(), 0);
+        LatentState::new(goal_encoding, "goal".to_string(), 0)
+    }
 
-        let mut scores: Vec<ActionScore> = candidate_actions
-            .iter()
+    fn encode_actions(&self, candidate_actions: Vec<String>) -> Vec<(String, Vec<f32>)> {
+        candidate_actions
+            .into_iter()
             .map(|action| {
-                let action_encoding = self.encoder.encode_action(action.clone());
-                let prediction = self.predictor.rollout(
-                    current_state,
-                    action_encoding,
-                    self.config.prediction_steps,
-                );
-
-                let score = prediction
-                    .future_states
-                    .last()
-                    .map(|s| s.similarity(&goal_state) * prediction.confidence)
-                    .unwrap_or(0.0);
+                let encoding = self.encoder.encode_action(action.clone());
+                (action, encoding)
+            })
+            .collect()
+    }
+}
+
+/// Score: weighted sum of the semantic similarities of the rollout's final
+/// state to each goal, scaled by the rollout's confidence
+fn goal_alignment(prediction: &Prediction, goals: &[(LatentState, f32)]) -> f32 {
+    prediction
+        .future_states
+        .last()
+        .map(|s| {
+            let alignment: f32 = goals
+                .iter()
+                .map(|(goal, weight)| s.similarity(goal) * weight)
+                .sum();
+            alignment * prediction.confidence
+        })
+        .unwrap_or(0.0)
+}
+
+fn describe(prediction: &Prediction, score: f32) -> String {
+    format!(
+        "Predicted {} steps, goal align: {:.3}",
+        prediction.future_states.len(),
+        score
+    )
+}
 
+/// Best pre-encoded action for a single start state
+fn best_action(
+    predictor: &AutoregressivePredictor,
+    steps: usize,
+    current_state: &LatentState,
+    goals: &[(LatentState, f32)],
+    actions: &[(String, Vec<f32>)],
+) -> ActionScore {
+    let mut best = ActionScore {
+        action: String::new(),
+        score: f32::NEG_INFINITY,
+        predicted_outcome: String::new(),
+    };
+
+    for (action, encoding) in actions {
+        // Rollout future (State-of-the-art prediction)
+        let prediction = predictor.rollout(current_state, encoding.clone(), steps);
+        let score = goal_alignment(&prediction, goals);
+        if score > best.score {
+            best = ActionScore {
+                action: action.clone(),
+                score,
+                predicted_outcome: describe(&prediction, score),
+            };
+        }
+    }
+    best
+}
+
+/// Surprise the rollout endpoint would register against the start state:
+/// 0.0 = stays put, 1.0 = lands on the opposite side of latent space.
+/// `evolution::CuriosityModule` only proposes challenge tasks from learned
+/// skill names and has no latent-space novelty measure, so the bonus reuses
+/// `LatentState::compute_surprise` instead.
+fn predicted_surprise(prediction: &Prediction, current_state: &LatentState) -> f32 {
+    let Some(last) = prediction.future_states.last() else {
+        return 0.0;
+    };
+    let mut endpoint =
+        LatentState::new_normalized(last.vector.clone(), last.agent_id.clone(), last.step);
+    let prior = LatentState::new_normalized(
+        current_state.vector.clone(),
+        current_state.agent_id.clone(),
+        current_state.step,
+    );
+    endpoint.compute_surprise(&prior);
+    endpoint.surprise_score
+}
+
+/// All pre-encoded actions scored by goal alignment plus
+/// `curiosity_weight` × predicted surprise, best first
+fn rank_actions(
+    predictor: &AutoregressivePredictor,
+    steps: usize,
+    current_state: &LatentState,
+    goals: &[(LatentState, f32)],
+    actions: &[(String, Vec<f32>)],
+    curiosity_weight: f32,
+) -> Vec<ActionScore> {
+    let mut scores: Vec<ActionScore> = actions
+        .iter()
+        .map(|(action, encoding)| {
+            let prediction = predictor.rollout(current_state, encoding.clone(), steps);
+            let alignment = goal_alignment(&prediction, goals);
+            if curiosity_weight > 0.0 {
+                let surprise = predicted_surprise(&prediction, current_state);
                 ActionScore {
                     action: action.clone(),
-                    score,
-                    predicted_outcome: format!("P(align)={:.3}", score),
+                    score: alignment + curiosity_weight * surprise,
+                    predicted_outcome: format!(
+                        "P(align)={:.3}, surprise={:.3}",
+                        alignment, surprise
+                    ),
                 }
-            })
+            } else {
+                ActionScore {
+                    action: action.clone(),
+                    score: alignment,
+                    predicted_outcome: format!("P(align)={:.3}", alignment),
+                }
+            }
+        })
+        .collect();
+
+    // Sort by score descending
+    scores.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    scores
+}
+
+/// Best pre-encoded action for each of `states`, rolling all states per action
+/// in one batch
+fn best_actions_batch(
+    predictor: &AutoregressivePredictor,
+    steps: usize,
+    states: Vec<LatentState>,
+    goals: &[(LatentState, f32)],
+    actions: &[(String, Vec<f32>)],
+) -> Vec<ActionScore> {
+    let mut best: Vec<ActionScore> = (0..states.len())
+        .map(|_| ActionScore {
+            action: String::new(),
+            score: f32::NEG_INFINITY,
+            predicted_outcome: String::new(),
+        })
+        .collect();
+
+    for (action, encoding) in actions {
+        let predictions = predictor.predict_batch(states.clone(), encoding.clone(), steps);
+        for (slot, prediction) in best.iter_mut().zip(&predictions) {
+            let score = goal_alignment(prediction, goals);
+            if score > slot.score {
+                *slot = ActionScore {
+                    action: action.clone(),
+                    score,
+                    predicted_outcome: describe(prediction, score),
+                };
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(v: Vec<f32>) -> Vec<f32> {
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        v.into_iter().map(|x| x / norm).collect()
+    }
+
+    #[test]
+    fn test_batch_matches_per_state_plan() {
+        let config = WorldModelConfig::default();
+        let dim = config.latent_dim;
+        let steps = config.prediction_steps;
+        let predictor = AutoregressivePredictor::new(Some(config)).unwrap();
+
+        let basis = |i: usize, j: usize| {
+            unit(
+                (0..dim)
+                    .map(|k| if k % i == j { 1.0 } else { 0.1 })
+                    .collect(),
+            )
+        };
+        let goal = LatentState::new(basis(3, 0), "goal".to_string(), 0);
+        let actions: Vec<(String, Vec<f32>)> = (0..4)
+            .map(|j| (format!("action_{}", j), basis(4, j)))
+            .collect();
+        let states: Vec<LatentState> = (0..5)
+            .map(|j| LatentState::new(basis(5, j), format!("agent_{}", j), 0))
             .collect();
 
-        // Sort by score descending
-        scores.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
+        let goals = [(goal, 1.0)];
+        let batch = best_actions_batch(&predictor, steps, states.clone(), &goals, &actions);
+        assert_eq!(batch.len(), states.len());
+        for (state, batched) in states.iter().zip(&batch) {
+            let single = best_action(&predictor, steps, state, &goals, &actions);
+            assert_eq!(batched.action, single.action);
+            assert!((batched.score - single.score).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_goal_weights_change_winning_action() {
+        let config = WorldModelConfig::default();
+        let dim = config.latent_dim;
+        let predictor = AutoregressivePredictor::new(Some(config)).unwrap();
+        let start = LatentState::new_normalized(vec![1.0; dim], "agent".to_string(), 0);
+        let actions: Vec<(String, Vec<f32>)> = vec![
+            (
+                "find_price".to_string(),
+                unit((0..dim).map(|k| (k % 2) as f32).collect()),
+            ),
+            (
+                "save_money".to_string(),
+                unit((0..dim).map(|k| (k % 3) as f32).collect()),
+            ),
+        ];
+
+        // One step keeps rollout confidence equal across actions, so only the
+        // goal weights decide. Each goal is exactly one action's endpoint.
+        let endpoint = |encoding: &Vec<f32>| {
+            predictor.rollout(&start, encoding.clone(), 1).future_states[0].clone()
+        };
+        let price_goal = endpoint(&actions[0].1);
+        let budget_goal = endpoint(&actions[1].1);
+
+        let pick = |price_weight: f32, budget_weight: f32| {
+            let goals = [
+                (price_goal.clone(), price_weight),
+                (budget_goal.clone(), budget_weight),
+            ];
+            best_action(&predictor, 1, &start, &goals, &actions).action
+        };
+        assert_eq!(pick(0.7, 0.3), "find_price");
+        assert_eq!(pick(0.3, 0.7), "save_money");
+    }
+
+    #[test]
+    fn test_curiosity_favors_surprising_outcomes() {
+        let config = WorldModelConfig::default();
+        let dim = config.latent_dim;
+        let predictor = AutoregressivePredictor::new(Some(config)).unwrap();
+        let start = LatentState::new_normalized(vec![1.0; dim], "agent".to_string(), 0);
+        let mut actions: Vec<(String, Vec<f32>)> = vec![
+            (
+                "a".to_string(),
+                unit((0..dim).map(|k| (k % 2) as f32).collect()),
+            ),
+            (
+                "b".to_string(),
+                unit((0..dim).map(|k| (k % 3) as f32 - 1.0).collect()),
+            ),
+        ];
+        let rollout = |encoding: &Vec<f32>| predictor.rollout(&start, encoding.clone(), 1);
+
+        // Name the actions by how surprising their outcomes are, then aim the
+        // goal squarely at the boring one
+        actions.sort_by(|x, y| {
+            let sx = predicted_surprise(&rollout(&x.1), &start);
+            let sy = predicted_surprise(&rollout(&y.1), &start);
+            sx.partial_cmp(&sy).unwrap()
         });
+        actions[0].0 = "boring".to_string();
+        actions[1].0 = "novel".to_string();
+        let boring = rollout(&actions[0].1);
+        let novel = rollout(&actions[1].1);
+        let goals = [(boring.future_states[0].clone(), 1.0)];
+
+        let exploit = rank_actions(&predictor, 1, &start, &goals, &actions, 0.0);
+        assert_eq!(exploit[0].action, "boring");
 
-        scores
+        // Large enough that the surprise gap outweighs the alignment gap
+        let align_gap = goal_alignment(&boring, &goals) - goal_alignment(&novel, &goals);
+        let surprise_gap = predicted_surprise(&novel, &start) - predicted_surprise(&boring, &start);
+        assert!(surprise_gap > 0.0);
+        let explore = rank_actions(
+            &predictor,
+            1,
+            &start,
+            &goals,
+            &actions,
+            2.0 * align_gap / surprise_gap,
+        );
+        assert_eq!(explore[0].action, "novel");
+        assert!(explore[0].predicted_outcome.contains("surprise="));
     }
 }